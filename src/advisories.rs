@@ -0,0 +1,33 @@
+use crate::error::SearchError;
+use crate::ApiClient;
+use serde_json::{json, Value};
+
+/// Looks up known vulnerabilities for a package in the OSV database.
+///
+/// # Arguments
+///
+/// * `ecosystem` - The OSV ecosystem name, e.g. `crates.io`, `npm`, or `PyPI`.
+/// * `name` - The package name.
+/// * `version` - Restricts results to advisories affecting this version (optional).
+///
+/// # Returns
+///
+/// Returns the matching advisories as a `serde_json::Value` array or an error.
+pub async fn lookup_advisories(
+    ecosystem: &str,
+    name: &str,
+    version: Option<&str>,
+) -> Result<Value, SearchError> {
+    let mut body = json!({
+        "package": { "name": name, "ecosystem": ecosystem },
+    });
+    if let Some(version) = version {
+        body["version"] = json!(version);
+    }
+
+    let response = ApiClient::new("https://api.osv.dev/v1/", None)
+        .post("query", &body)
+        .await?;
+
+    Ok(response.get("vulns").cloned().unwrap_or_else(|| json!([])))
+}
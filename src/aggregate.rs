@@ -0,0 +1,337 @@
+//! Searching several registries at once.
+
+use crate::cancel::with_deadline;
+use crate::error::SearchError;
+use crate::matching::match_score;
+use crate::metrics::{metrics, SearchMetrics};
+use crate::registry::{all_registries, Registry, RegistryError, SearchHit, SearchQuery};
+use futures::future::join_all;
+use std::time::Duration;
+use web_time::Instant;
+
+/// How [`rank_hits`] orders hits merged from several registries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RankingStrategy {
+    /// Keep the order the registries returned their hits in.
+    Unranked,
+    /// Order by how closely the name and description match the query.
+    Relevance,
+    /// Order by download count, most downloaded first.
+    Downloads,
+    /// Order by a score combining relevance and downloads.
+    #[default]
+    Combined,
+}
+
+/// The merged outcome of searching several registries.
+///
+/// A registry that fails does not fail the whole search: its error is
+/// collected in `errors` and the hits of every other registry are kept.
+#[derive(Debug, Default)]
+pub struct AggregateResults {
+    /// The hits of every registry that answered, in the order the
+    /// registries were given.
+    pub hits: Vec<SearchHit>,
+    /// The errors of the registries that failed.
+    pub errors: Vec<RegistryError>,
+}
+
+impl AggregateResults {
+    /// Deduplicates and orders the hits with [`rank_hits`].
+    pub fn ranked(mut self, query: &str, strategy: RankingStrategy) -> Self {
+        self.hits = rank_hits(self.hits, query, strategy);
+        self
+    }
+}
+
+/// Searches the given registries concurrently and merges their hits.
+///
+/// # Arguments
+///
+/// * `query` - The search query.
+/// * `sources` - The [`Registry::name`]s of the registries to search, e.g.
+///   `["crates", "npm"]`. An empty slice searches every registry returned by
+///   [`all_registries`].
+/// * `limit` - The maximum number of hits to take from each registry.
+///
+/// # Returns
+///
+/// Returns the merged hits, each tagged with the registry it came from,
+/// along with the error of every registry that failed or is unknown.
+pub async fn search_all(query: &str, sources: &[&str], limit: usize) -> AggregateResults {
+    search_sources(query, sources, limit, None).await
+}
+
+/// Searches the given registries concurrently as [`search_all`] does, but
+/// stops waiting for them once `deadline` has passed.
+///
+/// The hits of the registries that answered in time are kept; each of the
+/// others is cancelled and reported with [`SearchError::DeadlineExceeded`].
+///
+/// # Arguments
+///
+/// * `query` - The search query.
+/// * `sources` - The [`Registry::name`]s of the registries to search; an
+///   empty slice searches every registry.
+/// * `limit` - The maximum number of hits to take from each registry.
+/// * `deadline` - How long to wait for the registries.
+///
+/// # Returns
+///
+/// Returns the merged hits of the registries that answered in time, along
+/// with the error of every registry that failed, is unknown, or was too slow.
+pub async fn search_all_with_deadline(
+    query: &str,
+    sources: &[&str],
+    limit: usize,
+    deadline: Duration,
+) -> AggregateResults {
+    search_sources(query, sources, limit, Some(deadline)).await
+}
+
+async fn search_sources(
+    query: &str,
+    sources: &[&str],
+    limit: usize,
+    deadline: Option<Duration>,
+) -> AggregateResults {
+    let mut registries = all_registries();
+    let mut errors = Vec::new();
+
+    if !sources.is_empty() {
+        for source in sources {
+            if !registries.iter().any(|registry| registry.name() == *source) {
+                errors.push(RegistryError::new(source, SearchError::NotFound));
+            }
+        }
+        registries.retain(|registry| sources.contains(&registry.name()));
+    }
+
+    let query = SearchQuery::new(query).limit(limit);
+    let mut results = search_each(&registries, &query, deadline).await;
+    errors.append(&mut results.errors);
+    results.errors = errors;
+    results
+}
+
+/// Searches every registry in `registries` concurrently with the same query.
+///
+/// This is the building block of [`search_all`] for callers that configure
+/// their own registries, e.g. ones that need credentials.
+pub async fn search_registries(
+    registries: &[Box<dyn Registry>],
+    query: &SearchQuery,
+) -> AggregateResults {
+    search_each(registries, query, None).await
+}
+
+async fn search_each(
+    registries: &[Box<dyn Registry>],
+    query: &SearchQuery,
+    deadline: Option<Duration>,
+) -> AggregateResults {
+    let metrics = metrics();
+    let searches = registries.iter().map(|registry| {
+        let metrics = metrics.clone();
+        async move {
+            let started = Instant::now();
+            let result = match deadline {
+                Some(deadline) => with_deadline(
+                    async { registry.search(query).await.map_err(|error| error.error) },
+                    deadline,
+                )
+                .await
+                .map_err(|error| RegistryError::new(registry.name(), error)),
+                None => registry.search(query).await,
+            };
+            if let Some(metrics) = metrics {
+                metrics.record_search(&SearchMetrics {
+                    registry: registry.name(),
+                    hits: result.as_ref().map_or(0, Vec::len),
+                    latency: started.elapsed(),
+                    error: result.as_ref().err(),
+                });
+            }
+            (registry.name(), result)
+        }
+    });
+
+    let mut results = AggregateResults::default();
+    for (name, result) in join_all(searches).await {
+        match result {
+            Ok(hits) => results.hits.extend(hits.into_iter().map(|mut hit| {
+                hit.source = name.to_string();
+                hit
+            })),
+            Err(error) => results.errors.push(error),
+        }
+    }
+    results
+}
+
+/// Merges hits that refer to the same project and orders the rest.
+///
+/// Two hits are the same project when they point at the same source
+/// repository and come from different registries, e.g. a crate and an npm
+/// package built from one GitHub repo. The first of them is kept and its
+/// missing fields are filled in from the others. Hits from one registry are
+/// only merged when their names match too, so the packages of a monorepo,
+/// such as `serde` and `serde_derive`, stay apart. Ties keep the order the
+/// hits were given in.
+///
+/// # Arguments
+///
+/// * `hits` - The hits to rank, usually [`AggregateResults::hits`].
+/// * `query` - The query the hits were found with.
+/// * `strategy` - How to order the hits.
+///
+/// # Returns
+///
+/// Returns the deduplicated hits, best first.
+pub fn rank_hits(hits: Vec<SearchHit>, query: &str, strategy: RankingStrategy) -> Vec<SearchHit> {
+    let mut merged: Vec<SearchHit> = Vec::with_capacity(hits.len());
+    // The (source, name) pairs already merged into each hit.
+    let mut members: Vec<Vec<(String, String)>> = Vec::with_capacity(hits.len());
+    for hit in hits {
+        let repository = hit.repository.as_deref().map(normalize_repository);
+        let duplicate = repository.and_then(|repository| {
+            merged.iter().zip(&members).position(|(other, members)| {
+                other
+                    .repository
+                    .as_deref()
+                    .map(normalize_repository)
+                    .as_ref()
+                    == Some(&repository)
+                    && members
+                        .iter()
+                        .all(|(source, name)| *source != hit.source || *name == hit.name)
+            })
+        });
+        match duplicate {
+            Some(index) => {
+                members[index].push((hit.source.clone(), hit.name.clone()));
+                merge_hit(&mut merged[index], hit);
+            }
+            None => {
+                members.push(vec![(hit.source.clone(), hit.name.clone())]);
+                merged.push(hit);
+            }
+        }
+    }
+
+    match strategy {
+        RankingStrategy::Unranked => {}
+        RankingStrategy::Relevance => merged.sort_by(|a, b| {
+            relevance(b, query)
+                .total_cmp(&relevance(a, query))
+                .then_with(|| b.downloads.cmp(&a.downloads))
+        }),
+        RankingStrategy::Downloads => merged.sort_by(|a, b| b.downloads.cmp(&a.downloads)),
+        RankingStrategy::Combined => {
+            merged.sort_by(|a, b| combined(b, query).total_cmp(&combined(a, query)))
+        }
+    }
+    merged
+}
+
+/// Scores how well a hit matches the query, from 0 (no match) to 10 (exact
+/// name).
+fn relevance(hit: &SearchHit, query: &str) -> f64 {
+    let description = hit.description.as_deref().unwrap_or("");
+    match match_score(query, &hit.name, description) {
+        Some(score) => 10.0 - 2.0 * f64::from(score),
+        None => 0.0,
+    }
+}
+
+/// Adds the order of magnitude of the downloads to the relevance, so a
+/// popular prefix match can outrank an obscure exact match.
+fn combined(hit: &SearchHit, query: &str) -> f64 {
+    let downloads = hit.downloads.unwrap_or(0) as f64;
+    relevance(hit, query) + (downloads + 1.0).log10()
+}
+
+/// Reduces a repository URL to `host/owner/name` so that `git+https://`,
+/// `.git` and trailing-slash variants compare equal.
+pub(crate) fn normalize_repository(url: &str) -> String {
+    let url = url.trim().trim_start_matches("git+");
+    let url = url.split_once("://").map_or(url, |(_, rest)| rest);
+    let url = url.trim_start_matches("git@").trim_start_matches("www.");
+    url.replacen(':', "/", 1)
+        .trim_end_matches('/')
+        .trim_end_matches(".git")
+        .to_lowercase()
+}
+
+/// Fills the fields `hit` is missing from `other`.
+fn merge_hit(hit: &mut SearchHit, other: SearchHit) {
+    hit.version = hit.version.take().or(other.version);
+    hit.description = hit.description.take().or(other.description);
+    hit.homepage = hit.homepage.take().or(other.homepage);
+    hit.license = hit.license.take().or(other.license);
+    hit.updated = hit.updated.take().max(other.updated);
+    hit.downloads = hit.downloads.max(other.downloads);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hit(source: &str, name: &str, repository: &str) -> SearchHit {
+        SearchHit {
+            name: name.to_string(),
+            repository: Some(repository.to_string()),
+            source: source.to_string(),
+            ..SearchHit::default()
+        }
+    }
+
+    #[test]
+    fn merges_one_project_across_registries() {
+        let mut npm = hit(
+            "npm",
+            "serde-wasm",
+            "git+https://github.com/serde-rs/serde.git",
+        );
+        npm.downloads = Some(10);
+        npm.license = Some("MIT".to_string());
+        let hits = vec![
+            hit("crates", "serde", "https://github.com/serde-rs/serde"),
+            npm,
+        ];
+        let ranked = rank_hits(hits, "serde", RankingStrategy::Unranked);
+        assert_eq!(ranked.len(), 1);
+        assert_eq!(ranked[0].source, "crates");
+        assert_eq!(ranked[0].downloads, Some(10));
+        assert_eq!(ranked[0].license.as_deref(), Some("MIT"));
+    }
+
+    #[test]
+    fn keeps_monorepo_siblings_from_one_registry_apart() {
+        let hits = vec![
+            hit("crates", "serde", "https://github.com/serde-rs/serde"),
+            hit(
+                "crates",
+                "serde_derive",
+                "https://github.com/serde-rs/serde",
+            ),
+            hit("npm", "@babel/core", "https://github.com/babel/babel"),
+            hit("npm", "@babel/parser", "https://github.com/babel/babel.git"),
+        ];
+        let ranked = rank_hits(hits, "serde", RankingStrategy::Unranked);
+        let names: Vec<_> = ranked.iter().map(|hit| hit.name.as_str()).collect();
+        assert_eq!(
+            names,
+            ["serde", "serde_derive", "@babel/core", "@babel/parser"]
+        );
+    }
+
+    #[test]
+    fn merges_the_same_package_listed_twice() {
+        let hits = vec![
+            hit("crates", "serde", "https://github.com/serde-rs/serde"),
+            hit("crates", "serde", "https://github.com/serde-rs/serde/"),
+        ];
+        assert_eq!(rank_hits(hits, "serde", RankingStrategy::Unranked).len(), 1);
+    }
+}
@@ -0,0 +1,230 @@
+//! Alfred Script Filter output.
+//!
+//! Alfred workflows run a script and read the items to show from its
+//! output, as described at
+//! <https://www.alfredapp.com/help/workflows/inputs/script-filter/json/>.
+
+use crate::error::SearchError;
+use crate::registry::SearchHit;
+use serde::Serialize;
+
+/// The JSON document a Script Filter prints.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ScriptFilter {
+    pub items: Vec<Item>,
+}
+
+impl ScriptFilter {
+    /// Creates a Script Filter with one item per hit.
+    pub fn from_hits(hits: &[SearchHit]) -> Self {
+        Self {
+            items: hits.iter().map(Item::from).collect(),
+        }
+    }
+
+    /// Creates a Script Filter with a single item describing `error`, which
+    /// cannot be actioned.
+    pub fn error(error: &SearchError) -> Self {
+        Self {
+            items: vec![Item {
+                title: "Error".to_string(),
+                subtitle: error.to_string(),
+                valid: false,
+                ..Default::default()
+            }],
+        }
+    }
+}
+
+/// A row in Alfred's result list.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Item {
+    /// Lets Alfred learn which items are picked most often.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub uid: Option<String>,
+    pub title: String,
+    pub subtitle: String,
+    /// Passed to the next action of the workflow when the item is picked.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub arg: Option<String>,
+    /// Whether the item can be picked.
+    pub valid: bool,
+    /// The URL shown when the item is previewed with Shift or ⌘Y.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quicklookurl: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text: Option<Text>,
+    #[serde(skip_serializing_if = "Mods::is_empty")]
+    pub mods: Mods,
+}
+
+impl Default for Item {
+    fn default() -> Self {
+        Self {
+            uid: None,
+            title: String::new(),
+            subtitle: String::new(),
+            arg: None,
+            valid: true,
+            quicklookurl: None,
+            text: None,
+            mods: Mods::default(),
+        }
+    }
+}
+
+impl From<&SearchHit> for Item {
+    /// Opens the homepage (or else the repository) when picked, copies the
+    /// install command (or else the package name) with ⌘C, opens the
+    /// repository with ⌘, and passes the install command on with ⌃.
+    fn from(hit: &SearchHit) -> Self {
+        let url = hit.homepage.clone().or_else(|| hit.repository.clone());
+        let install = hit.install_command();
+        let mut subtitle = match &hit.version {
+            Some(version) => format!("{} {}", hit.source, version),
+            None => hit.source.clone(),
+        };
+        if let Some(description) = hit
+            .description
+            .as_deref()
+            .and_then(|description| description.lines().next())
+        {
+            subtitle.push_str(" · ");
+            subtitle.push_str(description.trim());
+        }
+
+        Self {
+            uid: Some(format!("{}:{}", hit.source, hit.name)),
+            title: hit.name.clone(),
+            subtitle,
+            valid: url.is_some(),
+            arg: url.clone(),
+            quicklookurl: url,
+            text: Some(Text {
+                copy: install.clone().unwrap_or_else(|| hit.name.clone()),
+                largetype: hit.name.clone(),
+            }),
+            mods: Mods {
+                cmd: hit.repository.as_ref().map(|repository| Modifier {
+                    arg: repository.clone(),
+                    subtitle: format!("Open {}", repository),
+                    valid: true,
+                }),
+                alt: hit.version.as_ref().map(|version| Modifier {
+                    arg: format!("{}@{}", hit.name, version),
+                    subtitle: format!("Use {}@{}", hit.name, version),
+                    valid: true,
+                }),
+                ctrl: install.map(|install| Modifier {
+                    subtitle: format!("Run `{}`", install),
+                    arg: install,
+                    valid: true,
+                }),
+            },
+        }
+    }
+}
+
+/// The text copied with ⌘C and shown with ⌘L.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct Text {
+    pub copy: String,
+    pub largetype: String,
+}
+
+/// What picking an item does while a modifier key is held.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct Mods {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cmd: Option<Modifier>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub alt: Option<Modifier>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ctrl: Option<Modifier>,
+}
+
+impl Mods {
+    fn is_empty(&self) -> bool {
+        self.cmd.is_none() && self.alt.is_none() && self.ctrl.is_none()
+    }
+}
+
+/// The `arg` and subtitle of an item while a modifier key is held.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct Modifier {
+    pub arg: String,
+    pub subtitle: String,
+    pub valid: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn serde() -> SearchHit {
+        SearchHit {
+            name: "serde".to_string(),
+            version: Some("1.0.219".to_string()),
+            description: Some("A serialization framework\nfor Rust".to_string()),
+            homepage: Some("https://serde.rs".to_string()),
+            repository: Some("https://github.com/serde-rs/serde".to_string()),
+            source: "crates".to_string(),
+            ..SearchHit::default()
+        }
+    }
+
+    #[test]
+    fn turns_hits_into_items() {
+        let filter = ScriptFilter::from_hits(&[serde()]);
+        assert_eq!(
+            serde_json::to_value(&filter).unwrap(),
+            json!({"items": [{
+                "uid": "crates:serde",
+                "title": "serde",
+                "subtitle": "crates 1.0.219 · A serialization framework",
+                "arg": "https://serde.rs",
+                "valid": true,
+                "quicklookurl": "https://serde.rs",
+                "text": {"copy": "cargo add serde", "largetype": "serde"},
+                "mods": {
+                    "cmd": {
+                        "arg": "https://github.com/serde-rs/serde",
+                        "subtitle": "Open https://github.com/serde-rs/serde",
+                        "valid": true,
+                    },
+                    "alt": {"arg": "serde@1.0.219", "subtitle": "Use serde@1.0.219", "valid": true},
+                    "ctrl": {"arg": "cargo add serde", "subtitle": "Run `cargo add serde`", "valid": true},
+                },
+            }]})
+        );
+    }
+
+    #[test]
+    fn leaves_hits_without_a_url_unactionable() {
+        let hit = SearchHit {
+            name: "left-pad".to_string(),
+            source: "unknown".to_string(),
+            ..SearchHit::default()
+        };
+        let item = Item::from(&hit);
+        assert!(!item.valid);
+        assert_eq!(item.arg, None);
+        assert_eq!(item.subtitle, "unknown");
+        assert_eq!(item.text.unwrap().copy, "left-pad");
+        assert!(item.mods.is_empty());
+    }
+
+    #[test]
+    fn reports_errors_as_a_single_invalid_item() {
+        let filter = ScriptFilter::error(&SearchError::NotFound);
+        assert_eq!(
+            serde_json::to_value(&filter).unwrap(),
+            json!({"items": [{
+                "title": "Error",
+                "subtitle": SearchError::NotFound.to_string(),
+                "valid": false,
+            }]})
+        );
+    }
+}
@@ -0,0 +1,93 @@
+use crate::error::SearchError;
+use crate::registry::{collect_hits, text, Registry, RegistryError, SearchHit, SearchQuery};
+use crate::ApiClient;
+use async_trait::async_trait;
+use serde_json::Value;
+
+/// The kinds of content hosted on Ansible Galaxy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GalaxyContentType {
+    #[default]
+    Collection,
+    Role,
+}
+
+/// Searches for collections or roles on Ansible Galaxy.
+///
+/// Collections are searched through the v3 API; roles were never migrated
+/// off the v1 API, so role searches use that instead.
+///
+/// # Arguments
+///
+/// * `query` - The search query (optional).
+/// * `content_type` - Whether to search collections or roles.
+///
+/// # Returns
+///
+/// Returns the search results as a `serde_json::Value` or an error.
+pub async fn search_galaxy(
+    query: Option<&str>,
+    content_type: GalaxyContentType,
+) -> Result<Value, SearchError> {
+    let client = ApiClient::new("https://galaxy.ansible.com/api/", None)
+        .set_param("keywords", query.unwrap_or(""))
+        .set_param("limit", "25");
+
+    match content_type {
+        GalaxyContentType::Collection => {
+            client
+                .set_param("is_highest", "true")
+                .get("v3/plugin/ansible/search/collection-versions/")
+                .await
+        }
+        GalaxyContentType::Role => client.get("v1/roles/").await,
+    }
+}
+
+/// Ansible Galaxy.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Galaxy {
+    /// Whether to search collections or roles.
+    pub content_type: GalaxyContentType,
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl Registry for Galaxy {
+    fn name(&self) -> &str {
+        "galaxy"
+    }
+
+    async fn search(&self, query: &SearchQuery) -> Result<Vec<SearchHit>, RegistryError> {
+        let results = search_galaxy(Some(&query.text), self.content_type)
+            .await
+            .map_err(|error| RegistryError::new(self.name(), error))?;
+        let hits = match self.content_type {
+            GalaxyContentType::Collection => collect_hits(&results["data"], query.limit, |item| {
+                let collection = &item["collection_version"];
+                SearchHit {
+                    name: text(&collection["namespace"])
+                        .zip(text(&collection["name"]))
+                        .map(|(namespace, name)| format!("{}.{}", namespace, name))
+                        .unwrap_or_default(),
+                    version: text(&collection["version"]),
+                    description: text(&collection["description"]),
+                    homepage: None,
+                    source: self.name().to_string(),
+                    ..Default::default()
+                }
+            }),
+            GalaxyContentType::Role => {
+                collect_hits(&results["results"], query.limit, |role| SearchHit {
+                    name: text(&role["name"]).unwrap_or_default(),
+                    version: None,
+                    description: text(&role["description"]),
+                    homepage: None,
+                    source: self.name().to_string(),
+                    ..Default::default()
+                })
+            }
+        };
+        Ok(hits)
+    }
+}
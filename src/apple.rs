@@ -0,0 +1,109 @@
+use crate::error::SearchError;
+use crate::registry::{collect_hits, text, Registry, RegistryError, SearchHit, SearchQuery};
+use crate::ApiClient;
+use async_trait::async_trait;
+use serde_json::{json, Value};
+
+/// Searches for pods on CocoaPods.
+///
+/// Queries the Algolia index that backs the search box on cocoapods.org.
+///
+/// # Arguments
+///
+/// * `query` - The search query (optional).
+///
+/// # Returns
+///
+/// Returns the matching pods as a `serde_json::Value` array or an error.
+pub async fn search_cocoapods(query: Option<&str>) -> Result<Value, SearchError> {
+    let response = ApiClient::new("https://wbhhamhynm-dsn.algolia.net/1/indexes/", None)
+        .set_param("query", query.unwrap_or(""))
+        .set_param("hitsPerPage", "25")
+        .set_header("x-algolia-application-id", "WBHHAMHYNM")
+        .set_header("x-algolia-api-key", "4f7544ca8701f9bf2a4e55daff1b09e9")
+        .get("cocoapods")
+        .await?;
+
+    Ok(response.get("hits").cloned().unwrap_or_else(|| json!([])))
+}
+
+/// Searches for Swift packages on the Swift Package Index.
+///
+/// # Arguments
+///
+/// * `query` - The search query (optional).
+/// * `token` - A Swift Package Index API token, which the search API requires.
+///
+/// # Returns
+///
+/// Returns the search results as a `serde_json::Value` or an error.
+pub async fn search_swiftpm(
+    query: Option<&str>,
+    token: Option<&str>,
+) -> Result<Value, SearchError> {
+    let mut client = ApiClient::new("https://swiftpackageindex.com/api/", None)
+        .set_param("query", query.unwrap_or(""));
+
+    if let Some(token) = token {
+        client = client.set_header("Authorization", &format!("Bearer {}", token));
+    }
+
+    client.get("search").await
+}
+
+/// The CocoaPods trunk.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CocoaPods;
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl Registry for CocoaPods {
+    fn name(&self) -> &str {
+        "cocoapods"
+    }
+
+    async fn search(&self, query: &SearchQuery) -> Result<Vec<SearchHit>, RegistryError> {
+        let results = search_cocoapods(Some(&query.text))
+            .await
+            .map_err(|error| RegistryError::new(self.name(), error))?;
+        Ok(collect_hits(&results, query.limit, |pod| SearchHit {
+            name: text(&pod["name"]).unwrap_or_default(),
+            version: text(&pod["version"]),
+            description: text(&pod["summary"]),
+            homepage: text(&pod["homepage"]),
+            source: self.name().to_string(),
+            ..Default::default()
+        }))
+    }
+}
+
+/// The Swift Package Index.
+#[derive(Debug, Clone, Default)]
+pub struct SwiftPackageIndex {
+    /// A Swift Package Index API token, which the search API requires.
+    pub token: Option<String>,
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl Registry for SwiftPackageIndex {
+    fn name(&self) -> &str {
+        "swiftpm"
+    }
+
+    async fn search(&self, query: &SearchQuery) -> Result<Vec<SearchHit>, RegistryError> {
+        let results = search_swiftpm(Some(&query.text), self.token.as_deref())
+            .await
+            .map_err(|error| RegistryError::new(self.name(), error))?;
+        Ok(collect_hits(&results["results"], query.limit, |result| {
+            SearchHit {
+                name: text(&result["package"]["packageName"]).unwrap_or_default(),
+                version: None,
+                description: text(&result["package"]["summary"]),
+                homepage: None,
+                source: self.name().to_string(),
+                ..Default::default()
+            }
+        }))
+    }
+}
@@ -0,0 +1,96 @@
+use crate::client::encode_path_segment;
+use crate::error::SearchError;
+use crate::registry::{collect_hits, text, Registry, RegistryError, SearchHit, SearchQuery};
+use crate::ApiClient;
+use async_trait::async_trait;
+use futures::future::join_all;
+use serde_json::{json, Value};
+
+const SOURCES_API: &str = "https://sources.debian.org/api/";
+
+/// Searches for Debian source packages on sources.debian.org.
+///
+/// The search endpoint only returns names, so the newest version of each of
+/// the first `limit` results is looked up separately, all at once; a package
+/// whose lookup fails is listed with a `null` version. sources.debian.org
+/// does not expose package descriptions, so `description` is always `null`.
+///
+/// # Arguments
+///
+/// * `query` - The search query (optional).
+/// * `limit` - The maximum number of results to return.
+///
+/// # Returns
+///
+/// Returns the search results as a `serde_json::Value` array of
+/// `name`/`version`/`description` objects or an error.
+pub async fn search_apt(query: Option<&str>, limit: usize) -> Result<Value, SearchError> {
+    let response = ApiClient::new(SOURCES_API, None)
+        .get(&format!(
+            "search/{}/",
+            encode_path_segment(query.unwrap_or(""))
+        ))
+        .await?;
+
+    let results = &response["results"];
+    let names: Vec<&str> = results["exact"]["name"]
+        .as_str()
+        .into_iter()
+        .chain(
+            results["other"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(|other| other["name"].as_str()),
+        )
+        .take(limit)
+        .collect();
+
+    let versions = join_all(names.iter().map(|name| latest_version(name))).await;
+    let hits = names
+        .into_iter()
+        .zip(versions)
+        .map(|(name, version)| {
+            json!({
+                "name": name,
+                "version": version.unwrap_or(Value::Null),
+                "description": null,
+            })
+        })
+        .collect();
+    Ok(Value::Array(hits))
+}
+
+async fn latest_version(name: &str) -> Result<Value, SearchError> {
+    let response = ApiClient::new(SOURCES_API, None)
+        .get(&format!("src/{}/", encode_path_segment(name)))
+        .await?;
+    Ok(response["versions"][0]["version"].clone())
+}
+
+/// Debian source packages.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Debian;
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl Registry for Debian {
+    fn name(&self) -> &str {
+        "apt"
+    }
+
+    async fn search(&self, query: &SearchQuery) -> Result<Vec<SearchHit>, RegistryError> {
+        let results = search_apt(Some(&query.text), query.limit)
+            .await
+            .map_err(|error| RegistryError::new(self.name(), error))?;
+        Ok(collect_hits(&results, query.limit, |package| SearchHit {
+            name: text(&package["name"]).unwrap_or_default(),
+            version: text(&package["version"]),
+            description: text(&package["description"]),
+            homepage: text(&package["name"])
+                .map(|name| format!("https://tracker.debian.org/pkg/{}", name)),
+            source: self.name().to_string(),
+            ..Default::default()
+        }))
+    }
+}
@@ -0,0 +1,97 @@
+use crate::error::SearchError;
+use crate::registry::{collect_hits, text, Registry, RegistryError, SearchHit, SearchQuery};
+use crate::ApiClient;
+use async_trait::async_trait;
+use serde_json::{json, Value};
+
+/// The package kinds Artifact Hub can filter by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArtifactHubKind {
+    Helm,
+    Falco,
+    Opa,
+    Olm,
+    HelmPlugin,
+    TektonTask,
+    TektonPipeline,
+    Container,
+    Kyverno,
+}
+
+impl ArtifactHubKind {
+    /// Returns the numeric kind identifier Artifact Hub expects.
+    pub fn id(&self) -> u8 {
+        match self {
+            ArtifactHubKind::Helm => 0,
+            ArtifactHubKind::Falco => 1,
+            ArtifactHubKind::Opa => 2,
+            ArtifactHubKind::Olm => 3,
+            ArtifactHubKind::HelmPlugin => 5,
+            ArtifactHubKind::TektonTask => 6,
+            ArtifactHubKind::TektonPipeline => 10,
+            ArtifactHubKind::Container => 11,
+            ArtifactHubKind::Kyverno => 14,
+        }
+    }
+}
+
+/// Searches for packages on Artifact Hub.
+///
+/// # Arguments
+///
+/// * `query` - The search query (optional).
+/// * `kind` - Restricts results to one package kind; `None` searches all kinds.
+/// * `limit` - The maximum number of results to return (at most 60).
+///
+/// # Returns
+///
+/// Returns the matching packages as a `serde_json::Value` array or an error.
+pub async fn search_artifacthub(
+    query: Option<&str>,
+    kind: Option<ArtifactHubKind>,
+    limit: u32,
+) -> Result<Value, SearchError> {
+    let mut client = ApiClient::new("https://artifacthub.io/api/v1/packages/", None)
+        .set_param("ts_query_web", query.unwrap_or(""))
+        .set_param("limit", &limit.to_string())
+        .set_param("offset", "0");
+
+    if let Some(kind) = kind {
+        client = client.set_param("kind", &kind.id().to_string());
+    }
+
+    let response = client.get("search").await?;
+    Ok(response
+        .get("packages")
+        .cloned()
+        .unwrap_or_else(|| json!([])))
+}
+
+/// Artifact Hub.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ArtifactHub {
+    /// Restricts results to one package kind; `None` searches all kinds.
+    pub kind: Option<ArtifactHubKind>,
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl Registry for ArtifactHub {
+    fn name(&self) -> &str {
+        "artifacthub"
+    }
+
+    async fn search(&self, query: &SearchQuery) -> Result<Vec<SearchHit>, RegistryError> {
+        let results = search_artifacthub(Some(&query.text), self.kind, query.limit as u32)
+            .await
+            .map_err(|error| RegistryError::new(self.name(), error))?;
+        Ok(collect_hits(&results, query.limit, |package| SearchHit {
+            name: text(&package["name"]).unwrap_or_default(),
+            version: text(&package["version"]),
+            description: text(&package["description"]),
+            homepage: text(&package["repository"]["url"]),
+            source: self.name().to_string(),
+            ..Default::default()
+        }))
+    }
+}
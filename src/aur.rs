@@ -0,0 +1,87 @@
+use crate::error::SearchError;
+use crate::registry::{collect_hits, text, Registry, RegistryError, SearchHit, SearchQuery};
+use crate::ApiClient;
+use async_trait::async_trait;
+use serde_json::Value;
+
+/// The package fields the AUR RPC can search by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AurSearchField {
+    Name,
+    #[default]
+    NameDesc,
+    Maintainer,
+    Depends,
+    MakeDepends,
+    OptDepends,
+    CheckDepends,
+}
+
+impl AurSearchField {
+    /// Returns the value the AUR RPC expects for the `by` parameter.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AurSearchField::Name => "name",
+            AurSearchField::NameDesc => "name-desc",
+            AurSearchField::Maintainer => "maintainer",
+            AurSearchField::Depends => "depends",
+            AurSearchField::MakeDepends => "makedepends",
+            AurSearchField::OptDepends => "optdepends",
+            AurSearchField::CheckDepends => "checkdepends",
+        }
+    }
+}
+
+/// Searches for packages in the Arch User Repository.
+///
+/// # Arguments
+///
+/// * `query` - The search query (optional).
+/// * `by_field` - The package field to match the query against.
+///
+/// # Returns
+///
+/// Returns the search results as a `serde_json::Value` or an error.
+pub async fn search_aur(
+    query: Option<&str>,
+    by_field: AurSearchField,
+) -> Result<Value, SearchError> {
+    ApiClient::new("https://aur.archlinux.org/rpc/", None)
+        .set_param("v", "5")
+        .set_param("type", "search")
+        .set_param("by", by_field.as_str())
+        .set_param("arg", query.unwrap_or(""))
+        .get("")
+        .await
+}
+
+/// The Arch User Repository.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Aur {
+    /// The package field to match the query against.
+    pub by_field: AurSearchField,
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl Registry for Aur {
+    fn name(&self) -> &str {
+        "aur"
+    }
+
+    async fn search(&self, query: &SearchQuery) -> Result<Vec<SearchHit>, RegistryError> {
+        let results = search_aur(Some(&query.text), self.by_field)
+            .await
+            .map_err(|error| RegistryError::new(self.name(), error))?;
+        Ok(collect_hits(&results["results"], query.limit, |package| {
+            SearchHit {
+                name: text(&package["Name"]).unwrap_or_default(),
+                version: text(&package["Version"]),
+                description: text(&package["Description"]),
+                homepage: text(&package["URL"]),
+                source: self.name().to_string(),
+                ..Default::default()
+            }
+        }))
+    }
+}
@@ -0,0 +1,69 @@
+//! Credentials for private registries and authenticated mirrors.
+
+use reqwest::RequestBuilder;
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex};
+
+static HOST_AUTH: LazyLock<Mutex<HashMap<String, Auth>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// How requests to a registry authenticate.
+#[derive(Clone, PartialEq, Eq)]
+pub enum Auth {
+    /// An `Authorization: Bearer <token>` header, as used by GitHub
+    /// Packages, Verdaccio and Artifactory npm tokens.
+    Bearer(String),
+    /// HTTP basic authentication.
+    Basic {
+        username: String,
+        password: Option<String>,
+    },
+    /// An arbitrary header, e.g. a crates.io mirror's `Authorization: <token>`
+    /// or an `X-JFrog-Art-Api` key.
+    Header { name: String, value: String },
+}
+
+impl Auth {
+    /// Adds the credentials to `request`.
+    pub(crate) fn apply(&self, request: RequestBuilder) -> RequestBuilder {
+        match self {
+            Auth::Bearer(token) => request.bearer_auth(token),
+            Auth::Basic { username, password } => request.basic_auth(username, password.as_ref()),
+            Auth::Header { name, value } => request.header(name, value),
+        }
+    }
+}
+
+// Keeps tokens and passwords out of logs and panic messages.
+impl std::fmt::Debug for Auth {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Auth::Bearer(_) => f.write_str("Bearer(..)"),
+            Auth::Basic { username, .. } => write!(f, "Basic {{ username: {:?}, .. }}", username),
+            Auth::Header { name, .. } => write!(f, "Header {{ name: {:?}, .. }}", name),
+        }
+    }
+}
+
+/// Authenticates every request any client in the process sends to `host`,
+/// e.g. `npm.corp.example`, including those made by the search functions
+/// of the registry modules.
+///
+/// Credentials given to a client with
+/// [`ApiClientBuilder::auth`](crate::ApiClientBuilder::auth) take precedence.
+pub fn set_host_auth(host: &str, auth: Auth) {
+    let mut credentials = HOST_AUTH.lock().unwrap_or_else(|error| error.into_inner());
+    credentials.insert(host.to_string(), auth);
+}
+
+/// Removes the credentials set for `host` with [`set_host_auth`].
+pub fn clear_host_auth(host: &str) {
+    let mut credentials = HOST_AUTH.lock().unwrap_or_else(|error| error.into_inner());
+    credentials.remove(host);
+}
+
+/// Returns the credentials set for `host`, if any.
+pub(crate) fn host_auth(host: &str) -> Option<Auth> {
+    let credentials = HOST_AUTH.lock().unwrap_or_else(|error| error.into_inner());
+    credentials.get(host).cloned()
+}
@@ -0,0 +1,276 @@
+//! Checking whether a package name is free across registries.
+
+use crate::crates::CrateHit;
+use crate::error::SearchError;
+use crate::registry::{text, Registry, RegistryError, SearchHit, SearchQuery};
+use crate::{composer, crates, npm, pypi};
+use futures::future::join_all;
+use serde_json::Value;
+
+/// Words that mark a package as a placeholder rather than a real project.
+const PLACEHOLDER_WORDS: &[&str] = &["placeholder", "reserved", "coming soon", "squat"];
+
+/// Whether a name is available on one registry.
+#[derive(Debug)]
+pub enum Availability {
+    /// No package with the name exists.
+    Free,
+    /// A package with the name exists.
+    Taken(SearchHit),
+    /// A package with the name exists but looks like an empty placeholder,
+    /// so the registry may hand the name over on request.
+    Squatted(SearchHit),
+    /// No package with the name turned up in the registry's search results.
+    /// The registry can't be asked for a name directly, so one may still
+    /// exist that ranks too low to be listed.
+    Unlisted,
+    /// The registry could not be queried.
+    Unknown(RegistryError),
+}
+
+impl Availability {
+    /// Returns `true` if the name can be used on the registry.
+    pub fn is_free(&self) -> bool {
+        matches!(self, Availability::Free)
+    }
+}
+
+/// The availability of a name on one registry.
+#[derive(Debug)]
+pub struct NameAvailability {
+    /// The [`Registry::name`] of the registry.
+    pub registry: String,
+    pub availability: Availability,
+}
+
+/// Checks whether `name` is taken on each of the given registries.
+///
+/// crates.io, npm, PyPI, and Packagist are asked for the name itself, and
+/// answer as they would when it is published: crates.io treats `-` and `_`
+/// as equal, and PyPI normalizes names as PEP 503 describes. Packagist names
+/// must include the vendor, e.g. `acme/name`.
+///
+/// Other registries are searched for the name, and a hit whose name equals
+/// it, ignoring case, counts as taken. Without one the name is
+/// [`Unlisted`](Availability::Unlisted) rather than free. Namespaced
+/// registries, such as Docker Hub or GitHub, only match the full name, e.g.
+/// `owner/name`.
+///
+/// Registries are queried concurrently.
+///
+/// # Arguments
+///
+/// * `name` - The package name to check.
+/// * `registries` - The registries to check, e.g. from
+///   [`all_registries`](crate::registry::all_registries).
+///
+/// # Returns
+///
+/// Returns the availability of the name on every registry, in the order the
+/// registries were given.
+pub async fn check_name_availability(
+    name: &str,
+    registries: &[Box<dyn Registry>],
+) -> Vec<NameAvailability> {
+    let query = SearchQuery::new(name);
+    let checks = registries.iter().map(|registry| {
+        let query = &query;
+        async move {
+            let availability = match lookup(registry.name(), name).await {
+                Some(Ok(Some(hit))) => taken(hit),
+                Some(Ok(None)) => Availability::Free,
+                Some(Err(error)) => {
+                    Availability::Unknown(RegistryError::new(registry.name(), error))
+                }
+                None => match registry.search(query).await {
+                    Ok(hits) => scan(name, hits),
+                    Err(error) => Availability::Unknown(error),
+                },
+            };
+            NameAvailability {
+                registry: registry.name().to_string(),
+                availability,
+            }
+        }
+    });
+    join_all(checks).await
+}
+
+/// Fetches the package named `name` from the registries that can be asked
+/// for one by name.
+///
+/// # Returns
+///
+/// Returns the package, or `None` inside if there is none, or `None` if the
+/// registry has no lookup by name.
+async fn lookup(registry: &str, name: &str) -> Option<Result<Option<SearchHit>, SearchError>> {
+    let found = match registry {
+        "crates" => crates::get_crate(name).await.and_then(|response| {
+            let krate: CrateHit = serde_json::from_value(response["crate"].clone())?;
+            Ok(SearchHit::from(krate))
+        }),
+        "npm" => npm::get_package(name).await.map(|package| {
+            let latest = &package["dist-tags"]["latest"];
+            SearchHit {
+                name: package["name"].as_str().unwrap_or(name).to_string(),
+                version: text(latest),
+                description: text(&package["description"]),
+                homepage: text(&package["homepage"]),
+                license: text(&package["license"]),
+                deprecated: package["versions"][latest.as_str().unwrap_or_default()]["deprecated"]
+                    .is_string(),
+                source: registry.to_string(),
+                ..SearchHit::default()
+            }
+        }),
+        "pypi" => pypi::get_project(name).await.map(|project| {
+            let info = &project["info"];
+            SearchHit {
+                name: info["name"].as_str().unwrap_or(name).to_string(),
+                version: text(&info["version"]),
+                description: text(&info["summary"]),
+                homepage: text(&info["home_page"]),
+                license: text(&info["license"]),
+                source: registry.to_string(),
+                ..SearchHit::default()
+            }
+        }),
+        "composer" if !name.contains('/') => Err(SearchError::InvalidQuery(format!(
+            "Packagist names include the vendor, e.g. 'acme/{}'",
+            name
+        ))),
+        "composer" => composer::get_package(&name.to_lowercase())
+            .await
+            .map(|package| {
+                let latest = package["packages"]
+                    .as_object()
+                    .and_then(|packages| packages.values().next())
+                    .and_then(|releases| releases.get(0))
+                    .cloned()
+                    .unwrap_or(Value::Null);
+                SearchHit {
+                    name: name.to_lowercase(),
+                    version: text(&latest["version"]),
+                    description: text(&latest["description"]),
+                    homepage: text(&latest["homepage"]),
+                    source: registry.to_string(),
+                    ..SearchHit::default()
+                }
+            }),
+        _ => return None,
+    };
+    Some(match found {
+        Ok(hit) => Ok(Some(hit)),
+        Err(SearchError::NotFound) => Ok(None),
+        Err(error) => Err(error),
+    })
+}
+
+/// Looks for a hit named `name`, ignoring case, among search results.
+fn scan(name: &str, hits: Vec<SearchHit>) -> Availability {
+    match hits
+        .into_iter()
+        .find(|hit| hit.name.eq_ignore_ascii_case(name))
+    {
+        Some(hit) => taken(hit),
+        None => Availability::Unlisted,
+    }
+}
+
+fn taken(hit: SearchHit) -> Availability {
+    if is_placeholder(&hit) {
+        Availability::Squatted(hit)
+    } else {
+        Availability::Taken(hit)
+    }
+}
+
+/// Returns `true` for packages with no real description whose latest
+/// release is a `0.0.x`. Packages whose version isn't known are never
+/// placeholders.
+fn is_placeholder(hit: &SearchHit) -> bool {
+    let description = hit.description.as_deref().unwrap_or("").to_lowercase();
+    let empty = description.trim().is_empty()
+        || PLACEHOLDER_WORDS
+            .iter()
+            .any(|word| description.contains(word));
+    let unreleased = hit
+        .version
+        .as_deref()
+        .is_some_and(|version| version.starts_with("0.0."));
+    empty && unreleased
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+
+    /// A registry that can only be searched, answering with fixed hits.
+    struct Fixed(Result<Vec<SearchHit>, ()>);
+
+    #[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+    #[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+    impl Registry for Fixed {
+        fn name(&self) -> &str {
+            "fixed"
+        }
+
+        async fn search(&self, _query: &SearchQuery) -> Result<Vec<SearchHit>, RegistryError> {
+            self.0
+                .clone()
+                .map_err(|()| RegistryError::new(self.name(), SearchError::NotFound))
+        }
+    }
+
+    fn hit(name: &str, version: &str, description: &str) -> SearchHit {
+        SearchHit {
+            name: name.to_string(),
+            version: Some(version.to_string()),
+            description: Some(description.to_string()),
+            ..SearchHit::default()
+        }
+    }
+
+    #[test]
+    fn tells_placeholders_from_real_packages() {
+        assert!(is_placeholder(&hit("left-pad", "0.0.1", "")));
+        assert!(is_placeholder(&hit("left-pad", "0.0.0", "Name reserved")));
+        assert!(!is_placeholder(&hit("left-pad", "0.0.1", "Pads strings")));
+        assert!(!is_placeholder(&hit("left-pad", "1.0.0", "")));
+        let unversioned = SearchHit {
+            version: None,
+            ..hit("left-pad", "", "")
+        };
+        assert!(!is_placeholder(&unversioned));
+    }
+
+    #[test]
+    fn matches_search_hits_by_name_ignoring_case() {
+        let hits = vec![hit("Left-Pad", "1.3.0", "Pads strings")];
+        assert!(matches!(
+            scan("left-pad", hits.clone()),
+            Availability::Taken(_)
+        ));
+        assert!(matches!(scan("right-pad", hits), Availability::Unlisted));
+        let placeholder = vec![hit("left-pad", "0.0.1", "coming soon")];
+        assert!(matches!(
+            scan("left-pad", placeholder),
+            Availability::Squatted(_)
+        ));
+    }
+
+    #[tokio::test]
+    async fn checks_registries_without_a_lookup_by_searching() {
+        let registries: Vec<Box<dyn Registry>> = vec![
+            Box::new(Fixed(Ok(vec![hit("left-pad", "1.3.0", "Pads strings")]))),
+            Box::new(Fixed(Ok(Vec::new()))),
+            Box::new(Fixed(Err(()))),
+        ];
+        let results = check_name_availability("left-pad", &registries).await;
+        assert!(matches!(results[0].availability, Availability::Taken(_)));
+        assert!(matches!(results[1].availability, Availability::Unlisted));
+        assert!(matches!(results[2].availability, Availability::Unknown(_)));
+        assert!(results.iter().all(|result| result.registry == "fixed"));
+    }
+}
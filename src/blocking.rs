@@ -0,0 +1,88 @@
+//! Synchronous versions of the most used searches, for scripts and plugins
+//! that don't run an async runtime of their own.
+//!
+//! Each function runs its async counterpart to completion on a tokio
+//! runtime the module starts on first use and keeps for the life of the
+//! process, so that connections pooled by one call can be reused by the
+//! next. `reqwest` needs tokio underneath, so the `blocking` feature pulls
+//! it in; the rest of the library doesn't depend on it. Don't call these
+//! functions from inside an async runtime; use the async functions there
+//! instead.
+
+use crate::aggregate::{self, AggregateResults};
+use crate::composer::{self, ComposerHit, ComposerSearchOptions};
+use crate::crates::{self, CrateHit, CrateSort};
+use crate::docker::{self, DockerHit};
+use crate::error::SearchError;
+use crate::npm::{self, NpmHit};
+use crate::results::SearchResults;
+use crate::{pypi, rubygems};
+use serde_json::Value;
+use std::future::Future;
+use std::io;
+use std::sync::LazyLock;
+use tokio::runtime::{Builder, Runtime};
+
+/// The runtime every call runs on. It has a worker thread of its own, which
+/// keeps the connections of the shared client alive between calls.
+static RUNTIME: LazyLock<io::Result<Runtime>> = LazyLock::new(|| {
+    Builder::new_multi_thread()
+        .worker_threads(1)
+        .thread_name("search-libraries-blocking")
+        .enable_all()
+        .build()
+});
+
+/// Runs `future` to completion on the shared runtime.
+fn block_on<F: Future>(future: F) -> Result<F::Output, SearchError> {
+    let runtime = RUNTIME
+        .as_ref()
+        .map_err(|error| io::Error::new(error.kind(), error.to_string()))?;
+    Ok(runtime.block_on(future))
+}
+
+/// Searches for crates on crates.io; see [`crates::search_crates`].
+pub fn search_crates(
+    query: Option<&str>,
+    sort: CrateSort,
+) -> Result<SearchResults<CrateHit>, SearchError> {
+    block_on(crates::search_crates(query, sort))?
+}
+
+/// Searches for packages on the npm registry; see [`npm::search_npm`].
+pub fn search_npm(query: Option<&str>) -> Result<SearchResults<NpmHit>, SearchError> {
+    block_on(npm::search_npm(query))?
+}
+
+/// Searches for images on Docker Hub; see [`docker::search_docker`].
+pub fn search_docker(query: Option<&str>) -> Result<SearchResults<DockerHit>, SearchError> {
+    block_on(docker::search_docker(query))?
+}
+
+/// Searches for Composer packages on Packagist; see
+/// [`composer::search_composer`].
+pub fn search_composer(
+    query: Option<&str>,
+    options: &ComposerSearchOptions,
+) -> Result<SearchResults<ComposerHit>, SearchError> {
+    block_on(composer::search_composer(query, options))?
+}
+
+/// Searches for packages on PyPI; see [`pypi::search_pypi`].
+pub fn search_pypi(query: Option<&str>, page: u32) -> Result<Value, SearchError> {
+    block_on(pypi::search_pypi(query, page))?
+}
+
+/// Searches for gems on RubyGems; see [`rubygems::search_gems`].
+pub fn search_gems(query: Option<&str>, page: u32) -> Result<Value, SearchError> {
+    block_on(rubygems::search_gems(query, page))?
+}
+
+/// Searches several registries at once; see [`aggregate::search_all`].
+pub fn search_all(
+    query: &str,
+    sources: &[&str],
+    limit: usize,
+) -> Result<AggregateResults, SearchError> {
+    block_on(aggregate::search_all(query, sources, limit))
+}
@@ -0,0 +1,343 @@
+//! Caching of registry responses and large registry indexes.
+
+use crate::error::SearchError;
+use crate::ApiClient;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Arc, LazyLock, Mutex, RwLock};
+use std::time::Duration;
+use web_time::Instant;
+
+/// The number of responses a [`ResponseCache`] keeps in memory by default.
+const DEFAULT_CAPACITY: usize = 256;
+
+static RESPONSE_CACHE: LazyLock<RwLock<Option<Arc<ResponseCache>>>> =
+    LazyLock::new(|| RwLock::new(None));
+
+/// Returns the directory cached indexes are stored in.
+///
+/// This is `$XDG_CACHE_HOME/search-libraries`, falling back to
+/// `~/.cache/search-libraries` and then `%LOCALAPPDATA%\search-libraries`.
+/// There is none on wasm32, so nothing is cached on disk there.
+pub fn cache_dir() -> Option<PathBuf> {
+    if cfg!(target_arch = "wasm32") {
+        return None;
+    }
+    std::env::var_os("XDG_CACHE_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".cache")))
+        .or_else(|| std::env::var_os("LOCALAPPDATA").map(PathBuf::from))
+        .map(|dir| dir.join("search-libraries"))
+}
+
+/// Returns the JSON document at `url`, reusing the cached copy stored under
+/// `name` if it is younger than `max_age`.
+///
+/// Failing to write the cache is not an error; the index is simply fetched
+/// again next time.
+pub(crate) async fn cached_index(
+    name: &str,
+    url: &str,
+    max_age: Duration,
+) -> Result<Value, SearchError> {
+    if let Some(contents) = read_fresh(name, max_age) {
+        if let Ok(index) = serde_json::from_str(&contents) {
+            return Ok(index);
+        }
+    }
+
+    let contents = ApiClient::new(url, None).get_text("").await?;
+    let index = serde_json::from_str(&contents)?;
+    write(name, &contents);
+    Ok(index)
+}
+
+fn read_fresh(name: &str, max_age: Duration) -> Option<String> {
+    let path = cache_dir()?.join(name);
+    let age = fs::metadata(&path).ok()?.modified().ok()?.elapsed().ok()?;
+    if age > max_age {
+        return None;
+    }
+    fs::read_to_string(path).ok()
+}
+
+fn write(name: &str, contents: &str) {
+    if let Some(path) = cache_dir().map(|dir| dir.join(name)) {
+        if let Some(dir) = path.parent() {
+            let _ = fs::create_dir_all(dir).and_then(|_| fs::write(&path, contents));
+        }
+    }
+}
+
+/// A cache of registry responses keyed by URL and query parameters, so that
+/// repeated identical queries, e.g. from as-you-type frontends, are answered
+/// without a request.
+///
+/// Responses are kept in memory, evicting the least recently used beyond
+/// the capacity, and optionally on disk under [`cache_dir`]. Only GET
+/// requests are cached, and only those without credentials, so that a
+/// response is never served for other credentials or written to disk.
+///
+/// Expired responses that came with an `ETag` or `Last-Modified` header are
+/// kept and revalidated with a conditional request; a `304 Not Modified`
+/// answer renews them without downloading the body again. A TTL of zero
+/// therefore revalidates on every request, which suits polling.
+#[derive(Debug)]
+pub struct ResponseCache {
+    ttl: Duration,
+    capacity: usize,
+    disk: bool,
+    entries: Mutex<HashMap<String, CacheEntry>>,
+}
+
+/// A cached response body along with its validators.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct CachedResponse {
+    pub(crate) body: String,
+    pub(crate) etag: Option<String>,
+    pub(crate) last_modified: Option<String>,
+    /// Whether the response is younger than the TTL and can be used without
+    /// revalidating it.
+    pub(crate) fresh: bool,
+}
+
+impl CachedResponse {
+    fn has_validators(&self) -> bool {
+        self.etag.is_some() || self.last_modified.is_some()
+    }
+}
+
+#[derive(Debug)]
+struct CacheEntry {
+    response: CachedResponse,
+    stored: Instant,
+    last_used: Instant,
+}
+
+impl ResponseCache {
+    /// Creates an in-memory cache whose responses expire after `ttl`.
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            capacity: DEFAULT_CAPACITY,
+            disk: false,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Sets how many responses are kept in memory. Defaults to 256.
+    pub fn capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity;
+        self
+    }
+
+    /// Also stores responses under `responses/` in [`cache_dir`], so they
+    /// outlive the process.
+    pub fn on_disk(mut self) -> Self {
+        self.disk = true;
+        self
+    }
+
+    /// Removes every cached response, in memory and on disk.
+    pub fn clear(&self) {
+        self.lock().clear();
+        if self.disk {
+            if let Some(dir) = cache_dir() {
+                let _ = fs::remove_dir_all(dir.join("responses"));
+            }
+        }
+    }
+
+    /// Returns the response cached for `key` if it is fresh, or if it has
+    /// expired but can be revalidated.
+    pub(crate) fn get(&self, key: &str) -> Option<CachedResponse> {
+        let mut entries = self.lock();
+        if let Some(entry) = entries.get_mut(key) {
+            entry.last_used = Instant::now();
+            let mut response = entry.response.clone();
+            response.fresh = entry.stored.elapsed() <= self.ttl;
+            if response.fresh || response.has_validators() {
+                return Some(response);
+            }
+            entries.remove(key);
+        }
+        drop(entries);
+
+        if !self.disk {
+            return None;
+        }
+        let (mut response, age) = read_response(&disk_name(key))?;
+        response.fresh = age <= self.ttl;
+        if !response.fresh && !response.has_validators() {
+            return None;
+        }
+        self.insert_in_memory(key, response.clone(), age);
+        Some(response)
+    }
+
+    /// Caches `response` for `key`.
+    pub(crate) fn insert(&self, key: &str, response: CachedResponse) {
+        if self.disk {
+            write_response(&disk_name(key), &response);
+        }
+        self.insert_in_memory(key, response, Duration::ZERO);
+    }
+
+    fn insert_in_memory(&self, key: &str, response: CachedResponse, age: Duration) {
+        if self.capacity == 0 {
+            return;
+        }
+        let mut entries = self.lock();
+        if entries.len() >= self.capacity && !entries.contains_key(key) {
+            let oldest = entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(key, _)| key.clone());
+            if let Some(oldest) = oldest {
+                entries.remove(&oldest);
+            }
+        }
+        let now = Instant::now();
+        entries.insert(
+            key.to_string(),
+            CacheEntry {
+                response,
+                stored: now.checked_sub(age).unwrap_or(now),
+                last_used: now,
+            },
+        );
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, CacheEntry>> {
+        self.entries
+            .lock()
+            .unwrap_or_else(|error| error.into_inner())
+    }
+}
+
+/// Caches the responses of every client in the process that was not given
+/// its own cache, including those made by the search functions of the
+/// registry modules. `None` turns caching off again.
+pub fn set_response_cache(cache: Option<ResponseCache>) {
+    let mut global = RESPONSE_CACHE
+        .write()
+        .unwrap_or_else(|error| error.into_inner());
+    *global = cache.map(Arc::new);
+}
+
+/// Returns the cache set with [`set_response_cache`], if any.
+pub(crate) fn response_cache() -> Option<Arc<ResponseCache>> {
+    RESPONSE_CACHE
+        .read()
+        .unwrap_or_else(|error| error.into_inner())
+        .clone()
+}
+
+/// Returns the file name a response is stored under in `responses/`.
+///
+/// The name is the SHA-256 of the key, which, unlike the standard library's
+/// hasher, stays the same across Rust releases.
+fn disk_name(key: &str) -> String {
+    let digest = Sha256::digest(key);
+    let hex: String = digest.iter().map(|byte| format!("{:02x}", byte)).collect();
+    format!("responses/{}", hex)
+}
+
+/// Reads a response stored by [`write_response`] along with its age.
+fn read_response(name: &str) -> Option<(CachedResponse, Duration)> {
+    let path = cache_dir()?.join(name);
+    let age = fs::metadata(&path).ok()?.modified().ok()?.elapsed().ok()?;
+    let stored: Value = serde_json::from_str(&fs::read_to_string(path).ok()?).ok()?;
+    let response = CachedResponse {
+        body: stored["body"].as_str()?.to_string(),
+        etag: stored["etag"].as_str().map(str::to_string),
+        last_modified: stored["last_modified"].as_str().map(str::to_string),
+        fresh: false,
+    };
+    Some((response, age))
+}
+
+/// Stores a response with its validators as a small JSON document.
+fn write_response(name: &str, response: &CachedResponse) {
+    let stored = serde_json::json!({
+        "etag": response.etag,
+        "last_modified": response.last_modified,
+        "body": response.body,
+    });
+    write(name, &stored.to_string());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn response(body: &str, etag: Option<&str>) -> CachedResponse {
+        CachedResponse {
+            body: body.to_string(),
+            etag: etag.map(str::to_string),
+            ..CachedResponse::default()
+        }
+    }
+
+    #[test]
+    fn answers_from_memory_while_fresh() {
+        let cache = ResponseCache::new(Duration::from_secs(60));
+        cache.insert("a", response("1", None));
+        let cached = cache.get("a").unwrap();
+        assert_eq!(cached.body, "1");
+        assert!(cached.fresh);
+        assert!(cache.get("b").is_none());
+    }
+
+    #[test]
+    fn evicts_the_least_recently_used_response() {
+        let cache = ResponseCache::new(Duration::from_secs(60)).capacity(2);
+        cache.insert("a", response("1", None));
+        cache.insert("b", response("2", None));
+        cache.get("a");
+        cache.insert("c", response("3", None));
+        assert!(cache.get("a").is_some());
+        assert!(cache.get("b").is_none());
+        assert!(cache.get("c").is_some());
+    }
+
+    #[test]
+    fn keeps_nothing_without_capacity() {
+        let cache = ResponseCache::new(Duration::from_secs(60)).capacity(0);
+        cache.insert("a", response("1", None));
+        assert!(cache.get("a").is_none());
+    }
+
+    #[test]
+    fn drops_expired_responses_that_cannot_be_revalidated() {
+        let cache = ResponseCache::new(Duration::ZERO);
+        cache.insert("a", response("1", None));
+        std::thread::sleep(Duration::from_millis(5));
+        assert!(cache.get("a").is_none());
+    }
+
+    #[test]
+    fn keeps_expired_responses_with_validators_for_revalidation() {
+        let cache = ResponseCache::new(Duration::from_millis(200));
+        cache.insert("a", response("1", Some("\"v1\"")));
+        std::thread::sleep(Duration::from_millis(250));
+        let stale = cache.get("a").unwrap();
+        assert!(!stale.fresh);
+        assert_eq!(stale.etag.as_deref(), Some("\"v1\""));
+
+        // A 304 answer stores the response again, which renews it.
+        cache.insert("a", stale);
+        assert!(cache.get("a").unwrap().fresh);
+    }
+
+    #[test]
+    fn names_stored_responses_after_the_sha256_of_their_key() {
+        assert_eq!(
+            disk_name("abc"),
+            "responses/ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+}
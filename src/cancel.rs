@@ -0,0 +1,40 @@
+//! Cancelling searches that are no longer needed.
+//!
+//! Every search in this crate is a future that does nothing unless polled,
+//! so dropping it cancels it: the request in flight is aborted, its
+//! connection is released, and no retries or further pages are fetched.
+//! A type-ahead frontend can simply drop the future of the previous query,
+//! e.g. by replacing the task that awaits it, when the user keeps typing.
+//!
+//! [`with_deadline`] gives up on a search that takes too long; see also
+//! [`search_all_with_deadline`](crate::aggregate::search_all_with_deadline),
+//! which keeps the hits of the registries that answered in time.
+
+use crate::error::SearchError;
+use futures::future::{select, Either};
+use futures_timer::Delay;
+use std::future::Future;
+use std::pin::pin;
+use std::time::Duration;
+
+/// Runs `future` until it completes or `deadline` has passed, whichever
+/// comes first. On expiry the future is dropped, which cancels it.
+///
+/// # Arguments
+///
+/// * `future` - The search to run, e.g. `crates::get_crate("serde")`.
+/// * `deadline` - How long to wait for it.
+///
+/// # Returns
+///
+/// Returns the output of `future`, or [`SearchError::DeadlineExceeded`] if
+/// it did not finish in time.
+pub async fn with_deadline<T, F>(future: F, deadline: Duration) -> Result<T, SearchError>
+where
+    F: Future<Output = Result<T, SearchError>>,
+{
+    match select(pin!(future), Delay::new(deadline)).await {
+        Either::Left((output, _)) => output,
+        Either::Right(_) => Err(SearchError::DeadlineExceeded(deadline)),
+    }
+}
@@ -0,0 +1,1240 @@
+use crate::auth::{host_auth, Auth};
+use crate::cache::{response_cache, CachedResponse, ResponseCache};
+use crate::error::SearchError;
+use crate::metrics::{metrics, RequestMetrics};
+use crate::middleware::{self, Middleware};
+use crate::rate_limit::{self, RateLimiter};
+use crate::vcr::{Vcr, VcrMode};
+use futures_timer::Delay;
+use reqwest::header::{ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED, RETRY_AFTER};
+#[cfg(not(target_arch = "wasm32"))]
+use reqwest::{Certificate, Proxy};
+use reqwest::{Client, Method, RequestBuilder, Response, StatusCode, Url};
+use serde_json::Value;
+use std::collections::hash_map::RandomState;
+use std::collections::HashMap;
+use std::future::Future;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::{Arc, LazyLock, RwLock};
+use std::time::Duration;
+#[cfg(feature = "tracing")]
+use tracing::Instrument;
+use web_time::Instant;
+
+/// The number of bytes of an error response kept in [`SearchError::Status`].
+const BODY_SNIPPET_LEN: usize = 512;
+
+/// The number of bytes of an error response read to find its messages.
+const ERROR_BODY_LIMIT: usize = 64 * 1024;
+
+/// The longest a single backoff delay may grow to.
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// Names of query parameters and fields that carry credentials, such as
+/// the API key of Libraries.io.
+const CREDENTIAL_NAMES: [&str; 11] = [
+    "access_token",
+    "api_key",
+    "apikey",
+    "auth",
+    "client_secret",
+    "key",
+    "password",
+    "secret",
+    "sig",
+    "signature",
+    "token",
+];
+
+/// The largest response body a client reads, which leaves room for full
+/// indexes such as Homebrew's `formula.json`.
+pub const DEFAULT_MAX_RESPONSE_SIZE: usize = 128 * 1024 * 1024;
+
+/// How long to wait for a connection to a registry to be established.
+pub const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How long to wait for a whole request, from connecting to reading the
+/// last byte of the response.
+pub const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// The User-Agent sent by clients that were not given one, identifying the
+/// crate, its version, and where to reach its maintainers.
+pub const DEFAULT_USER_AGENT: &str = concat!(
+    env!("CARGO_PKG_NAME"),
+    "/",
+    env!("CARGO_PKG_VERSION"),
+    " (",
+    env!("CARGO_PKG_REPOSITORY"),
+    ")"
+);
+
+static USER_AGENT: LazyLock<RwLock<String>> =
+    LazyLock::new(|| RwLock::new(DEFAULT_USER_AGENT.to_string()));
+
+#[cfg(not(target_arch = "wasm32"))]
+static DEFAULT_CLIENT: LazyLock<Client> = LazyLock::new(|| {
+    Client::builder()
+        .connect_timeout(DEFAULT_CONNECT_TIMEOUT)
+        .build()
+        .unwrap_or_default()
+});
+
+#[cfg(target_arch = "wasm32")]
+static DEFAULT_CLIENT: LazyLock<Client> = LazyLock::new(Client::new);
+
+/// Returns the `reqwest::Client` shared by every [`ApiClient`] that was not
+/// given its own, so that repeated searches reuse pooled connections.
+///
+/// Cloning a `Client` is cheap; the clones share one connection pool.
+pub fn default_client() -> Client {
+    DEFAULT_CLIENT.clone()
+}
+
+/// Sets the User-Agent sent by every client in the process that was not
+/// given its own, including those made by the search functions of the
+/// registry modules.
+///
+/// Registries such as crates.io require a User-Agent that identifies the
+/// application and a way to contact its author, e.g.
+/// `my-tool/1.0 (https://example.com/my-tool)`. Defaults to
+/// [`DEFAULT_USER_AGENT`].
+///
+/// # Arguments
+///
+/// * `user_agent` - The User-Agent header value.
+pub fn set_default_user_agent(user_agent: &str) {
+    let mut global = USER_AGENT
+        .write()
+        .unwrap_or_else(|error| error.into_inner());
+    *global = user_agent.to_string();
+}
+
+/// Returns the User-Agent set with [`set_default_user_agent`].
+fn default_user_agent() -> String {
+    USER_AGENT
+        .read()
+        .unwrap_or_else(|error| error.into_inner())
+        .clone()
+}
+
+/// A client for making API requests.
+pub struct ApiClient {
+    client: Client,
+    search_url: String,
+    params: HashMap<String, String>,
+    headers: HashMap<String, String>,
+    user_agent: Option<String>,
+    max_retries: u32,
+    backoff: Duration,
+    jitter: bool,
+    request_timeout: Option<Duration>,
+    max_response_size: usize,
+    rate_limiter: Option<RateLimiter>,
+    auth: Option<Auth>,
+    cache: Option<Arc<ResponseCache>>,
+    no_cache: bool,
+    vcr: Option<Vcr>,
+    middleware: Middleware,
+}
+
+/// Configures an [`ApiClient`] beyond its search URL and user agent.
+///
+/// Created by [`ApiClient::builder`].
+#[derive(Debug, Clone)]
+pub struct ApiClientBuilder {
+    client: Option<Client>,
+    search_url: String,
+    user_agent: Option<String>,
+    max_retries: u32,
+    backoff: Duration,
+    jitter: bool,
+    connect_timeout: Option<Duration>,
+    read_timeout: Option<Duration>,
+    request_timeout: Option<Duration>,
+    max_response_size: usize,
+    rate_limit: Option<f64>,
+    proxy: Option<String>,
+    no_proxy: bool,
+    root_certificates: Vec<Vec<u8>>,
+    accept_invalid_certs: bool,
+    auth: Option<Auth>,
+    cache: Option<Arc<ResponseCache>>,
+    vcr: Option<Vcr>,
+    middleware: Middleware,
+}
+
+impl ApiClientBuilder {
+    /// Sets the User-Agent header sent with every request, overriding the one
+    /// set with [`set_default_user_agent`].
+    pub fn user_agent(mut self, user_agent: &str) -> Self {
+        self.user_agent = Some(user_agent.to_string());
+        self
+    }
+
+    /// Sets how many times a transient failure is retried. Defaults to 2;
+    /// 0 disables retries.
+    ///
+    /// Transient failures are timeouts, connection errors, 429 and 5xx
+    /// responses.
+    pub fn max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Sets the delay before the first retry, which doubles on every retry
+    /// after it. Defaults to 500ms.
+    ///
+    /// A `Retry-After` header sent by the registry takes precedence.
+    pub fn backoff(mut self, backoff: Duration) -> Self {
+        self.backoff = backoff;
+        self
+    }
+
+    /// Sets whether backoff delays are randomized, so that many clients
+    /// failing at once don't retry in lockstep. Defaults to `true`.
+    pub fn jitter(mut self, jitter: bool) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// Limits the built client to `requests_per_second` requests. A rate of
+    /// 0 or less removes the limit.
+    ///
+    /// The limit is the client's own and applies on top of the limit of
+    /// its host, which is shared by every client in the process; see
+    /// [`set_rate_limit`](crate::rate_limit::set_rate_limit).
+    pub fn rate_limit(mut self, requests_per_second: f64) -> Self {
+        self.rate_limit = Some(requests_per_second).filter(|rate| *rate > 0.0);
+        self
+    }
+
+    /// Sends requests through `client` instead of the [`default_client`].
+    ///
+    /// The connect timeout of an injected client is left as it was built.
+    pub fn client(mut self, client: Client) -> Self {
+        self.client = Some(client);
+        self
+    }
+
+    /// Sets how long to wait for a connection to be established. Defaults
+    /// to [`DEFAULT_CONNECT_TIMEOUT`].
+    ///
+    /// Has no effect on wasm32, where the browser manages connections.
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Sets how long to wait for each request to complete. Defaults to
+    /// [`DEFAULT_REQUEST_TIMEOUT`].
+    ///
+    /// A retried request gets the full timeout again on every attempt.
+    ///
+    /// Has no effect on wasm32, where the browser manages connections.
+    pub fn request_timeout(mut self, timeout: Duration) -> Self {
+        self.request_timeout = Some(timeout);
+        self
+    }
+
+    /// Lets requests take as long as they need, e.g. to download large
+    /// files; pair it with a [`read_timeout`](Self::read_timeout).
+    pub fn no_request_timeout(mut self) -> Self {
+        self.request_timeout = None;
+        self
+    }
+
+    /// Sets how long to wait for each read from a connection, which fails
+    /// stalled requests without limiting how long a request may take.
+    /// Defaults to none.
+    ///
+    /// Has no effect on wasm32, where the browser manages connections.
+    pub fn read_timeout(mut self, timeout: Duration) -> Self {
+        self.read_timeout = Some(timeout);
+        self
+    }
+
+    /// Sets the largest response body to read, in bytes. Larger responses
+    /// fail with [`SearchError::TooLarge`] as soon as the limit is passed,
+    /// without buffering the rest. Defaults to [`DEFAULT_MAX_RESPONSE_SIZE`].
+    pub fn max_response_size(mut self, bytes: usize) -> Self {
+        self.max_response_size = bytes;
+        self
+    }
+
+    /// Authenticates every request with `auth`.
+    pub fn auth(mut self, auth: Auth) -> Self {
+        self.auth = Some(auth);
+        self
+    }
+
+    /// Authenticates every request with an `Authorization: Bearer` token.
+    pub fn bearer_auth(self, token: &str) -> Self {
+        self.auth(Auth::Bearer(token.to_string()))
+    }
+
+    /// Authenticates every request with HTTP basic authentication.
+    pub fn basic_auth(self, username: &str, password: Option<&str>) -> Self {
+        self.auth(Auth::Basic {
+            username: username.to_string(),
+            password: password.map(str::to_string),
+        })
+    }
+
+    /// Answers repeated GET requests from `cache` instead of the one set
+    /// with [`set_response_cache`](crate::cache::set_response_cache).
+    ///
+    /// The cache can be shared with other clients by cloning the `Arc`.
+    pub fn cache(mut self, cache: Arc<ResponseCache>) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Passes every request through `middleware` before it is sent, e.g. to
+    /// add a tracing header.
+    ///
+    /// Middleware runs in the order it was added, after any added with
+    /// [`add_global_middleware`](crate::middleware::add_global_middleware).
+    pub fn with_middleware(
+        mut self,
+        middleware: impl Fn(RequestBuilder) -> RequestBuilder + Send + Sync + 'static,
+    ) -> Self {
+        self.middleware.add_request(Arc::new(middleware));
+        self
+    }
+
+    /// Shows every response to `hook` as it arrives, before its status is
+    /// checked, e.g. to count responses by status code.
+    pub fn on_response(mut self, hook: impl Fn(&Response) + Send + Sync + 'static) -> Self {
+        self.middleware.add_response(Arc::new(hook));
+        self
+    }
+
+    /// Records responses to, or replays them from, fixture files instead of
+    /// following the `SEARCH_LIBRARIES_VCR` environment variable.
+    pub fn vcr(mut self, vcr: Vcr) -> Self {
+        self.vcr = Some(vcr);
+        self
+    }
+
+    /// Sends every request through the proxy at `url`, e.g.
+    /// `http://proxy.corp:3128` or `socks5://127.0.0.1:1080`.
+    ///
+    /// Without it, the `HTTPS_PROXY`, `HTTP_PROXY`, `ALL_PROXY` and
+    /// `NO_PROXY` environment variables are honored.
+    ///
+    /// Has no effect on wasm32, where the browser manages connections.
+    pub fn proxy(mut self, url: &str) -> Self {
+        self.proxy = Some(url.to_string());
+        self
+    }
+
+    /// Connects directly, ignoring any proxy set in the environment.
+    ///
+    /// Has no effect on wasm32, where the browser manages connections.
+    pub fn no_proxy(mut self) -> Self {
+        self.no_proxy = true;
+        self
+    }
+
+    /// Trusts the PEM-encoded root certificate `pem` in addition to the
+    /// system's, e.g. the CA of a TLS-intercepting corporate proxy.
+    ///
+    /// Has no effect on wasm32, where the browser manages connections.
+    pub fn add_root_certificate(mut self, pem: &[u8]) -> Self {
+        self.root_certificates.push(pem.to_vec());
+        self
+    }
+
+    /// Accepts invalid TLS certificates, including self-signed and expired
+    /// ones.
+    ///
+    /// This makes every connection open to interception and should only be
+    /// used against a registry the network is trusted for. Prefer
+    /// [`add_root_certificate`](Self::add_root_certificate).
+    ///
+    /// Has no effect on wasm32, where the browser manages connections.
+    pub fn danger_accept_invalid_certs(mut self, accept: bool) -> Self {
+        self.accept_invalid_certs = accept;
+        self
+    }
+
+    /// Creates the configured `ApiClient`.
+    ///
+    /// # Returns
+    ///
+    /// Returns the client, or an error if the proxy URL or a root
+    /// certificate is invalid or its `reqwest::Client` could not be created.
+    pub fn build(self) -> Result<ApiClient, SearchError> {
+        let client = match &self.client {
+            Some(client) => client.clone(),
+            None if self.uses_default_client() => default_client(),
+            None => self.build_client()?,
+        };
+        Ok(self.with_client(client))
+    }
+
+    fn uses_default_client(&self) -> bool {
+        self.connect_timeout.is_none()
+            && self.read_timeout.is_none()
+            && self.proxy.is_none()
+            && !self.no_proxy
+            && self.root_certificates.is_empty()
+            && !self.accept_invalid_certs
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn build_client(&self) -> Result<Client, SearchError> {
+        let mut builder = Client::builder()
+            .connect_timeout(self.connect_timeout.unwrap_or(DEFAULT_CONNECT_TIMEOUT))
+            .danger_accept_invalid_certs(self.accept_invalid_certs);
+        if let Some(timeout) = self.read_timeout {
+            builder = builder.read_timeout(timeout);
+        }
+        if let Some(proxy) = &self.proxy {
+            builder = builder.proxy(Proxy::all(proxy)?);
+        }
+        if self.no_proxy {
+            builder = builder.no_proxy();
+        }
+        for pem in &self.root_certificates {
+            builder = builder.add_root_certificate(Certificate::from_pem(pem)?);
+        }
+        Ok(builder.build()?)
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn build_client(&self) -> Result<Client, SearchError> {
+        Ok(Client::builder().build()?)
+    }
+
+    fn with_client(self, client: Client) -> ApiClient {
+        ApiClient {
+            client,
+            search_url: self.search_url,
+            params: HashMap::new(),
+            headers: HashMap::new(),
+            user_agent: self.user_agent,
+            max_retries: self.max_retries,
+            backoff: self.backoff,
+            jitter: self.jitter,
+            request_timeout: self.request_timeout,
+            max_response_size: self.max_response_size,
+            rate_limiter: self.rate_limit.map(RateLimiter::new),
+            auth: self.auth,
+            cache: self.cache,
+            no_cache: false,
+            vcr: self.vcr.or_else(Vcr::from_env),
+            middleware: self.middleware,
+        }
+    }
+}
+
+impl ApiClient {
+    /// Creates a new `ApiClient` with the specified search URL and optional user agent.
+    ///
+    /// Without a user agent, the one set with [`set_default_user_agent`] is sent.
+    pub fn new(search_url: &str, user_agent: Option<&str>) -> Self {
+        let mut builder = Self::builder(search_url);
+        builder.user_agent = user_agent.map(str::to_string);
+        builder.with_client(default_client())
+    }
+
+    /// Returns a builder for an `ApiClient` with the specified search URL.
+    pub fn builder(search_url: &str) -> ApiClientBuilder {
+        ApiClientBuilder {
+            client: None,
+            search_url: search_url.to_string(),
+            user_agent: None,
+            max_retries: 2,
+            backoff: Duration::from_millis(500),
+            jitter: true,
+            connect_timeout: None,
+            read_timeout: None,
+            request_timeout: Some(DEFAULT_REQUEST_TIMEOUT),
+            max_response_size: DEFAULT_MAX_RESPONSE_SIZE,
+            rate_limit: None,
+            proxy: None,
+            no_proxy: false,
+            root_certificates: Vec::new(),
+            accept_invalid_certs: false,
+            auth: None,
+            cache: None,
+            vcr: None,
+            middleware: Middleware::default(),
+        }
+    }
+
+    /// Sets a query parameter for the API request.
+    pub fn set_param(mut self, key: &str, value: &str) -> Self {
+        self.params.insert(key.to_string(), value.to_string());
+        self
+    }
+
+    /// Sets a header for the API request.
+    pub fn set_header(mut self, key: &str, value: &str) -> Self {
+        self.headers.insert(key.to_string(), value.to_string());
+        self
+    }
+
+    /// Sets the credentials for the API request. They take precedence over
+    /// any set for its host with [`set_host_auth`](crate::auth::set_host_auth).
+    pub fn set_auth(mut self, auth: Option<Auth>) -> Self {
+        self.auth = auth;
+        self
+    }
+
+    /// Neither answers requests from the response cache nor stores their
+    /// responses in it, e.g. for short-lived tokens.
+    ///
+    /// Requests with credentials are never cached; see
+    /// [`ResponseCache`].
+    pub fn no_cache(mut self) -> Self {
+        self.no_cache = true;
+        self
+    }
+
+    /// Sends a GET request to the specified endpoint.
+    ///
+    /// Without a cache, the body is decoded from the bytes as they were read,
+    /// without first being copied into a string.
+    pub async fn get(&self, endpoint: &str) -> Result<Value, SearchError> {
+        if self.response_cache().is_none() {
+            let response = self.send(Method::GET, endpoint, None, None).await?;
+            let body = read_body(response, self.max_response_size).await?;
+            return Ok(serde_json::from_slice(&body)?);
+        }
+        let body = self.get_text(endpoint).await?;
+        Ok(serde_json::from_str(&body)?)
+    }
+
+    /// Sends a POST request with a JSON body to the specified endpoint.
+    pub async fn post(&self, endpoint: &str, body: &Value) -> Result<Value, SearchError> {
+        let response = self.send(Method::POST, endpoint, Some(body), None).await?;
+        let body = read_body(response, self.max_response_size).await?;
+        Ok(serde_json::from_slice(&body)?)
+    }
+
+    /// Sends a GET request to the specified endpoint and returns the raw body.
+    ///
+    /// Used for sources that only offer an HTML search page.
+    pub async fn get_text(&self, endpoint: &str) -> Result<String, SearchError> {
+        let Some(cache) = self.response_cache() else {
+            let response = self.send(Method::GET, endpoint, None, None).await?;
+            return read_text(response, self.max_response_size).await;
+        };
+
+        let key = self.cache_key(endpoint);
+        let cached = cache.get(&key);
+        if let Some(cached) = cached.as_ref().filter(|cached| cached.fresh) {
+            return Ok(cached.body.clone());
+        }
+
+        let response = self
+            .send(Method::GET, endpoint, None, cached.as_ref())
+            .await?;
+        if let (StatusCode::NOT_MODIFIED, Some(cached)) = (response.status(), cached) {
+            // The cached body is still current; store it again to renew it.
+            let body = cached.body.clone();
+            cache.insert(&key, cached);
+            return Ok(body);
+        }
+
+        let header = |name| {
+            response
+                .headers()
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string)
+        };
+        let etag = header(ETAG);
+        let last_modified = header(LAST_MODIFIED);
+        let body = read_text(response, self.max_response_size).await?;
+        cache.insert(
+            &key,
+            CachedResponse {
+                body: body.clone(),
+                etag,
+                last_modified,
+                fresh: true,
+            },
+        );
+        Ok(body)
+    }
+
+    /// Sends a GET request to the specified endpoint and returns the response
+    /// as-is, for callers that need its headers.
+    ///
+    /// Outside a tokio runtime, reading the body of the response needs the
+    /// `runtime-agnostic` feature and must be wrapped in [`compat`].
+    pub async fn get_response(&self, endpoint: &str) -> Result<Response, SearchError> {
+        self.send(Method::GET, endpoint, None, None).await
+    }
+
+    /// Returns the largest response body the client reads, in bytes, for
+    /// callers of [`get_response`](Self::get_response) to read the body
+    /// with [`read_body`].
+    pub(crate) fn max_response_size(&self) -> usize {
+        self.max_response_size
+    }
+
+    /// Sends a request, retrying transient failures with exponential backoff.
+    ///
+    /// With a `cached` response, the request is made conditional on its
+    /// validators and may be answered with `304 Not Modified`.
+    ///
+    /// With the `tracing` feature, the request runs in a `request` span that
+    /// records its URL, final status, retry count, and duration. The same
+    /// figures go to the recorder set with
+    /// [`set_metrics`](crate::metrics::set_metrics), if any.
+    async fn send(
+        &self,
+        method: Method,
+        endpoint: &str,
+        body: Option<&Value>,
+        cached: Option<&CachedResponse>,
+    ) -> Result<Response, SearchError> {
+        #[cfg(feature = "tracing")]
+        let span = tracing::debug_span!(
+            "request",
+            method = %method,
+            url = %self.url(endpoint),
+            status = tracing::field::Empty,
+            retries = tracing::field::Empty,
+            elapsed_ms = tracing::field::Empty,
+        );
+        let started = Instant::now();
+
+        let send = self.send_with_retries(method, endpoint, body, cached);
+        #[cfg(feature = "tracing")]
+        let send = send.instrument(span.clone());
+        let (result, retries) = send.await;
+        let latency = started.elapsed();
+        let status = result_status(&result);
+
+        #[cfg(feature = "tracing")]
+        {
+            if let Some(status) = status {
+                span.record("status", status.as_u16());
+            }
+            span.record("retries", retries);
+            span.record("elapsed_ms", latency.as_millis() as u64);
+            if let Err(error) = &result {
+                span.in_scope(|| tracing::debug!(%error, "request failed"));
+            }
+        }
+
+        if let Some(metrics) = metrics() {
+            let url = self.url(endpoint);
+            metrics.record_request(&RequestMetrics {
+                host: host(&url).as_deref().unwrap_or(&url),
+                status,
+                retries,
+                latency,
+                failed: result.is_err(),
+            });
+        }
+
+        result
+    }
+
+    /// Sends a request as [`send`](Self::send) does, also returning the
+    /// number of retries it took.
+    async fn send_with_retries(
+        &self,
+        method: Method,
+        endpoint: &str,
+        body: Option<&Value>,
+        cached: Option<&CachedResponse>,
+    ) -> (Result<Response, SearchError>, u32) {
+        let fixture_url = self.request_url(endpoint);
+        if let Some(vcr) = self
+            .vcr
+            .as_ref()
+            .filter(|vcr| vcr.mode() == VcrMode::Replay)
+        {
+            let result = match vcr.replay(&method, &fixture_url, body) {
+                Ok(response) => check_status(response).await,
+                Err(error) => Err(error),
+            };
+            return (result, 0);
+        }
+
+        let global_middleware = middleware::global();
+        let host = host(&self.url(endpoint));
+        let mut attempt = 0;
+        loop {
+            if let Some(host) = &host {
+                rate_limit::acquire(host).await;
+            }
+            if let Some(limiter) = &self.rate_limiter {
+                limiter.acquire().await;
+            }
+
+            let mut request = self.request(method.clone(), endpoint);
+            if let Some(body) = body {
+                request = request.json(body);
+            }
+            if let Some(etag) = cached.and_then(|cached| cached.etag.as_ref()) {
+                request = request.header(IF_NONE_MATCH, etag);
+            }
+            if let Some(date) = cached.and_then(|cached| cached.last_modified.as_ref()) {
+                request = request.header(IF_MODIFIED_SINCE, date);
+            }
+            let request = self.middleware.apply(global_middleware.apply(request));
+            // The headers the request goes out with, for a recorded fixture.
+            let request_headers = self
+                .vcr
+                .as_ref()
+                .and_then(|_| request.try_clone()?.build().ok())
+                .map(|request| request.headers().clone())
+                .unwrap_or_default();
+
+            let result = match compat(request.send()).await {
+                Ok(response) => {
+                    let response = match &self.vcr {
+                        Some(vcr) => {
+                            vcr.record(
+                                &method,
+                                &fixture_url,
+                                body,
+                                &request_headers,
+                                response,
+                                self.max_response_size,
+                            )
+                            .await
+                        }
+                        None => Ok(response),
+                    };
+                    match response {
+                        Ok(response) => {
+                            global_middleware.observe(&response);
+                            self.middleware.observe(&response);
+                            check_status(response).await
+                        }
+                        Err(error) => Err(error),
+                    }
+                }
+                Err(error) => Err(error.into()),
+            };
+            match result {
+                Err(error) if attempt < self.max_retries && is_transient(&error) => {
+                    let delay = match error {
+                        SearchError::RateLimited {
+                            retry_after: Some(retry_after),
+                        } => retry_after,
+                        _ => self.backoff_delay(attempt),
+                    };
+                    #[cfg(feature = "tracing")]
+                    tracing::debug!(%error, attempt, ?delay, "retrying request");
+                    Delay::new(delay).await;
+                    attempt += 1;
+                }
+                result => return (result, attempt),
+            }
+        }
+    }
+
+    /// Returns the delay before retry number `attempt` (0-based).
+    fn backoff_delay(&self, attempt: u32) -> Duration {
+        let delay = self
+            .backoff
+            .saturating_mul(2u32.saturating_pow(attempt))
+            .min(MAX_BACKOFF);
+        if self.jitter {
+            // Somewhere between half and all of the delay.
+            let random = RandomState::new().build_hasher().finish();
+            delay / 2 + delay.mul_f64((random % 1000) as f64 / 2000.0)
+        } else {
+            delay
+        }
+    }
+
+    fn url(&self, endpoint: &str) -> String {
+        join_url(&self.search_url, endpoint)
+    }
+
+    /// Returns the cache responses are kept in, or `None` if they aren't
+    /// cached: with [`no_cache`](Self::no_cache), and for requests with
+    /// credentials, which must neither be answered for other credentials nor
+    /// be written to disk.
+    fn response_cache(&self) -> Option<Arc<ResponseCache>> {
+        if self.no_cache || self.has_credentials() {
+            return None;
+        }
+        self.cache.clone().or_else(response_cache)
+    }
+
+    /// Returns whether requests carry credentials, in an `Authorization`
+    /// header or a query parameter such as `api_key`.
+    fn has_credentials(&self) -> bool {
+        self.auth().is_some()
+            || self
+                .headers
+                .keys()
+                .any(|key| key.eq_ignore_ascii_case("Authorization"))
+            || self.params.keys().any(|key| is_credential(key))
+    }
+
+    /// Returns the credentials requests are sent with: the client's own,
+    /// or those set for its host.
+    fn auth(&self) -> Option<Auth> {
+        self.auth
+            .clone()
+            .or_else(|| host(&self.search_url).and_then(|host| host_auth(&host)))
+    }
+
+    /// Returns the URL of a request with its query parameters
+    /// percent-encoded and in a stable order. Recorded fixtures are named
+    /// after it.
+    fn request_url(&self, endpoint: &str) -> String {
+        let mut params: Vec<_> = self.params.iter().collect();
+        params.sort();
+        let query: Vec<_> = params
+            .into_iter()
+            .map(|(key, value)| {
+                format!(
+                    "{}={}",
+                    encode_path_segment(key),
+                    encode_path_segment(value)
+                )
+            })
+            .collect();
+        format!("{}?{}", self.url(endpoint), query.join("&"))
+    }
+
+    /// Returns the key a response is cached under: the request URL followed
+    /// by the headers the request is sent with, such as `Accept`, which can
+    /// change the response. Requests with credentials are not cached.
+    fn cache_key(&self, endpoint: &str) -> String {
+        let mut headers: Vec<_> = self
+            .headers
+            .iter()
+            .map(|(name, value)| format!("\n{}: {}", name.to_ascii_lowercase(), value))
+            .collect();
+        headers.sort();
+        self.request_url(endpoint) + &headers.concat()
+    }
+
+    fn request(&self, method: Method, endpoint: &str) -> RequestBuilder {
+        let mut request = self
+            .client
+            .request(method, self.url(endpoint))
+            .query(&self.params);
+
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            if let Some(timeout) = self.request_timeout {
+                request = request.timeout(timeout);
+            }
+        }
+
+        let user_agent = match &self.user_agent {
+            Some(user_agent) => user_agent.clone(),
+            None => default_user_agent(),
+        };
+        request = request.header("User-Agent", user_agent);
+
+        for (key, value) in &self.headers {
+            request = request.header(key, value);
+        }
+
+        match self.auth() {
+            Some(auth) => auth.apply(request),
+            None => request,
+        }
+    }
+}
+
+/// Turns an unsuccessful response into the matching [`SearchError`].
+///
+/// 404 becomes [`SearchError::NotFound`], 429 (and 503 with a `Retry-After`
+/// header) becomes [`SearchError::RateLimited`], and anything else outside
+/// 2xx becomes [`SearchError::Status`]. 304 is passed through for
+/// conditional requests.
+pub(crate) async fn check_status(response: Response) -> Result<Response, SearchError> {
+    let status = response.status();
+    if status.is_success() || status == StatusCode::NOT_MODIFIED {
+        return Ok(response);
+    }
+
+    let retry_after = response
+        .headers()
+        .get(RETRY_AFTER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim().parse().ok())
+        .map(Duration::from_secs);
+    match status {
+        StatusCode::NOT_FOUND => return Err(SearchError::NotFound),
+        StatusCode::TOO_MANY_REQUESTS => return Err(SearchError::RateLimited { retry_after }),
+        StatusCode::SERVICE_UNAVAILABLE if retry_after.is_some() => {
+            return Err(SearchError::RateLimited { retry_after })
+        }
+        _ => {}
+    }
+
+    let body = read_prefix(response, ERROR_BODY_LIMIT)
+        .await
+        .unwrap_or_default();
+    let body = String::from_utf8_lossy(&body);
+    let errors = serde_json::from_str::<Value>(&body)
+        .ok()
+        .and_then(|json| json["errors"].as_array().cloned())
+        .into_iter()
+        .flatten()
+        .filter_map(|error| match error {
+            Value::String(message) => Some(message),
+            error => error["detail"]
+                .as_str()
+                .or_else(|| error["message"].as_str())
+                .map(str::to_string),
+        })
+        .collect();
+    let mut end = body.len().min(BODY_SNIPPET_LEN);
+    while !body.is_char_boundary(end) {
+        end -= 1;
+    }
+
+    Err(SearchError::Status {
+        status,
+        body: body[..end].to_string(),
+        errors,
+    })
+}
+
+/// Reads the body of `response` chunk by chunk, failing with
+/// [`SearchError::TooLarge`] as soon as it grows past `limit` bytes.
+pub(crate) async fn read_body(response: Response, limit: usize) -> Result<Vec<u8>, SearchError> {
+    if response
+        .content_length()
+        .is_some_and(|length| length > limit as u64)
+    {
+        return Err(SearchError::TooLarge { limit });
+    }
+    let body = read_prefix(response, limit.saturating_add(1)).await?;
+    if body.len() > limit {
+        return Err(SearchError::TooLarge { limit });
+    }
+    Ok(body)
+}
+
+/// Reads the body of `response` as [`read_body`] does and decodes it as
+/// UTF-8, replacing invalid sequences.
+pub(crate) async fn read_text(response: Response, limit: usize) -> Result<String, SearchError> {
+    let body = read_body(response, limit).await?;
+    Ok(match String::from_utf8(body) {
+        Ok(text) => text,
+        Err(error) => String::from_utf8_lossy(error.as_bytes()).into_owned(),
+    })
+}
+
+/// Reads at most `limit` bytes of the body of `response`, dropping the
+/// connection once they have arrived.
+async fn read_prefix(mut response: Response, limit: usize) -> Result<Vec<u8>, reqwest::Error> {
+    let mut body = Vec::new();
+    while body.len() < limit {
+        let Some(chunk) = compat(response.chunk()).await? else {
+            break;
+        };
+        let take = chunk.len().min(limit - body.len());
+        body.extend_from_slice(&chunk[..take]);
+    }
+    Ok(body)
+}
+
+/// Returns `true` for failures that may succeed if the request is repeated.
+pub(crate) fn is_transient(error: &SearchError) -> bool {
+    match error {
+        #[cfg(not(target_arch = "wasm32"))]
+        SearchError::Http(error) => error.is_timeout() || error.is_connect(),
+        #[cfg(target_arch = "wasm32")]
+        SearchError::Http(error) => error.is_timeout(),
+        SearchError::RateLimited { .. } => true,
+        SearchError::Status { status, .. } => status.is_server_error(),
+        _ => false,
+    }
+}
+
+/// Returns the HTTP status a request ended with, if it got a response.
+fn result_status(result: &Result<Response, SearchError>) -> Option<StatusCode> {
+    match result {
+        Ok(response) => Some(response.status()),
+        Err(SearchError::Http(error)) => error.status(),
+        Err(SearchError::NotFound) => Some(StatusCode::NOT_FOUND),
+        Err(SearchError::RateLimited { .. }) => Some(StatusCode::TOO_MANY_REQUESTS),
+        Err(SearchError::Status { status, .. }) => Some(*status),
+        Err(_) => None,
+    }
+}
+
+/// Resolves `endpoint` against `base` as a path below it.
+///
+/// `base` is treated as a directory whether or not it ends in a slash, and
+/// a leading slash on `endpoint` is ignored. An empty `endpoint` leaves
+/// `base` as-is, so a full endpoint URL can be used as the base.
+fn join_url(base: &str, endpoint: &str) -> String {
+    let endpoint = endpoint.trim_start_matches('/');
+    if endpoint.is_empty() {
+        return base.to_string();
+    }
+
+    let base = if base.ends_with('/') {
+        base.to_string()
+    } else {
+        format!("{}/", base)
+    };
+    // The `./` keeps a colon in the first segment from being read as a scheme.
+    match Url::parse(&base).and_then(|url| url.join(&format!("./{}", endpoint))) {
+        Ok(url) => url.into(),
+        Err(_) => format!("{}{}", base, endpoint),
+    }
+}
+
+/// Returns whether a query parameter or field named `name` carries
+/// credentials, such as `api_key`, `Client-Secret`, or `npm_token`,
+/// ignoring case.
+pub(crate) fn is_credential(name: &str) -> bool {
+    let name = name.to_ascii_lowercase().replace('-', "_");
+    CREDENTIAL_NAMES.contains(&name.as_str())
+        || ["_key", "_password", "_secret", "_token"]
+            .iter()
+            .any(|suffix| name.ends_with(suffix))
+}
+
+/// Percent-encodes each `/`-separated segment of `path` as
+/// [`encode_path_segment`] does, keeping the slashes, e.g. for
+/// `vendor/name` or `@scope/pkg` where the API expects both segments.
+pub(crate) fn encode_path(path: &str) -> String {
+    path.split('/')
+        .map(encode_path_segment)
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Percent-encodes `segment` for use as one path segment of an endpoint,
+/// e.g. `@scope/pkg` becomes `@scope%2Fpkg`.
+///
+/// # Arguments
+///
+/// * `segment` - The path parameter, such as a package name.
+///
+/// # Returns
+///
+/// Returns the segment with every byte other than ASCII letters, digits,
+/// `-`, `.`, `_`, `~`, `@`, and `:` percent-encoded.
+pub fn encode_path_segment(segment: &str) -> String {
+    let mut encoded = String::with_capacity(segment.len());
+    for byte in segment.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'@' | b':' => {
+                encoded.push(byte as char)
+            }
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+/// Returns the host of `url`, e.g. `crates.io`.
+fn host(url: &str) -> Option<String> {
+    Url::parse(url).ok()?.host_str().map(str::to_string)
+}
+
+/// Runs a future of `reqwest`, which needs a tokio reactor, so that it also
+/// completes on other executors such as async-std or smol.
+///
+/// With the `runtime-agnostic` feature, the future is driven by a
+/// background tokio runtime whenever it is not polled inside one already.
+/// Without it, the future is awaited as-is.
+pub async fn compat<F: Future>(future: F) -> F::Output {
+    #[cfg(feature = "runtime-agnostic")]
+    {
+        async_compat::Compat::new(future).await
+    }
+    #[cfg(not(feature = "runtime-agnostic"))]
+    {
+        future.await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn joins_endpoints_below_the_base() {
+        let crates = "https://crates.io/api/v1/crates";
+        assert_eq!(join_url("https://crates.io/api/v1/", "crates"), crates);
+        assert_eq!(join_url("https://crates.io/api/v1", "crates"), crates);
+        assert_eq!(join_url("https://crates.io/api/v1/", "/crates"), crates);
+    }
+
+    #[test]
+    fn keeps_the_base_for_an_empty_endpoint() {
+        assert_eq!(
+            join_url("https://api.npms.io/v2/search/", ""),
+            "https://api.npms.io/v2/search/"
+        );
+    }
+
+    #[test]
+    fn joins_endpoints_with_a_colon_in_the_first_segment() {
+        assert_eq!(
+            join_url("https://example.com/api/", "pkg:npm/left-pad"),
+            "https://example.com/api/pkg:npm/left-pad"
+        );
+    }
+
+    #[test]
+    fn encodes_path_segments() {
+        assert_eq!(encode_path_segment("@scope/pkg"), "@scope%2Fpkg");
+        assert_eq!(encode_path_segment("a b?c#d"), "a%20b%3Fc%23d");
+        assert_eq!(encode_path_segment("é"), "%C3%A9");
+        assert_eq!(encode_path_segment("v1.0.0-beta_1~x"), "v1.0.0-beta_1~x");
+    }
+
+    fn response(status: u16, headers: &[(&str, &str)], body: impl Into<reqwest::Body>) -> Response {
+        let mut response = http::Response::builder().status(status);
+        for (name, value) in headers {
+            response = response.header(*name, *value);
+        }
+        Response::from(response.body(body).unwrap())
+    }
+
+    #[tokio::test]
+    async fn passes_successful_and_not_modified_responses_through() {
+        for status in [200, 204, 304] {
+            let checked = check_status(response(status, &[], "")).await.unwrap();
+            assert_eq!(checked.status().as_u16(), status);
+        }
+    }
+
+    #[tokio::test]
+    async fn maps_not_found_and_rate_limits() {
+        assert!(matches!(
+            check_status(response(404, &[], "")).await,
+            Err(SearchError::NotFound)
+        ));
+        assert!(matches!(
+            check_status(response(429, &[("Retry-After", "7")], "")).await,
+            Err(SearchError::RateLimited {
+                retry_after: Some(delay)
+            }) if delay == Duration::from_secs(7)
+        ));
+        assert!(matches!(
+            check_status(response(503, &[("Retry-After", "2")], "")).await,
+            Err(SearchError::RateLimited { .. })
+        ));
+        assert!(matches!(
+            check_status(response(503, &[], "")).await,
+            Err(SearchError::Status { status, .. }) if status == StatusCode::SERVICE_UNAVAILABLE
+        ));
+    }
+
+    #[tokio::test]
+    async fn keeps_the_messages_of_json_error_responses() {
+        let body = r#"{"errors": [{"detail": "crate `x` does not exist"}, "bad query"]}"#;
+        match check_status(response(400, &[], body)).await {
+            Err(SearchError::Status {
+                status,
+                body: snippet,
+                errors,
+            }) => {
+                assert_eq!(status, StatusCode::BAD_REQUEST);
+                assert_eq!(snippet, body);
+                assert_eq!(errors, ["crate `x` does not exist", "bad query"]);
+            }
+            result => panic!("unexpected {:?}", result.map(|response| response.status())),
+        }
+    }
+
+    #[tokio::test]
+    async fn cuts_long_error_bodies_on_a_character_boundary() {
+        let body = "é".repeat(BODY_SNIPPET_LEN);
+        match check_status(response(500, &[], body.clone())).await {
+            Err(SearchError::Status { body: snippet, .. }) => {
+                assert!(snippet.len() <= BODY_SNIPPET_LEN);
+                assert!(body.starts_with(&snippet));
+            }
+            result => panic!("unexpected {:?}", result.map(|response| response.status())),
+        }
+    }
+
+    fn backoff_client(jitter: bool) -> ApiClient {
+        ApiClient::builder("https://example.com/")
+            .backoff(Duration::from_millis(100))
+            .jitter(jitter)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn doubles_the_backoff_up_to_a_limit() {
+        let client = backoff_client(false);
+        let delays: Vec<_> = (0..4)
+            .map(|attempt| client.backoff_delay(attempt))
+            .collect();
+        assert_eq!(delays, [100, 200, 400, 800].map(Duration::from_millis));
+        assert_eq!(client.backoff_delay(20), MAX_BACKOFF);
+        assert_eq!(client.backoff_delay(u32::MAX), MAX_BACKOFF);
+    }
+
+    #[test]
+    fn jitters_between_half_and_all_of_the_backoff() {
+        let client = backoff_client(true);
+        for attempt in 0..8 {
+            let delay = client.backoff_delay(attempt);
+            let full = backoff_client(false).backoff_delay(attempt);
+            assert!(
+                delay >= full / 2 && delay <= full,
+                "{:?} of {:?}",
+                delay,
+                full
+            );
+        }
+    }
+
+    #[test]
+    fn retries_only_transient_failures() {
+        let status = |status| SearchError::Status {
+            status,
+            body: String::new(),
+            errors: Vec::new(),
+        };
+        assert!(is_transient(&SearchError::RateLimited {
+            retry_after: None
+        }));
+        assert!(is_transient(&status(StatusCode::BAD_GATEWAY)));
+        assert!(!is_transient(&status(StatusCode::BAD_REQUEST)));
+        assert!(!is_transient(&SearchError::NotFound));
+    }
+
+    #[test]
+    fn encodes_query_parameters_in_cache_keys() {
+        let client = ApiClient::new("https://example.com/", None)
+            .set_param("q", "a&b=c")
+            .set_param("tags[0]", "x y");
+        assert_eq!(
+            client.cache_key("search"),
+            "https://example.com/search?q=a%26b%3Dc&tags%5B0%5D=x%20y"
+        );
+    }
+
+    #[test]
+    fn keys_responses_by_their_request_headers() {
+        let client = ApiClient::new("https://example.com/", None);
+        let plain = client.cache_key("manifest");
+        let client = client.set_header("Accept", "application/json");
+        assert_eq!(
+            client.cache_key("manifest"),
+            format!("{}\naccept: application/json", plain)
+        );
+        assert_eq!(client.request_url("manifest"), plain);
+    }
+
+    #[test]
+    fn encodes_paths_segment_by_segment() {
+        assert_eq!(encode_path("monolog/monolog"), "monolog/monolog");
+        assert_eq!(encode_path("@scope/my pkg"), "@scope/my%20pkg");
+        assert_eq!(encode_path("a?b/c#d"), "a%3Fb/c%23d");
+    }
+}
@@ -0,0 +1,56 @@
+use crate::error::SearchError;
+use crate::registry::{collect_hits, text, Registry, RegistryError, SearchHit, SearchQuery};
+use crate::ApiClient;
+use async_trait::async_trait;
+use serde_json::Value;
+
+/// Searches for Clojure libraries on Clojars.
+///
+/// # Arguments
+///
+/// * `query` - The search query (optional).
+/// * `page` - The 1-based result page; Clojars returns 24 results per page.
+///
+/// # Returns
+///
+/// Returns the search results as a `serde_json::Value` or an error.
+pub async fn search_clojars(query: Option<&str>, page: u32) -> Result<Value, SearchError> {
+    ApiClient::new("https://clojars.org/", None)
+        .set_param("q", query.unwrap_or(""))
+        .set_param("format", "json")
+        .set_param("page", &page.to_string())
+        .get("search")
+        .await
+}
+
+/// The Clojars repository.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Clojars;
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl Registry for Clojars {
+    fn name(&self) -> &str {
+        "clojars"
+    }
+
+    async fn search(&self, query: &SearchQuery) -> Result<Vec<SearchHit>, RegistryError> {
+        let results = search_clojars(Some(&query.text), 1)
+            .await
+            .map_err(|error| RegistryError::new(self.name(), error))?;
+        Ok(collect_hits(&results["results"], query.limit, |jar| {
+            SearchHit {
+                name: text(&jar["group_name"])
+                    .zip(text(&jar["jar_name"]))
+                    .map(|(group, jar)| format!("{}/{}", group, jar))
+                    .unwrap_or_default(),
+                version: text(&jar["version"]),
+                description: text(&jar["description"]),
+                homepage: None,
+                downloads: jar["downloads"].as_u64(),
+                source: self.name().to_string(),
+                ..Default::default()
+            }
+        }))
+    }
+}
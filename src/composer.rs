@@ -0,0 +1,310 @@
+use crate::client::encode_path;
+use crate::error::SearchError;
+use crate::registry::{Registry, RegistryConfig, RegistryError, SearchHit, SearchQuery};
+use crate::results::SearchResults;
+use crate::ApiClient;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+const PACKAGIST: &str = "https://packagist.org/";
+
+/// A package returned by Packagist search.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ComposerHit {
+    pub name: String,
+    pub description: Option<String>,
+    /// The package page on packagist.org.
+    pub url: String,
+    pub repository: Option<String>,
+    #[serde(default)]
+    pub downloads: u64,
+    #[serde(default)]
+    pub favers: u64,
+}
+
+impl From<ComposerHit> for SearchHit {
+    fn from(package: ComposerHit) -> Self {
+        Self {
+            name: package.name,
+            version: None,
+            description: package.description,
+            homepage: Some(package.url),
+            repository: package.repository,
+            downloads: Some(package.downloads),
+            license: None,
+            updated: None,
+            deprecated: false,
+            source: "composer".to_string(),
+        }
+    }
+}
+
+/// Common Composer package types.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PackageType {
+    Library,
+    Project,
+    Metapackage,
+    ComposerPlugin,
+    SymfonyBundle,
+    WordpressPlugin,
+    WordpressTheme,
+    DrupalModule,
+    /// Any other installer type, e.g. `magento2-module`.
+    Other(String),
+}
+
+impl PackageType {
+    /// Returns the value Packagist expects for the `type` parameter.
+    pub fn as_str(&self) -> &str {
+        match self {
+            PackageType::Library => "library",
+            PackageType::Project => "project",
+            PackageType::Metapackage => "metapackage",
+            PackageType::ComposerPlugin => "composer-plugin",
+            PackageType::SymfonyBundle => "symfony-bundle",
+            PackageType::WordpressPlugin => "wordpress-plugin",
+            PackageType::WordpressTheme => "wordpress-theme",
+            PackageType::DrupalModule => "drupal-module",
+            PackageType::Other(package_type) => package_type,
+        }
+    }
+}
+
+/// Filters for Packagist search.
+#[derive(Debug, Clone, Default)]
+pub struct ComposerSearchOptions {
+    package_type: Option<PackageType>,
+    tags: Vec<String>,
+    page: Option<u32>,
+    per_page: Option<u32>,
+}
+
+impl ComposerSearchOptions {
+    /// Creates options that do not filter results.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Restricts results to packages of this type.
+    pub fn package_type(mut self, package_type: PackageType) -> Self {
+        self.package_type = Some(package_type);
+        self
+    }
+
+    /// Restricts results to packages tagged with this keyword.
+    pub fn tag(mut self, tag: &str) -> Self {
+        self.tags.push(tag.to_string());
+        self
+    }
+
+    /// Sets the 1-based result page.
+    pub fn page(mut self, page: u32) -> Self {
+        self.page = Some(page);
+        self
+    }
+
+    /// Sets the number of results per page (25 by default).
+    pub fn per_page(mut self, per_page: u32) -> Self {
+        self.per_page = Some(per_page);
+        self
+    }
+}
+
+/// Searches for Composer packages on Packagist.
+///
+/// # Arguments
+///
+/// * `query` - The search query (optional).
+/// * `options` - The package type and tag filters.
+///
+/// # Returns
+///
+/// Returns the matching packages or an error.
+pub async fn search_composer(
+    query: Option<&str>,
+    options: &ComposerSearchOptions,
+) -> Result<SearchResults<ComposerHit>, SearchError> {
+    search_composer_with(query, options, &RegistryConfig::new()).await
+}
+
+/// Searches for Composer packages on a Packagist-compatible repository,
+/// such as a self-hosted Private Packagist.
+///
+/// # Arguments
+///
+/// * `query` - The search query (optional).
+/// * `options` - The package type and tag filters, and the result page.
+/// * `config` - The repository URL and credentials; packagist.org is used
+///   when no URL is set.
+///
+/// # Returns
+///
+/// Returns the matching packages or an error.
+pub async fn search_composer_with(
+    query: Option<&str>,
+    options: &ComposerSearchOptions,
+    config: &RegistryConfig,
+) -> Result<SearchResults<ComposerHit>, SearchError> {
+    let mut client = config
+        .client(PACKAGIST, None)
+        .set_param("q", query.unwrap_or(""))
+        .set_param("per_page", &options.per_page.unwrap_or(25).to_string());
+
+    if let Some(page) = options.page {
+        client = client.set_param("page", &page.to_string());
+    }
+    if let Some(package_type) = &options.package_type {
+        client = client.set_param("type", package_type.as_str());
+    }
+    for (index, tag) in options.tags.iter().enumerate() {
+        client = client.set_param(&format!("tags[{}]", index), tag);
+    }
+
+    let response = client.get("search.json").await?;
+    SearchResults::from_raw(response, "/results")
+}
+
+/// Fetches the metadata of every tagged release of a package.
+///
+/// Packagist minifies this metadata, listing only what changed from one
+/// release to the next; the releases are returned expanded, each with all
+/// of its fields.
+///
+/// # Arguments
+///
+/// * `name` - The package name, e.g. `monolog/monolog`.
+///
+/// # Returns
+///
+/// Returns the package metadata as a `serde_json::Value` or an error.
+pub async fn get_package(name: &str) -> Result<Value, SearchError> {
+    let mut package = ApiClient::new("https://repo.packagist.org/p2/", None)
+        .get(&metadata_endpoint(name))
+        .await?;
+    expand_minified(&mut package);
+    Ok(package)
+}
+
+/// Expands metadata in the `composer/2.0` minified format in place.
+///
+/// Every release after the first lists only the fields that changed: the
+/// others carry over from the release before it, and a field set to
+/// `"__unset"` is removed.
+fn expand_minified(package: &mut Value) {
+    if package.get("minified").and_then(Value::as_str) != Some("composer/2.0") {
+        return;
+    }
+    if let Some(object) = package.as_object_mut() {
+        object.remove("minified");
+    }
+    let Some(packages) = package.get_mut("packages").and_then(Value::as_object_mut) else {
+        return;
+    };
+    for releases in packages.values_mut().filter_map(Value::as_array_mut) {
+        let mut previous = serde_json::Map::new();
+        for release in releases.iter_mut() {
+            let Some(changes) = release.as_object() else {
+                continue;
+            };
+            for (key, value) in changes {
+                if value == "__unset" {
+                    previous.remove(key);
+                } else {
+                    previous.insert(key.clone(), value.clone());
+                }
+            }
+            *release = Value::Object(previous.clone());
+        }
+    }
+}
+
+/// Fetches the download statistics of a package.
+///
+/// # Arguments
+///
+/// * `name` - The package name, e.g. `monolog/monolog`.
+///
+/// # Returns
+///
+/// Returns the total, monthly, and daily download counts as a
+/// `serde_json::Value` or an error.
+pub async fn package_stats(name: &str) -> Result<Value, SearchError> {
+    ApiClient::new(PACKAGIST, None)
+        .get(&format!("packages/{}/stats.json", encode_path(name)))
+        .await
+}
+
+/// Returns the metadata endpoint of a package, keeping the slash between
+/// vendor and name.
+fn metadata_endpoint(name: &str) -> String {
+    format!("{}.json", encode_path(name))
+}
+
+/// The Packagist Composer registry.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Packagist;
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl Registry for Packagist {
+    fn name(&self) -> &str {
+        "composer"
+    }
+
+    async fn search(&self, query: &SearchQuery) -> Result<Vec<SearchHit>, RegistryError> {
+        // Packagist serves at most 100 packages a page.
+        let options = ComposerSearchOptions::new().per_page(query.limit.min(100) as u32);
+        let results = search_composer(Some(&query.text), &options)
+            .await
+            .map_err(|error| RegistryError::new(self.name(), error))?;
+        Ok(results
+            .into_iter()
+            .take(query.limit)
+            .map(SearchHit::from)
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encodes_package_names_in_endpoints() {
+        assert_eq!(metadata_endpoint("monolog/monolog"), "monolog/monolog.json");
+        assert_eq!(metadata_endpoint("acme/a?b"), "acme/a%3Fb.json");
+    }
+
+    #[test]
+    fn expands_minified_metadata() {
+        let mut package = serde_json::json!({
+            "minified": "composer/2.0",
+            "packages": {"acme/lib": [
+                {"name": "acme/lib", "version": "2.0.0", "require": {"php": ">=8.1"}, "suggest": {"ext-json": "*"}},
+                {"version": "1.1.0", "require": {"php": ">=7.4"}},
+                {"version": "1.0.0", "suggest": "__unset"},
+            ]},
+        });
+        expand_minified(&mut package);
+        assert_eq!(
+            package,
+            serde_json::json!({
+                "packages": {"acme/lib": [
+                    {"name": "acme/lib", "version": "2.0.0", "require": {"php": ">=8.1"}, "suggest": {"ext-json": "*"}},
+                    {"name": "acme/lib", "version": "1.1.0", "require": {"php": ">=7.4"}, "suggest": {"ext-json": "*"}},
+                    {"name": "acme/lib", "version": "1.0.0", "require": {"php": ">=7.4"}},
+                ]},
+            })
+        );
+    }
+
+    #[test]
+    fn leaves_expanded_metadata_alone() {
+        let mut package = serde_json::json!({"packages": {"acme/lib": [{"version": "1.0.0"}]}});
+        let expected = package.clone();
+        expand_minified(&mut package);
+        assert_eq!(package, expected);
+    }
+}
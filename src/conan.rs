@@ -0,0 +1,73 @@
+use crate::error::SearchError;
+use crate::matching::compare_versions;
+use crate::registry::{collect_hits, text, Registry, RegistryError, SearchHit, SearchQuery};
+use crate::ApiClient;
+use async_trait::async_trait;
+use serde_json::{json, Value};
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+
+/// Searches for C and C++ recipes on ConanCenter.
+///
+/// The Conan REST API returns one `name/version` reference per published
+/// version, so references are grouped by recipe and only the latest version
+/// of each is kept.
+///
+/// # Arguments
+///
+/// * `query` - The search query (optional).
+///
+/// # Returns
+///
+/// Returns the matching recipes as a `serde_json::Value` array of
+/// `name`/`version` objects or an error.
+pub async fn search_conan(query: Option<&str>) -> Result<Value, SearchError> {
+    let response = ApiClient::new("https://center2.conan.io/v2/conans/", None)
+        .set_param("q", &format!("*{}*", query.unwrap_or("")))
+        .get("search")
+        .await?;
+
+    let mut latest: BTreeMap<&str, &str> = BTreeMap::new();
+    for reference in response["results"].as_array().into_iter().flatten() {
+        let reference = reference.as_str().unwrap_or_default();
+        let reference = reference.split('@').next().unwrap_or_default();
+        if let Some((name, version)) = reference.split_once('/') {
+            let entry = latest.entry(name).or_insert(version);
+            if compare_versions(version, entry) == Ordering::Greater {
+                *entry = version;
+            }
+        }
+    }
+
+    Ok(latest
+        .into_iter()
+        .map(|(name, version)| json!({ "name": name, "version": version }))
+        .collect())
+}
+
+/// The ConanCenter recipe index.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ConanCenter;
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl Registry for ConanCenter {
+    fn name(&self) -> &str {
+        "conan"
+    }
+
+    async fn search(&self, query: &SearchQuery) -> Result<Vec<SearchHit>, RegistryError> {
+        let results = search_conan(Some(&query.text))
+            .await
+            .map_err(|error| RegistryError::new(self.name(), error))?;
+        Ok(collect_hits(&results, query.limit, |recipe| SearchHit {
+            name: text(&recipe["name"]).unwrap_or_default(),
+            version: text(&recipe["version"]),
+            description: None,
+            homepage: text(&recipe["name"])
+                .map(|name| format!("https://conan.io/center/recipes/{}", name)),
+            source: self.name().to_string(),
+            ..Default::default()
+        }))
+    }
+}
@@ -0,0 +1,275 @@
+//! The configuration file of the `search` binary.
+//!
+//! The file lives at `$XDG_CONFIG_HOME/search-libraries/config.toml`,
+//! falling back to `~/.config/search-libraries/config.toml`, or wherever
+//! `SEARCH_LIBRARIES_CONFIG` points. For example:
+//!
+//! ```toml
+//! source = "crates"
+//! limit = 10
+//! format = "table"
+//!
+//! [registries.npm]
+//! url = "https://npm.example.com/"
+//! token = "npm_..."
+//!
+//! [auth]
+//! "api.github.com" = "ghp_..."
+//!
+//! [cache]
+//! ttl = 600
+//! on_disk = true
+//! ```
+//!
+//! Command-line flags override the file, and the `SEARCH_LIBRARIES_SOURCE`,
+//! `SEARCH_LIBRARIES_LIMIT`, and `SEARCH_LIBRARIES_FORMAT` environment
+//! variables override both.
+
+use crate::Format;
+use clap::ValueEnum;
+use search_libraries::auth::{set_host_auth, Auth};
+use search_libraries::cache::{set_response_cache, ResponseCache};
+use search_libraries::registry::RegistryConfig;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::error::Error;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// The settings read from the configuration file.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// The registry searched when none is given.
+    pub source: Option<String>,
+    /// The number of results per page.
+    pub limit: Option<u32>,
+    /// How search results are printed.
+    pub format: Option<Format>,
+    /// Registry URLs and tokens, keyed by source, for sources that can be
+    /// self-hosted (crates, npm, composer, nuget, gems).
+    pub registries: HashMap<String, RegistrySettings>,
+    /// Bearer tokens sent to every request to a host, keyed by host.
+    pub auth: HashMap<String, String>,
+    /// Response caching; responses are not cached without this section.
+    pub cache: Option<CacheSettings>,
+}
+
+/// Where and how to reach a self-hosted registry.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RegistrySettings {
+    pub url: Option<String>,
+    /// A bearer token sent with every request to the registry.
+    pub token: Option<String>,
+}
+
+/// How responses are cached.
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CacheSettings {
+    /// How long a response is fresh, in seconds.
+    pub ttl: u64,
+    /// How many responses are kept in memory.
+    pub capacity: Option<usize>,
+    /// Whether responses are also stored on disk, so they outlive the process.
+    pub on_disk: bool,
+}
+
+impl Default for CacheSettings {
+    fn default() -> Self {
+        Self {
+            ttl: 300,
+            capacity: None,
+            on_disk: false,
+        }
+    }
+}
+
+impl Config {
+    /// Returns the path of the configuration file.
+    pub fn path() -> Option<PathBuf> {
+        if let Some(path) = std::env::var_os("SEARCH_LIBRARIES_CONFIG") {
+            return Some(PathBuf::from(path));
+        }
+        std::env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+            .or_else(|| std::env::var_os("APPDATA").map(PathBuf::from))
+            .map(|dir| dir.join("search-libraries").join("config.toml"))
+    }
+
+    /// Reads the configuration file, or returns the defaults if there is
+    /// none.
+    pub fn load() -> Result<Self, Box<dyn Error>> {
+        let Some(path) = Self::path() else {
+            return Ok(Self::default());
+        };
+        match fs::read_to_string(&path) {
+            Ok(contents) => toml::from_str(&contents)
+                .map_err(|error| format!("{}: {}", path.display(), error).into()),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(error) => Err(format!("{}: {}", path.display(), error).into()),
+        }
+    }
+
+    /// Returns the default source, from the environment or the file.
+    pub fn source(&self) -> Option<String> {
+        std::env::var("SEARCH_LIBRARIES_SOURCE")
+            .ok()
+            .or_else(|| self.source.clone())
+    }
+
+    /// Returns the result limit, from the environment, the `flag`, or the
+    /// file, in that order.
+    pub fn limit(&self, flag: Option<u32>) -> Result<Option<u32>, Box<dyn Error>> {
+        match std::env::var("SEARCH_LIBRARIES_LIMIT") {
+            Ok(limit) => {
+                Ok(Some(limit.parse().map_err(|error| {
+                    format!("SEARCH_LIBRARIES_LIMIT: {}", error)
+                })?))
+            }
+            Err(_) => Ok(flag.or(self.limit)),
+        }
+    }
+
+    /// Returns the output format, from the environment, the `flag`, or the
+    /// file, in that order.
+    pub fn format(&self, flag: Option<Format>) -> Result<Option<Format>, Box<dyn Error>> {
+        match std::env::var("SEARCH_LIBRARIES_FORMAT") {
+            Ok(format) => {
+                Ok(Some(Format::from_str(&format, true).map_err(|error| {
+                    format!("SEARCH_LIBRARIES_FORMAT: {}", error)
+                })?))
+            }
+            Err(_) => Ok(flag.or(self.format)),
+        }
+    }
+
+    /// Returns the URL and credentials configured for `source`.
+    pub fn registry(&self, source: &str) -> RegistryConfig {
+        let mut config = RegistryConfig::new();
+        if let Some(settings) = self.registries.get(source) {
+            if let Some(url) = &settings.url {
+                config = config.base_url(url);
+            }
+            if let Some(token) = &settings.token {
+                config = config.auth(Auth::Bearer(token.clone()));
+            }
+        }
+        config
+    }
+
+    /// Applies the host credentials and cache settings to every request the
+    /// process makes.
+    pub fn apply(&self) {
+        for (host, token) in &self.auth {
+            set_host_auth(host, Auth::Bearer(token.clone()));
+        }
+        if let Some(settings) = &self.cache {
+            let mut cache = ResponseCache::new(Duration::from_secs(settings.ttl));
+            if let Some(capacity) = settings.capacity {
+                cache = cache.capacity(capacity);
+            }
+            if settings.on_disk {
+                cache = cache.on_disk();
+            }
+            set_response_cache(Some(cache));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = r#"
+        source = "crates"
+        limit = 10
+        format = "table"
+
+        [registries.npm]
+        url = "https://npm.example.com/"
+        token = "npm_secret"
+
+        [auth]
+        "api.github.com" = "ghp_secret"
+
+        [cache]
+        on_disk = true
+    "#;
+
+    #[test]
+    fn reads_every_setting() {
+        let config: Config = toml::from_str(EXAMPLE).unwrap();
+        assert_eq!(config.source.as_deref(), Some("crates"));
+        assert_eq!(config.limit, Some(10));
+        assert_eq!(config.format, Some(Format::Table));
+        assert_eq!(
+            config.registry("npm"),
+            RegistryConfig::new()
+                .base_url("https://npm.example.com/")
+                .auth(Auth::Bearer("npm_secret".to_string()))
+        );
+        assert_eq!(config.registry("crates"), RegistryConfig::new());
+        assert_eq!(config.auth["api.github.com"], "ghp_secret");
+        let cache = config.cache.unwrap();
+        assert_eq!(
+            (cache.ttl, cache.capacity, cache.on_disk),
+            (300, None, true)
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_settings() {
+        assert!(toml::from_str::<Config>("sauce = \"crates\"").is_err());
+        assert!(toml::from_str::<Config>("[cache]\nttl = 60\nsize = 10").is_err());
+    }
+
+    // The environment is shared by every test, so everything that reads it
+    // is checked in this one test.
+    #[test]
+    fn lets_the_environment_override_flags_and_flags_the_file() {
+        let path = std::env::temp_dir().join(format!(
+            "search-libraries-config-{}.toml",
+            std::process::id()
+        ));
+        fs::write(&path, EXAMPLE).unwrap();
+        std::env::set_var("SEARCH_LIBRARIES_CONFIG", &path);
+        let config = Config::load();
+        fs::remove_file(&path).unwrap();
+        let config = config.unwrap();
+
+        assert_eq!(config.limit(None).unwrap(), Some(10));
+        assert_eq!(config.limit(Some(5)).unwrap(), Some(5));
+        assert_eq!(config.format(None).unwrap(), Some(Format::Table));
+        assert_eq!(config.format(Some(Format::Csv)).unwrap(), Some(Format::Csv));
+        assert_eq!(config.source().as_deref(), Some("crates"));
+
+        std::env::set_var("SEARCH_LIBRARIES_LIMIT", "3");
+        std::env::set_var("SEARCH_LIBRARIES_FORMAT", "JSON");
+        std::env::set_var("SEARCH_LIBRARIES_SOURCE", "npm");
+        assert_eq!(config.limit(Some(5)).unwrap(), Some(3));
+        assert_eq!(
+            config.format(Some(Format::Csv)).unwrap(),
+            Some(Format::Json)
+        );
+        assert_eq!(config.source().as_deref(), Some("npm"));
+        std::env::set_var("SEARCH_LIBRARIES_LIMIT", "ten");
+        assert!(config.limit(None).is_err());
+
+        for name in [
+            "SEARCH_LIBRARIES_LIMIT",
+            "SEARCH_LIBRARIES_FORMAT",
+            "SEARCH_LIBRARIES_SOURCE",
+        ] {
+            std::env::remove_var(name);
+        }
+        // A missing file leaves the defaults.
+        let config = Config::load().unwrap();
+        assert_eq!(config.limit, None);
+        std::env::remove_var("SEARCH_LIBRARIES_CONFIG");
+    }
+}
@@ -0,0 +1,52 @@
+use crate::error::SearchError;
+use crate::registry::{collect_hits, text, Registry, RegistryError, SearchHit, SearchQuery};
+use crate::ApiClient;
+use async_trait::async_trait;
+use serde_json::Value;
+
+/// Searches for Perl modules and distributions on MetaCPAN.
+///
+/// # Arguments
+///
+/// * `query` - The search query (optional).
+/// * `size` - The maximum number of results to return.
+///
+/// # Returns
+///
+/// Returns the search results as a `serde_json::Value` or an error.
+pub async fn search_cpan(query: Option<&str>, size: u32) -> Result<Value, SearchError> {
+    ApiClient::new("https://fastapi.metacpan.org/v1/search/", None)
+        .set_param("q", query.unwrap_or(""))
+        .set_param("size", &size.to_string())
+        .get("web")
+        .await
+}
+
+/// The Comprehensive Perl Archive Network as indexed by MetaCPAN.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MetaCpan;
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl Registry for MetaCpan {
+    fn name(&self) -> &str {
+        "cpan"
+    }
+
+    async fn search(&self, query: &SearchQuery) -> Result<Vec<SearchHit>, RegistryError> {
+        let results = search_cpan(Some(&query.text), query.limit as u32)
+            .await
+            .map_err(|error| RegistryError::new(self.name(), error))?;
+        Ok(collect_hits(&results["results"], query.limit, |group| {
+            SearchHit {
+                name: text(&group[0]["distribution"]).unwrap_or_default(),
+                version: text(&group[0]["version"]),
+                description: text(&group[0]["abstract"]),
+                homepage: text(&group[0]["distribution"])
+                    .map(|name| format!("https://metacpan.org/dist/{}", name)),
+                source: self.name().to_string(),
+                ..Default::default()
+            }
+        }))
+    }
+}
@@ -0,0 +1,57 @@
+use crate::error::SearchError;
+use crate::registry::{collect_hits, text, Registry, RegistryError, SearchHit, SearchQuery};
+use crate::ApiClient;
+use async_trait::async_trait;
+use serde_json::Value;
+
+/// Searches for R packages on CRAN via the METACRAN search service.
+///
+/// # Arguments
+///
+/// * `query` - The search query (optional).
+/// * `limit` - The maximum number of results to return.
+///
+/// # Returns
+///
+/// Returns the matching package records as a `serde_json::Value` array or an error.
+pub async fn search_cran(query: Option<&str>, limit: u32) -> Result<Value, SearchError> {
+    let response = ApiClient::new("https://search.r-pkg.org/package/", None)
+        .set_param("q", query.unwrap_or(""))
+        .set_param("size", &limit.to_string())
+        .get("_search")
+        .await?;
+
+    Ok(response["hits"]["hits"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .map(|hit| hit["_source"].clone())
+        .collect())
+}
+
+/// The Comprehensive R Archive Network.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Cran;
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl Registry for Cran {
+    fn name(&self) -> &str {
+        "cran"
+    }
+
+    async fn search(&self, query: &SearchQuery) -> Result<Vec<SearchHit>, RegistryError> {
+        let results = search_cran(Some(&query.text), query.limit as u32)
+            .await
+            .map_err(|error| RegistryError::new(self.name(), error))?;
+        Ok(collect_hits(&results, query.limit, |package| SearchHit {
+            name: text(&package["Package"]).unwrap_or_default(),
+            version: text(&package["Version"]),
+            description: text(&package["Title"]),
+            homepage: text(&package["URL"]),
+            license: text(&package["License"]),
+            source: self.name().to_string(),
+            ..Default::default()
+        }))
+    }
+}
@@ -0,0 +1,450 @@
+use crate::client::encode_path_segment;
+use crate::error::SearchError;
+use crate::paginate::paginate;
+use crate::registry::{Registry, RegistryConfig, RegistryError, SearchHit, SearchQuery};
+use crate::results::SearchResults;
+use crate::ApiClient;
+use async_trait::async_trait;
+use futures::Stream;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+const CRATES_API: &str = "https://crates.io/api/v1/";
+
+/// The orderings supported by crates.io search.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CrateSort {
+    #[default]
+    Relevance,
+    Downloads,
+    RecentDownloads,
+    RecentUpdates,
+    New,
+}
+
+impl CrateSort {
+    /// Returns the value crates.io expects for the `sort` parameter.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CrateSort::Relevance => "relevance",
+            CrateSort::Downloads => "downloads",
+            CrateSort::RecentDownloads => "recent-downloads",
+            CrateSort::RecentUpdates => "recent-updates",
+            CrateSort::New => "new",
+        }
+    }
+}
+
+/// A crate returned by crates.io search.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct CrateHit {
+    pub name: String,
+    pub max_version: String,
+    pub max_stable_version: Option<String>,
+    pub description: Option<String>,
+    pub homepage: Option<String>,
+    pub documentation: Option<String>,
+    pub repository: Option<String>,
+    pub downloads: u64,
+    pub recent_downloads: Option<u64>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl From<CrateHit> for SearchHit {
+    fn from(krate: CrateHit) -> Self {
+        Self {
+            name: krate.name,
+            version: Some(krate.max_version),
+            description: krate.description,
+            homepage: krate.homepage,
+            repository: krate.repository,
+            downloads: Some(krate.downloads),
+            license: None,
+            updated: Some(krate.updated_at),
+            deprecated: false,
+            source: "crates".to_string(),
+        }
+    }
+}
+
+/// A published version of a crate.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct CrateVersion {
+    pub id: u64,
+    pub num: String,
+    pub downloads: u64,
+    pub yanked: bool,
+    pub license: Option<String>,
+    pub crate_size: Option<u64>,
+    pub rust_version: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// The downloads of one crate version on one day.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct VersionDownloads {
+    /// The `id` of the [`CrateVersion`] the downloads belong to.
+    pub version: u64,
+    pub downloads: u64,
+    pub date: String,
+}
+
+/// The downloads on one day of versions not listed individually.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ExtraDownloads {
+    pub downloads: u64,
+    pub date: String,
+}
+
+/// The daily download counts of a crate over the last 90 days.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct CrateDownloads {
+    /// Per-version daily downloads for the most recent versions.
+    pub version_downloads: Vec<VersionDownloads>,
+    /// Daily downloads of all older versions combined.
+    pub extra_downloads: Vec<ExtraDownloads>,
+}
+
+/// A user or team that owns a crate.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct CrateOwner {
+    pub id: u64,
+    /// The GitHub login, or `github:org:team` for teams.
+    pub login: String,
+    /// Either `user` or `team`.
+    pub kind: String,
+    pub name: Option<String>,
+    pub url: Option<String>,
+    pub avatar: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct VersionsResponse {
+    versions: Vec<CrateVersion>,
+}
+
+#[derive(Deserialize)]
+struct DownloadsResponse {
+    version_downloads: Vec<VersionDownloads>,
+    meta: DownloadsMeta,
+}
+
+#[derive(Deserialize)]
+struct DownloadsMeta {
+    #[serde(default)]
+    extra_downloads: Vec<ExtraDownloads>,
+}
+
+#[derive(Deserialize)]
+struct OwnersResponse {
+    users: Vec<CrateOwner>,
+}
+
+fn client() -> ApiClient {
+    ApiClient::new(CRATES_API, None)
+}
+
+/// Searches for crates on crates.io.
+///
+/// # Arguments
+///
+/// * `query` - The search query (optional).
+/// * `sort` - The result ordering.
+///
+/// # Returns
+///
+/// Returns the matching crates or an error.
+pub async fn search_crates(
+    query: Option<&str>,
+    sort: CrateSort,
+) -> Result<SearchResults<CrateHit>, SearchError> {
+    search_crates_with(query, sort, 1, 25, &RegistryConfig::new()).await
+}
+
+/// Searches for crates on a registry that serves the crates.io web API,
+/// such as a kellnr or Cloudsmith instance.
+///
+/// # Arguments
+///
+/// * `query` - The search query (optional).
+/// * `sort` - The result ordering.
+/// * `page` - The 1-based result page.
+/// * `per_page` - The number of results per page (at most 100).
+/// * `config` - The API URL (ending in `api/v1`) and credentials;
+///   crates.io is used when no URL is set.
+///
+/// # Returns
+///
+/// Returns the matching crates or an error.
+pub async fn search_crates_with(
+    query: Option<&str>,
+    sort: CrateSort,
+    page: u32,
+    per_page: u32,
+    config: &RegistryConfig,
+) -> Result<SearchResults<CrateHit>, SearchError> {
+    let response = config
+        .client(CRATES_API, None)
+        .set_param("page", &page.to_string())
+        .set_param("per_page", &per_page.to_string())
+        .set_param("q", query.unwrap_or(""))
+        .set_param("sort", sort.as_str())
+        .get("crates")
+        .await?;
+    SearchResults::from_raw(response, "/crates")
+}
+
+/// Returns the crates.io search results as a stream of pages of 100 crates.
+///
+/// # Arguments
+///
+/// * `query` - The search query.
+/// * `sort` - The result ordering.
+///
+/// # Returns
+///
+/// Returns the stream of pages; see [`collect_n`](crate::paginate::collect_n)
+/// to take a number of crates from it.
+pub fn paginate_crates(
+    query: &str,
+    sort: CrateSort,
+) -> impl Stream<Item = Result<Vec<CrateHit>, SearchError>> + '_ {
+    paginate(100, move |page| async move {
+        let response = client()
+            .set_param("page", &page.number.to_string())
+            .set_param("per_page", &page.size.to_string())
+            .set_param("q", query)
+            .set_param("sort", sort.as_str())
+            .get("crates")
+            .await?;
+        Ok(SearchResults::from_raw(response, "/crates")?.into_hits())
+    })
+}
+
+/// Lists the crates that depend on a crate.
+///
+/// # Arguments
+///
+/// * `crate_name` - The name of the crate.
+/// * `page` - The 1-based result page.
+/// * `per_page` - The number of results per page (at most 100).
+///
+/// # Returns
+///
+/// Returns the dependent crate versions as a `serde_json::Value` or an error.
+pub async fn reverse_dependencies(
+    crate_name: &str,
+    page: u32,
+    per_page: u32,
+) -> Result<Value, SearchError> {
+    client()
+        .set_param("page", &page.to_string())
+        .set_param("per_page", &per_page.to_string())
+        .get(&format!(
+            "crates/{}/reverse_dependencies",
+            encode_path_segment(crate_name)
+        ))
+        .await
+}
+
+/// Lists the dependencies of one version of a crate.
+///
+/// # Arguments
+///
+/// * `name` - The name of the crate.
+/// * `version` - The exact version, e.g. `1.0.219`.
+///
+/// # Returns
+///
+/// Returns the version's normal, build, and dev dependencies with their
+/// requirements as a `serde_json::Value` or an error.
+pub async fn crate_dependencies(name: &str, version: &str) -> Result<Value, SearchError> {
+    client()
+        .get(&format!(
+            "crates/{}/{}/dependencies",
+            encode_path_segment(name),
+            encode_path_segment(version)
+        ))
+        .await
+}
+
+/// Fetches the full metadata of a crate.
+///
+/// The response includes the crate's versions, keywords, categories,
+/// download counts, and repository.
+///
+/// # Arguments
+///
+/// * `name` - The name of the crate.
+///
+/// # Returns
+///
+/// Returns the crate metadata as a `serde_json::Value` or an error.
+pub async fn get_crate(name: &str) -> Result<Value, SearchError> {
+    client()
+        .get(&format!("crates/{}", encode_path_segment(name)))
+        .await
+}
+
+/// Fetches the metadata of one version of a crate.
+///
+/// # Arguments
+///
+/// * `name` - The name of the crate.
+/// * `version` - The exact version, e.g. `1.0.219`.
+///
+/// # Returns
+///
+/// Returns the version metadata, including the SHA-256 `checksum` of its
+/// `.crate` file, as a `serde_json::Value` or an error.
+pub async fn get_crate_version(name: &str, version: &str) -> Result<Value, SearchError> {
+    client()
+        .get(&format!(
+            "crates/{}/{}",
+            encode_path_segment(name),
+            encode_path_segment(version)
+        ))
+        .await
+}
+
+/// Lists every published version of a crate, newest first.
+///
+/// # Arguments
+///
+/// * `name` - The name of the crate.
+///
+/// # Returns
+///
+/// Returns the crate's versions with their total download counts, or an error.
+pub async fn crate_versions(name: &str) -> Result<Vec<CrateVersion>, SearchError> {
+    let response = client()
+        .get(&format!("crates/{}/versions", encode_path_segment(name)))
+        .await?;
+    let response: VersionsResponse = serde_json::from_value(response)?;
+    Ok(response.versions)
+}
+
+/// Fetches the daily download counts of a crate.
+///
+/// # Arguments
+///
+/// * `name` - The name of the crate.
+///
+/// # Returns
+///
+/// Returns the per-version daily downloads for the last 90 days, or an error.
+pub async fn crate_downloads(name: &str) -> Result<CrateDownloads, SearchError> {
+    let response = client()
+        .get(&format!("crates/{}/downloads", encode_path_segment(name)))
+        .await?;
+    let response: DownloadsResponse = serde_json::from_value(response)?;
+    Ok(CrateDownloads {
+        version_downloads: response.version_downloads,
+        extra_downloads: response.meta.extra_downloads,
+    })
+}
+
+/// Lists the users and teams that own a crate.
+///
+/// # Arguments
+///
+/// * `name` - The name of the crate.
+///
+/// # Returns
+///
+/// Returns the crate's owners or an error.
+pub async fn crate_owners(name: &str) -> Result<Vec<CrateOwner>, SearchError> {
+    let response = client()
+        .get(&format!("crates/{}/owners", encode_path_segment(name)))
+        .await?;
+    let response: OwnersResponse = serde_json::from_value(response)?;
+    Ok(response.users)
+}
+
+/// Lists the crates.io categories.
+///
+/// # Arguments
+///
+/// * `page` - The 1-based result page.
+///
+/// # Returns
+///
+/// Returns the categories as a `serde_json::Value` or an error.
+pub async fn list_categories(page: u32) -> Result<Value, SearchError> {
+    client()
+        .set_param("page", &page.to_string())
+        .set_param("per_page", "25")
+        .get("categories")
+        .await
+}
+
+/// Lists the crates in a category.
+///
+/// # Arguments
+///
+/// * `slug` - The category slug, e.g. `command-line-utilities`.
+/// * `page` - The 1-based result page.
+///
+/// # Returns
+///
+/// Returns the crates as a `serde_json::Value` or an error.
+pub async fn crates_by_category(slug: &str, page: u32) -> Result<Value, SearchError> {
+    client()
+        .set_param("category", slug)
+        .set_param("page", &page.to_string())
+        .set_param("per_page", "25")
+        .get("crates")
+        .await
+}
+
+/// Lists the crates tagged with a keyword.
+///
+/// # Arguments
+///
+/// * `keyword` - The keyword, e.g. `async`.
+/// * `page` - The 1-based result page.
+///
+/// # Returns
+///
+/// Returns the crates as a `serde_json::Value` or an error.
+pub async fn crates_by_keyword(keyword: &str, page: u32) -> Result<Value, SearchError> {
+    client()
+        .set_param("keyword", keyword)
+        .set_param("page", &page.to_string())
+        .set_param("per_page", "25")
+        .get("crates")
+        .await
+}
+
+/// The crates.io registry.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CratesIo;
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl Registry for CratesIo {
+    fn name(&self) -> &str {
+        "crates"
+    }
+
+    async fn search(&self, query: &SearchQuery) -> Result<Vec<SearchHit>, RegistryError> {
+        // crates.io serves at most 100 crates a page.
+        let per_page = query.limit.min(100) as u32;
+        let results = search_crates_with(
+            Some(&query.text),
+            CrateSort::default(),
+            1,
+            per_page,
+            &RegistryConfig::new(),
+        )
+        .await
+        .map_err(|error| RegistryError::new(self.name(), error))?;
+        Ok(results
+            .into_iter()
+            .take(query.limit)
+            .map(SearchHit::from)
+            .collect())
+    }
+}
@@ -0,0 +1,100 @@
+use crate::error::SearchError;
+use crate::registry::{collect_hits, text, Registry, RegistryError, SearchHit, SearchQuery};
+use crate::ApiClient;
+use async_trait::async_trait;
+use serde_json::Value;
+
+/// Searches for packages on the JSR registry.
+///
+/// # Arguments
+///
+/// * `query` - The search query (optional).
+///
+/// # Returns
+///
+/// Returns the search results as a `serde_json::Value` or an error.
+pub async fn search_jsr(query: Option<&str>) -> Result<Value, SearchError> {
+    ApiClient::new("https://api.jsr.io/", None)
+        .set_param("query", query.unwrap_or(""))
+        .set_param("limit", "25")
+        .get("packages")
+        .await
+}
+
+/// Searches for third-party modules on deno.land/x.
+///
+/// # Arguments
+///
+/// * `query` - The search query (optional).
+///
+/// # Returns
+///
+/// Returns the search results as a `serde_json::Value` or an error.
+pub async fn search_denoland(query: Option<&str>) -> Result<Value, SearchError> {
+    ApiClient::new("https://api.deno.land/", None)
+        .set_param("query", query.unwrap_or(""))
+        .set_param("limit", "25")
+        .get("modules")
+        .await
+}
+
+/// The JSR registry.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Jsr;
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl Registry for Jsr {
+    fn name(&self) -> &str {
+        "jsr"
+    }
+
+    async fn search(&self, query: &SearchQuery) -> Result<Vec<SearchHit>, RegistryError> {
+        let results = search_jsr(Some(&query.text))
+            .await
+            .map_err(|error| RegistryError::new(self.name(), error))?;
+        Ok(collect_hits(&results["items"], query.limit, |package| {
+            SearchHit {
+                name: text(&package["scope"])
+                    .zip(text(&package["name"]))
+                    .map(|(scope, name)| format!("@{}/{}", scope, name))
+                    .unwrap_or_default(),
+                version: text(&package["latestVersion"]),
+                description: text(&package["description"]),
+                homepage: None,
+                source: self.name().to_string(),
+                ..Default::default()
+            }
+        }))
+    }
+}
+
+/// Third-party modules on deno.land/x.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DenoLand;
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl Registry for DenoLand {
+    fn name(&self) -> &str {
+        "denoland"
+    }
+
+    async fn search(&self, query: &SearchQuery) -> Result<Vec<SearchHit>, RegistryError> {
+        let results = search_denoland(Some(&query.text))
+            .await
+            .map_err(|error| RegistryError::new(self.name(), error))?;
+        Ok(collect_hits(
+            &results["data"]["results"],
+            query.limit,
+            |module| SearchHit {
+                name: text(&module["name"]).unwrap_or_default(),
+                version: None,
+                description: text(&module["description"]),
+                homepage: text(&module["name"]).map(|name| format!("https://deno.land/x/{}", name)),
+                source: self.name().to_string(),
+                ..Default::default()
+            },
+        ))
+    }
+}
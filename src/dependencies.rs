@@ -0,0 +1,341 @@
+//! Fetching the dependency trees of crates and npm packages.
+
+use crate::error::SearchError;
+use crate::versions::resolve_version;
+use crate::{crates, npm};
+#[cfg(not(target_arch = "wasm32"))]
+use futures::future::BoxFuture;
+#[cfg(target_arch = "wasm32")]
+use futures::future::LocalBoxFuture;
+use futures::future::{join_all, FutureExt};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
+use std::future::Future;
+
+/// The boxed future of a subtree, which is `Send` outside WebAssembly like
+/// the futures of [`Registry`](crate::Registry).
+#[cfg(not(target_arch = "wasm32"))]
+type NodeFuture<'a> = BoxFuture<'a, Result<DependencyNode, SearchError>>;
+#[cfg(target_arch = "wasm32")]
+type NodeFuture<'a> = LocalBoxFuture<'a, Result<DependencyNode, SearchError>>;
+
+#[cfg(not(target_arch = "wasm32"))]
+fn boxed<'a>(
+    future: impl Future<Output = Result<DependencyNode, SearchError>> + Send + 'a,
+) -> NodeFuture<'a> {
+    future.boxed()
+}
+
+#[cfg(target_arch = "wasm32")]
+fn boxed<'a>(
+    future: impl Future<Output = Result<DependencyNode, SearchError>> + 'a,
+) -> NodeFuture<'a> {
+    future.boxed_local()
+}
+
+/// A package version in a dependency tree, with the versions its
+/// requirements resolve to.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DependencyNode {
+    pub name: String,
+    /// The resolved version, or `None` if the requirement isn't a version
+    /// range or no published version meets it, e.g. a git URL, a `file:`
+    /// path, or `workspace:*`.
+    pub version: Option<String>,
+    /// The requirement the parent declared, e.g. `^1.0`; `None` for the
+    /// root.
+    pub requirement: Option<String>,
+    /// The resolved dependencies, empty below the depth limit.
+    pub dependencies: Vec<DependencyNode>,
+    /// Whether the dependencies of this version were left out because they
+    /// are listed elsewhere in the tree already.
+    pub repeated: bool,
+}
+
+impl DependencyNode {
+    /// Renders the tree like the `tree` command, with one package per line,
+    /// e.g. `├── serde_derive v1.0.219`. Repeated subtrees end in `(*)`,
+    /// and unresolved requirements in `(unresolved)`.
+    pub fn render_tree(&self) -> String {
+        let mut out = format!("{}\n", self.label());
+        self.render_children(&mut out, "");
+        out
+    }
+
+    /// Renders the tree with two spaces of indentation per level.
+    pub fn render_indented(&self) -> String {
+        let mut out = String::new();
+        self.render_indented_into(&mut out, 0);
+        out
+    }
+
+    fn label(&self) -> String {
+        let mut label = self.name.clone();
+        if let Some(version) = &self.version {
+            let _ = write!(label, " v{}", version);
+        }
+        if let Some(requirement) = &self.requirement {
+            let _ = write!(label, " ({})", requirement);
+        }
+        if self.version.is_none() {
+            label.push_str(" (unresolved)");
+        }
+        if self.repeated {
+            label.push_str(" (*)");
+        }
+        label
+    }
+
+    fn render_children(&self, out: &mut String, prefix: &str) {
+        for (index, child) in self.dependencies.iter().enumerate() {
+            let last = index + 1 == self.dependencies.len();
+            let (branch, indent) = if last {
+                ("└── ", "    ")
+            } else {
+                ("├── ", "│   ")
+            };
+            let _ = writeln!(out, "{}{}{}", prefix, branch, child.label());
+            child.render_children(out, &format!("{}{}", prefix, indent));
+        }
+    }
+
+    fn render_indented_into(&self, out: &mut String, level: usize) {
+        let _ = writeln!(out, "{}{}", "  ".repeat(level), self.label());
+        for child in &self.dependencies {
+            child.render_indented_into(out, level + 1);
+        }
+    }
+}
+
+/// The state shared by the whole traversal.
+struct Walk<'a> {
+    source: &'a str,
+    /// The versions requirements have resolved to, by name and requirement,
+    /// or `None` for those that can't be resolved.
+    resolved: HashMap<(String, String), Option<String>>,
+    /// The versions whose dependencies are in the tree already.
+    expanded: HashSet<(String, String)>,
+}
+
+/// Fetches the dependency tree of a package version, resolving every
+/// requirement to the newest version that meets it.
+///
+/// Only the normal, non-optional dependencies are followed; build, dev,
+/// and peer dependencies are left out. A version whose dependencies are
+/// already in the tree is listed again but not expanded, and marked
+/// [`repeated`](DependencyNode::repeated). A requirement that can't be
+/// resolved, such as a git URL or an alias, is listed without a
+/// [`version`](DependencyNode::version) and not expanded.
+///
+/// Each package costs a request or two. The dependencies of a package are
+/// resolved concurrently, but large trees still take a while; keep `depth`
+/// small.
+///
+/// # Arguments
+///
+/// * `source` - The registry: `crates` or `npm`.
+/// * `name` - The name of the package.
+/// * `version` - The exact version; the latest release when `None`.
+/// * `depth` - How many levels of dependencies to fetch; 0 fetches only
+///   the root.
+///
+/// # Returns
+///
+/// Returns the root of the tree or an error.
+pub async fn dependency_tree(
+    source: &str,
+    name: &str,
+    version: Option<&str>,
+    depth: usize,
+) -> Result<DependencyNode, SearchError> {
+    if !matches!(source, "crates" | "npm") {
+        return Err(SearchError::InvalidQuery(format!(
+            "dependency trees are not supported for '{}'; supported sources are 'crates' and 'npm'",
+            source
+        )));
+    }
+    let version = match version {
+        Some(version) => version.to_string(),
+        None => resolve_version(source, name, "*").await?.version,
+    };
+    let mut walk = Walk {
+        source,
+        resolved: HashMap::new(),
+        expanded: HashSet::new(),
+    };
+    walk.node(name.to_string(), Some(version), None, depth)
+        .await
+}
+
+impl Walk<'_> {
+    fn node(
+        &mut self,
+        name: String,
+        version: Option<String>,
+        requirement: Option<String>,
+        depth: usize,
+    ) -> NodeFuture<'_> {
+        boxed(async move {
+            let mut node = DependencyNode {
+                name,
+                version,
+                requirement,
+                dependencies: Vec::new(),
+                repeated: false,
+            };
+            let Some(version) = node.version.clone() else {
+                return Ok(node);
+            };
+            if depth == 0 {
+                return Ok(node);
+            }
+            if !self.expanded.insert((node.name.clone(), version.clone())) {
+                node.repeated = true;
+                return Ok(node);
+            }
+
+            let requirements = self.requirements(&node.name, &version).await?;
+            let versions = self.resolve_all(&requirements).await?;
+            for ((dependency, requirement), version) in requirements.into_iter().zip(versions) {
+                let child = self
+                    .node(dependency, version, Some(requirement), depth - 1)
+                    .await?;
+                node.dependencies.push(child);
+            }
+            Ok(node)
+        })
+    }
+
+    /// Returns the names and requirements of the normal dependencies of a
+    /// version.
+    async fn requirements(
+        &self,
+        name: &str,
+        version: &str,
+    ) -> Result<Vec<(String, String)>, SearchError> {
+        let requirements = match self.source {
+            "crates" => {
+                let response = crates::crate_dependencies(name, version).await?;
+                response["dependencies"]
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .filter(|dependency| {
+                        dependency["kind"] == "normal" && dependency["optional"] != true
+                    })
+                    .filter_map(|dependency| {
+                        Some((
+                            dependency["crate_id"].as_str()?.to_string(),
+                            dependency["req"].as_str()?.to_string(),
+                        ))
+                    })
+                    .collect()
+            }
+            _ => {
+                let manifest = npm::get_package_version(name, version).await?;
+                manifest["dependencies"]
+                    .as_object()
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|(name, requirement)| {
+                        Some((name.clone(), requirement.as_str()?.to_string()))
+                    })
+                    .collect()
+            }
+        };
+        Ok(requirements)
+    }
+
+    /// Resolves the requirements of sibling dependencies concurrently,
+    /// reusing earlier resolutions of them.
+    async fn resolve_all(
+        &mut self,
+        requirements: &[(String, String)],
+    ) -> Result<Vec<Option<String>>, SearchError> {
+        let source = self.source;
+        let pending: Vec<&(String, String)> = requirements
+            .iter()
+            .filter(|key| !self.resolved.contains_key(*key))
+            .collect();
+        let versions = join_all(
+            pending
+                .iter()
+                .map(|(name, requirement)| resolve(source, name, requirement)),
+        )
+        .await;
+        for (key, version) in pending.into_iter().zip(versions) {
+            self.resolved.insert(key.clone(), version?);
+        }
+        Ok(requirements
+            .iter()
+            .map(|key| self.resolved[key].clone())
+            .collect())
+    }
+}
+
+/// Resolves a requirement to a version.
+///
+/// Returns `None` for requirements that aren't version ranges, such as git
+/// URLs, `file:` paths, aliases, or `workspace:*`, and for those no
+/// published version meets.
+async fn resolve(
+    source: &str,
+    name: &str,
+    requirement: &str,
+) -> Result<Option<String>, SearchError> {
+    match resolve_version(source, name, requirement).await {
+        Ok(resolved) => Ok(Some(resolved.version)),
+        Err(SearchError::InvalidQuery(_) | SearchError::NotFound) => Ok(None),
+        Err(error) => Err(error),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(name: &str, version: Option<&str>, requirement: Option<&str>) -> DependencyNode {
+        DependencyNode {
+            name: name.to_string(),
+            version: version.map(str::to_string),
+            requirement: requirement.map(str::to_string),
+            dependencies: Vec::new(),
+            repeated: false,
+        }
+    }
+
+    fn tree() -> DependencyNode {
+        let mut serde = node("serde", Some("1.0.219"), Some("^1.0"));
+        serde.dependencies = vec![node("serde_derive", Some("1.0.219"), Some("=1.0.219"))];
+        let mut derive = node("serde_derive", Some("1.0.219"), Some("^1.0"));
+        derive.repeated = true;
+        let mut app = node("app", Some("0.1.0"), None);
+        app.dependencies = vec![serde, derive, node("local", None, Some("file:../local"))];
+        app
+    }
+
+    #[test]
+    fn renders_tree() {
+        assert_eq!(
+            tree().render_tree(),
+            "app v0.1.0\n\
+             ├── serde v1.0.219 (^1.0)\n\
+             │   └── serde_derive v1.0.219 (=1.0.219)\n\
+             ├── serde_derive v1.0.219 (^1.0) (*)\n\
+             └── local (file:../local) (unresolved)\n"
+        );
+    }
+
+    #[test]
+    fn renders_indented_tree() {
+        assert_eq!(
+            tree().render_indented(),
+            "app v0.1.0\n  \
+             serde v1.0.219 (^1.0)\n    \
+             serde_derive v1.0.219 (=1.0.219)\n  \
+             serde_derive v1.0.219 (^1.0) (*)\n  \
+             local (file:../local) (unresolved)\n"
+        );
+    }
+}
@@ -0,0 +1,321 @@
+use crate::client::{encode_path, encode_path_segment, read_body};
+use crate::error::SearchError;
+use crate::registry::{Registry, RegistryError, SearchHit, SearchQuery};
+use crate::results::SearchResults;
+use crate::ApiClient;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+const HUB_API: &str = "https://hub.docker.com/v2/";
+pub(crate) const REGISTRY: &str = "https://registry-1.docker.io/v2/";
+const MANIFEST_TYPES: &str = "application/vnd.oci.image.index.v1+json, \
+    application/vnd.docker.distribution.manifest.list.v2+json, \
+    application/vnd.oci.image.manifest.v1+json, \
+    application/vnd.docker.distribution.manifest.v2+json";
+
+/// An image repository returned by Docker Hub search.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct DockerHit {
+    pub repo_name: String,
+    pub short_description: Option<String>,
+    #[serde(default)]
+    pub star_count: u64,
+    #[serde(default)]
+    pub pull_count: u64,
+    #[serde(default)]
+    pub is_official: bool,
+    #[serde(default)]
+    pub is_automated: bool,
+}
+
+impl From<DockerHit> for SearchHit {
+    fn from(image: DockerHit) -> Self {
+        // Official images live under `library/` and have pages of their own.
+        let official_name = image.repo_name.strip_prefix("library/");
+        let homepage =
+            if image.is_official || official_name.is_some() || !image.repo_name.contains('/') {
+                let name = official_name.unwrap_or(&image.repo_name);
+                format!("https://hub.docker.com/_/{}", name)
+            } else {
+                format!("https://hub.docker.com/r/{}", image.repo_name)
+            };
+        Self {
+            homepage: Some(homepage),
+            name: image.repo_name,
+            version: None,
+            description: image.short_description,
+            repository: None,
+            downloads: Some(image.pull_count),
+            license: None,
+            updated: None,
+            deprecated: false,
+            source: "docker".to_string(),
+        }
+    }
+}
+
+/// An image manifest fetched from the Docker Hub registry.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ImageManifest {
+    /// The digest the tag currently points at.
+    pub digest: Option<String>,
+    pub media_type: Option<String>,
+    pub layers: Vec<ManifestLayer>,
+    /// The combined compressed size of all layers, in bytes.
+    pub total_size: u64,
+    /// The manifest document as returned by the registry.
+    pub raw: Value,
+}
+
+/// A layer of an image manifest.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ManifestLayer {
+    #[serde(rename = "mediaType")]
+    pub media_type: String,
+    pub digest: String,
+    pub size: u64,
+}
+
+/// Searches for images on Docker Hub.
+///
+/// # Arguments
+///
+/// * `query` - The search query (optional).
+///
+/// # Returns
+///
+/// Returns the matching repositories or an error.
+pub async fn search_docker(query: Option<&str>) -> Result<SearchResults<DockerHit>, SearchError> {
+    search_docker_page(query, 1, 25).await
+}
+
+/// Fetches one page of the images on Docker Hub matching a query.
+///
+/// # Arguments
+///
+/// * `query` - The search query (optional).
+/// * `page` - The 1-based result page.
+/// * `page_size` - The number of results per page (at most 100).
+///
+/// # Returns
+///
+/// Returns the matching repositories or an error.
+pub async fn search_docker_page(
+    query: Option<&str>,
+    page: u32,
+    page_size: u32,
+) -> Result<SearchResults<DockerHit>, SearchError> {
+    let response = ApiClient::new(HUB_API, None)
+        .set_param("query", query.unwrap_or(""))
+        .set_param("page", &page.to_string())
+        .set_param("page_size", &page_size.to_string())
+        .get("search/repositories/")
+        .await?;
+    SearchResults::from_raw(response, "/results")
+}
+
+/// Lists the tags of a Docker Hub repository.
+///
+/// Each tag lists its digest, last update, and the architecture, OS, and
+/// compressed size of every image it points at.
+///
+/// # Arguments
+///
+/// * `namespace` - The repository namespace; official images live under `library`.
+/// * `repo` - The repository name.
+/// * `page` - The 1-based result page.
+///
+/// # Returns
+///
+/// Returns the tags as a `serde_json::Value` or an error.
+pub async fn list_tags(namespace: &str, repo: &str, page: u32) -> Result<Value, SearchError> {
+    ApiClient::new(HUB_API, None)
+        .set_param("page", &page.to_string())
+        .set_param("page_size", "25")
+        .get(&format!(
+            "namespaces/{}/repositories/{}/tags",
+            encode_path_segment(namespace),
+            encode_path_segment(repo)
+        ))
+        .await
+}
+
+/// Fetches the manifest a Docker Hub image tag points at.
+///
+/// Performs the anonymous token exchange with `auth.docker.io` and then
+/// requests the manifest from `registry-1.docker.io`. When the tag points at
+/// a multi-platform index, the `linux/amd64` image manifest (or the first
+/// one listed) is resolved, while `digest` stays the digest of the tag.
+///
+/// # Arguments
+///
+/// * `repo` - The repository, e.g. `nginx` or `grafana/grafana`.
+/// * `tag` - The tag or digest to inspect.
+///
+/// # Returns
+///
+/// Returns the manifest with its digest, layers, and total size, or an error.
+pub async fn get_manifest(repo: &str, tag: &str) -> Result<ImageManifest, SearchError> {
+    let (registry, repo) = registry_client(repo).await?;
+    let response = registry
+        .get_response(&manifest_endpoint(&repo, tag))
+        .await?;
+    let digest = response
+        .headers()
+        .get("Docker-Content-Digest")
+        .and_then(|digest| digest.to_str().ok())
+        .map(str::to_string);
+    let body = read_body(response, registry.max_response_size()).await?;
+    let mut manifest: Value = serde_json::from_slice(&body)?;
+
+    if let Some(platform_digest) = platform_manifest_digest(&manifest) {
+        manifest = registry
+            .get(&manifest_endpoint(&repo, &platform_digest))
+            .await?;
+    }
+
+    let layers: Vec<ManifestLayer> = match manifest.get("layers") {
+        Some(layers) => serde_json::from_value(layers.clone())?,
+        None => Vec::new(),
+    };
+    Ok(ImageManifest {
+        digest,
+        media_type: manifest["mediaType"].as_str().map(str::to_string),
+        total_size: layers.iter().map(|layer| layer.size).sum(),
+        layers,
+        raw: manifest,
+    })
+}
+
+/// Returns a client for the `registry-1.docker.io` API of a repository,
+/// after the anonymous token exchange with `auth.docker.io`, along with
+/// the repository's full name, e.g. `library/nginx` for `nginx`.
+pub(crate) async fn registry_client(repo: &str) -> Result<(ApiClient, String), SearchError> {
+    authorize_registry(repo, ApiClient::new(REGISTRY, None)).await
+}
+
+/// Authorizes `registry`, a client for [`REGISTRY`], to pull a repository
+/// as [`registry_client`] does.
+pub(crate) async fn authorize_registry(
+    repo: &str,
+    registry: ApiClient,
+) -> Result<(ApiClient, String), SearchError> {
+    let repo = if repo.contains('/') {
+        repo.to_string()
+    } else {
+        format!("library/{}", repo)
+    };
+
+    // The token expires within minutes, so it must not be cached.
+    let token = ApiClient::new("https://auth.docker.io/", None)
+        .no_cache()
+        .set_param("service", "registry.docker.io")
+        .set_param("scope", &format!("repository:{}:pull", repo))
+        .get("token")
+        .await?;
+    let token = token["token"].as_str().ok_or_else(|| {
+        SearchError::Decode(serde::de::Error::custom(
+            "Docker Hub did not return a registry token",
+        ))
+    })?;
+
+    let registry = registry
+        .set_header("Authorization", &format!("Bearer {}", token))
+        .set_header("Accept", MANIFEST_TYPES);
+    Ok((registry, repo))
+}
+
+/// Returns the registry endpoint of the manifest `reference`, a tag or
+/// digest, of `repo`, e.g. `library/nginx`.
+pub(crate) fn manifest_endpoint(repo: &str, reference: &str) -> String {
+    format!(
+        "{}/manifests/{}",
+        encode_path(repo),
+        encode_path_segment(reference)
+    )
+}
+
+/// Picks the `linux/amd64` entry of a multi-platform index, if it is one.
+pub(crate) fn platform_manifest_digest(manifest: &Value) -> Option<String> {
+    let manifests = manifest["manifests"].as_array()?;
+    manifests
+        .iter()
+        .find(|entry| {
+            entry["platform"]["os"] == "linux" && entry["platform"]["architecture"] == "amd64"
+        })
+        .or_else(|| manifests.first())?["digest"]
+        .as_str()
+        .map(str::to_string)
+}
+
+/// The Docker Hub image registry.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DockerHub;
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl Registry for DockerHub {
+    fn name(&self) -> &str {
+        "docker"
+    }
+
+    async fn search(&self, query: &SearchQuery) -> Result<Vec<SearchHit>, RegistryError> {
+        // Docker Hub serves at most 100 repositories a page.
+        let page_size = query.limit.min(100) as u32;
+        let results = search_docker_page(Some(&query.text), 1, page_size)
+            .await
+            .map_err(|error| RegistryError::new(self.name(), error))?;
+        Ok(results
+            .into_iter()
+            .take(query.limit)
+            .map(SearchHit::from)
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn image(repo_name: &str, is_official: bool) -> DockerHit {
+        DockerHit {
+            repo_name: repo_name.to_string(),
+            short_description: None,
+            star_count: 0,
+            pull_count: 0,
+            is_official,
+            is_automated: false,
+        }
+    }
+
+    #[test]
+    fn links_official_images_to_their_own_pages() {
+        let homepage =
+            |repo_name, is_official| SearchHit::from(image(repo_name, is_official)).homepage;
+        let nginx = Some("https://hub.docker.com/_/nginx".to_string());
+        assert_eq!(homepage("nginx", true), nginx);
+        assert_eq!(homepage("nginx", false), nginx);
+        assert_eq!(homepage("library/nginx", false), nginx);
+        assert_eq!(
+            homepage("grafana/grafana", false),
+            Some("https://hub.docker.com/r/grafana/grafana".to_string())
+        );
+    }
+
+    #[test]
+    fn encodes_repositories_and_references_in_manifest_endpoints() {
+        assert_eq!(
+            manifest_endpoint("library/nginx", "1.27-alpine"),
+            "library/nginx/manifests/1.27-alpine"
+        );
+        assert_eq!(
+            manifest_endpoint("grafana/grafana", "sha256:abc"),
+            "grafana/grafana/manifests/sha256:abc"
+        );
+        assert_eq!(
+            manifest_endpoint("library/nginx", "../../v2/_catalog"),
+            "library/nginx/manifests/..%2F..%2Fv2%2F_catalog"
+        );
+    }
+}
@@ -0,0 +1,436 @@
+//! Downloading the files packages are published as, checked against the
+//! checksums their registries publish.
+
+use crate::client::{compat, encode_path, encode_path_segment, read_body};
+use crate::docker::{authorize_registry, manifest_endpoint, platform_manifest_digest, REGISTRY};
+use crate::error::SearchError;
+use crate::{crates, npm, ApiClient};
+use reqwest::StatusCode;
+use serde_json::{json, Value};
+use sha1::Sha1;
+use sha2::{Digest, Sha256, Sha512};
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// How long a download may stall before it fails. Downloads have no total
+/// timeout, since large files take as long as they take.
+const READ_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// A file or image written by [`download_artifact`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Artifact {
+    /// Where the artifact was written.
+    pub path: PathBuf,
+    /// The number of bytes downloaded.
+    pub size: u64,
+    /// The checksum the download was verified against, e.g.
+    /// `sha256:4d8f…`, or `None` if the registry publishes none.
+    pub checksum: Option<String>,
+}
+
+/// A hash algorithm registries publish checksums with, ordered from the
+/// weakest to the strongest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Algorithm {
+    Sha1,
+    Sha256,
+    Sha512,
+}
+
+impl Algorithm {
+    /// Returns the name digests and OCI blob directories use, e.g. `sha256`.
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Sha1 => "sha1",
+            Self::Sha256 => "sha256",
+            Self::Sha512 => "sha512",
+        }
+    }
+}
+
+/// A published checksum, as lowercase hex.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Checksum {
+    algorithm: Algorithm,
+    hex: String,
+}
+
+impl Checksum {
+    /// Parses an OCI digest, e.g. `sha256:4d8f…`.
+    fn from_digest(digest: &str) -> Option<Self> {
+        let (algorithm, hex) = digest.split_once(':')?;
+        let algorithm = match algorithm {
+            "sha256" => Algorithm::Sha256,
+            "sha512" => Algorithm::Sha512,
+            _ => return None,
+        };
+        Some(Self {
+            algorithm,
+            hex: hex.to_ascii_lowercase(),
+        })
+    }
+
+    /// Parses npm's `dist.integrity`, a Subresource Integrity string such
+    /// as `sha512-<base64>`.
+    fn from_integrity(integrity: &str) -> Option<Self> {
+        // Several hashes may be listed; the strongest one is checked.
+        integrity
+            .split_whitespace()
+            .filter_map(|hash| {
+                let (algorithm, encoded) = hash.split_once('-')?;
+                let algorithm = match algorithm {
+                    "sha512" => Algorithm::Sha512,
+                    "sha256" => Algorithm::Sha256,
+                    "sha1" => Algorithm::Sha1,
+                    _ => return None,
+                };
+                use base64::Engine;
+                let bytes = base64::engine::general_purpose::STANDARD
+                    .decode(encoded)
+                    .ok()?;
+                Some(Self {
+                    algorithm,
+                    hex: hex(&bytes),
+                })
+            })
+            .max_by_key(|checksum| checksum.algorithm)
+    }
+}
+
+impl fmt::Display for Checksum {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.algorithm.as_str(), self.hex)
+    }
+}
+
+/// A running hash of a download.
+enum Hasher {
+    Sha1(Sha1),
+    Sha256(Sha256),
+    Sha512(Sha512),
+}
+
+impl Hasher {
+    fn new(algorithm: Algorithm) -> Self {
+        match algorithm {
+            Algorithm::Sha1 => Self::Sha1(Sha1::new()),
+            Algorithm::Sha256 => Self::Sha256(Sha256::new()),
+            Algorithm::Sha512 => Self::Sha512(Sha512::new()),
+        }
+    }
+
+    fn update(&mut self, bytes: &[u8]) {
+        match self {
+            Self::Sha1(hasher) => hasher.update(bytes),
+            Self::Sha256(hasher) => hasher.update(bytes),
+            Self::Sha512(hasher) => hasher.update(bytes),
+        }
+    }
+
+    fn finalize(self) -> String {
+        match self {
+            Self::Sha1(hasher) => hex(&hasher.finalize()),
+            Self::Sha256(hasher) => hex(&hasher.finalize()),
+            Self::Sha512(hasher) => hex(&hasher.finalize()),
+        }
+    }
+}
+
+/// Downloads the file a package version is published as, verifying it
+/// against the registry's checksum where there is one.
+///
+/// * `crates` - the `.crate` file from `static.crates.io`, checked against
+///   the SHA-256 checksum in the version's metadata.
+/// * `npm` - the tarball, checked against `dist.integrity` or, for old
+///   versions without one, the SHA-1 `dist.shasum`.
+/// * `docker` - the image for `linux/amd64`, or the first platform listed,
+///   written to `dest` as an OCI image layout directory like `docker save`
+///   writes, with every blob checked against its digest. `version` is the
+///   tag, e.g. `latest`.
+///
+/// Files are written under a temporary `.part` name and renamed once they
+/// pass their check, so `dest` never holds a partial or corrupt download.
+///
+/// # Arguments
+///
+/// * `source` - The registry: `crates`, `npm`, or `docker`.
+/// * `name` - The name of the package or image, e.g. `serde` or `nginx`.
+/// * `version` - The exact version, or the image tag.
+/// * `dest` - The file to write, or the directory for a Docker image.
+///
+/// # Returns
+///
+/// Returns the artifact written, or [`SearchError::ChecksumMismatch`] if
+/// it doesn't match its checksum, or another error.
+pub async fn download_artifact(
+    source: &str,
+    name: &str,
+    version: &str,
+    dest: impl AsRef<Path>,
+) -> Result<Artifact, SearchError> {
+    let dest = dest.as_ref();
+    match source {
+        "crates" => {
+            let metadata = crates::get_crate_version(name, version).await?;
+            let checksum = metadata["version"]["checksum"]
+                .as_str()
+                .map(|hex| Checksum {
+                    algorithm: Algorithm::Sha256,
+                    hex: hex.to_ascii_lowercase(),
+                });
+            let url = format!(
+                "https://static.crates.io/crates/{0}/{0}-{1}.crate",
+                encode_path_segment(name),
+                encode_path_segment(version)
+            );
+            let size = fetch(&download_client(&url)?, "", dest, checksum.as_ref()).await?;
+            Ok(artifact(dest, size, checksum))
+        }
+        "npm" => {
+            let manifest = npm::get_package_version(name, version).await?;
+            let dist = &manifest["dist"];
+            let tarball = dist["tarball"].as_str().ok_or(SearchError::NotFound)?;
+            let checksum = dist["integrity"]
+                .as_str()
+                .and_then(Checksum::from_integrity)
+                .or_else(|| {
+                    dist["shasum"].as_str().map(|hex| Checksum {
+                        algorithm: Algorithm::Sha1,
+                        hex: hex.to_ascii_lowercase(),
+                    })
+                });
+            let size = fetch(&download_client(tarball)?, "", dest, checksum.as_ref()).await?;
+            Ok(artifact(dest, size, checksum))
+        }
+        "docker" => save_image(name, version, dest).await,
+        source => Err(SearchError::InvalidQuery(format!(
+            "downloads are not supported for '{}'; supported sources are 'crates', 'npm', and 'docker'",
+            source
+        ))),
+    }
+}
+
+fn artifact(path: &Path, size: u64, checksum: Option<Checksum>) -> Artifact {
+    Artifact {
+        path: path.to_path_buf(),
+        size,
+        checksum: checksum.map(|checksum| checksum.to_string()),
+    }
+}
+
+/// Returns a client for downloading from `url`, which gives up on stalled
+/// connections but not on slow ones.
+fn download_client(url: &str) -> Result<ApiClient, SearchError> {
+    ApiClient::builder(url)
+        .no_request_timeout()
+        .read_timeout(READ_TIMEOUT)
+        .build()
+}
+
+/// Streams a response body to `path`, hashing it on the way.
+///
+/// The body is written to a `.part` file next to `path`, which is renamed
+/// to `path` once it matches `checksum` and deleted if it doesn't or the
+/// download fails.
+async fn fetch(
+    client: &ApiClient,
+    endpoint: &str,
+    path: &Path,
+    checksum: Option<&Checksum>,
+) -> Result<u64, SearchError> {
+    let mut part = path.as_os_str().to_owned();
+    part.push(".part");
+    let part = PathBuf::from(part);
+    let result = fetch_to(client, endpoint, &part, checksum).await;
+    match result {
+        Ok(size) => {
+            fs::rename(&part, path)?;
+            Ok(size)
+        }
+        Err(error) => {
+            let _ = fs::remove_file(&part);
+            Err(error)
+        }
+    }
+}
+
+async fn fetch_to(
+    client: &ApiClient,
+    endpoint: &str,
+    path: &Path,
+    checksum: Option<&Checksum>,
+) -> Result<u64, SearchError> {
+    let mut response = client.get_response(endpoint).await?;
+    let mut file = BufWriter::new(File::create(path)?);
+    let mut hasher = checksum.map(|checksum| Hasher::new(checksum.algorithm));
+    let mut size = 0;
+    while let Some(chunk) = compat(response.chunk()).await? {
+        file.write_all(&chunk)?;
+        if let Some(hasher) = &mut hasher {
+            hasher.update(&chunk);
+        }
+        size += chunk.len() as u64;
+    }
+    file.flush()?;
+
+    if let (Some(expected), Some(hasher)) = (checksum, hasher) {
+        verify(expected, hasher.finalize())?;
+    }
+    Ok(size)
+}
+
+/// Returns an error unless `actual`, in hex, is the `expected` checksum.
+fn verify(expected: &Checksum, actual: String) -> Result<(), SearchError> {
+    if actual == expected.hex {
+        return Ok(());
+    }
+    let actual = Checksum {
+        algorithm: expected.algorithm,
+        hex: actual,
+    };
+    Err(SearchError::ChecksumMismatch {
+        expected: expected.to_string(),
+        actual: actual.to_string(),
+    })
+}
+
+/// Writes an image to `dest` as an OCI image layout: an `oci-layout` file,
+/// an `index.json` naming the image by its tag, and its manifest, config,
+/// and layers under `blobs/<algorithm>/`.
+async fn save_image(repo: &str, tag: &str, dest: &Path) -> Result<Artifact, SearchError> {
+    let (mut registry, repo) = authorize_registry(repo, download_client(REGISTRY)?).await?;
+    let (mut manifest, mut media_type, mut digest) = fetch_manifest(&registry, &repo, tag).await?;
+    let index: Value = serde_json::from_slice(&manifest)?;
+    if let Some(platform_digest) = platform_manifest_digest(&index) {
+        (manifest, media_type, digest) = fetch_manifest(&registry, &repo, &platform_digest).await?;
+        let checksum = Checksum::from_digest(&platform_digest).ok_or(SearchError::NotFound)?;
+        verify(&checksum, digest.hex.clone())?;
+    }
+
+    fs::write(blob_path(dest, &digest)?, &manifest)?;
+    let mut size = manifest.len() as u64;
+
+    let parsed: Value = serde_json::from_slice(&manifest)?;
+    let descriptors =
+        std::iter::once(&parsed["config"]).chain(parsed["layers"].as_array().into_iter().flatten());
+    for descriptor in descriptors {
+        let Some(checksum) = descriptor["digest"]
+            .as_str()
+            .and_then(Checksum::from_digest)
+        else {
+            continue;
+        };
+        let path = blob_path(dest, &checksum)?;
+        let endpoint = format!("{}/blobs/{}", encode_path(&repo), checksum);
+        size += match fetch(&registry, &endpoint, &path, Some(&checksum)).await {
+            // The token expires within minutes, which pulling large layers
+            // can outlast, so it is renewed once and the blob retried.
+            Err(SearchError::Status { status, .. }) if status == StatusCode::UNAUTHORIZED => {
+                (registry, _) = authorize_registry(&repo, registry).await?;
+                fetch(&registry, &endpoint, &path, Some(&checksum)).await?
+            }
+            result => result?,
+        };
+    }
+
+    fs::write(
+        dest.join("oci-layout"),
+        json!({ "imageLayoutVersion": "1.0.0" }).to_string(),
+    )?;
+    let index = json!({
+        "schemaVersion": 2,
+        "manifests": [{
+            "mediaType": media_type,
+            "digest": digest.to_string(),
+            "size": manifest.len(),
+            "annotations": { "org.opencontainers.image.ref.name": tag },
+        }],
+    });
+    fs::write(dest.join("index.json"), serde_json::to_vec_pretty(&index)?)?;
+    Ok(artifact(dest, size, Some(digest)))
+}
+
+/// Returns where the OCI image layout in `dest` keeps the blob with
+/// `digest`, e.g. `blobs/sha256/4d8f…`, creating its directory.
+fn blob_path(dest: &Path, digest: &Checksum) -> Result<PathBuf, SearchError> {
+    let blobs = dest.join("blobs").join(digest.algorithm.as_str());
+    fs::create_dir_all(&blobs)?;
+    Ok(blobs.join(&digest.hex))
+}
+
+/// Fetches a manifest as the exact bytes its digest covers, with its media
+/// type and SHA-256 digest, checked against the `Docker-Content-Digest`
+/// header when the registry sends one.
+async fn fetch_manifest(
+    registry: &ApiClient,
+    repo: &str,
+    reference: &str,
+) -> Result<(Vec<u8>, String, Checksum), SearchError> {
+    let response = registry
+        .get_response(&manifest_endpoint(repo, reference))
+        .await?;
+    let header = |name| {
+        response
+            .headers()
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string)
+    };
+    let media_type = header("Content-Type").unwrap_or_default();
+    let published = header("Docker-Content-Digest");
+    let bytes = read_body(response, registry.max_response_size()).await?;
+    let digest = Checksum {
+        algorithm: Algorithm::Sha256,
+        hex: hex(&Sha256::digest(&bytes)),
+    };
+    if let Some(published) = published.as_deref().and_then(Checksum::from_digest) {
+        let actual = match published.algorithm {
+            Algorithm::Sha512 => hex(&Sha512::digest(&bytes)),
+            _ => digest.hex.clone(),
+        };
+        verify(&published, actual)?;
+    }
+    Ok((bytes, media_type, digest))
+}
+
+/// Encodes bytes as lowercase hex.
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checks_the_strongest_integrity_hash() {
+        let sha1 = "sha1-qUqP5cyxm6YcTAhz05Hph5gvu9M=";
+        let sha512 = "sha512-7iaw3Ur350mqGo7jwQrpkj9hiYB3Lkc/iBml1JQODbJ6wYX4oOHV+E+IvIh/1nsUNzLDBMxfqa2Ob1f1ACio/w==";
+        for integrity in [
+            format!("{} {}", sha512, sha1),
+            format!("{} {}", sha1, sha512),
+        ] {
+            let checksum = Checksum::from_integrity(&integrity).unwrap();
+            assert_eq!(checksum.algorithm, Algorithm::Sha512);
+        }
+        let checksum = Checksum::from_integrity(sha1).unwrap();
+        assert_eq!(
+            checksum.to_string(),
+            "sha1:a94a8fe5ccb19ba61c4c0873d391e987982fbbd3"
+        );
+    }
+
+    #[test]
+    fn keeps_blobs_under_their_digest_algorithm() {
+        let dest =
+            std::env::temp_dir().join(format!("search-libraries-blobs-{}", std::process::id()));
+        let digest = Checksum::from_digest("sha512:ABC123").unwrap();
+        let path = blob_path(&dest, &digest).unwrap();
+        assert_eq!(path, dest.join("blobs").join("sha512").join("abc123"));
+        assert!(path.parent().unwrap().is_dir());
+        fs::remove_dir_all(&dest).unwrap();
+    }
+}
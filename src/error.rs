@@ -0,0 +1,61 @@
+//! The error type returned by every search.
+
+use reqwest::StatusCode;
+use std::io;
+use std::time::Duration;
+use thiserror::Error;
+
+/// An error raised while querying a registry.
+#[derive(Debug, Error)]
+pub enum SearchError {
+    /// The request could not be sent or its response could not be read.
+    #[error("HTTP request failed: {0}")]
+    Http(#[from] reqwest::Error),
+    /// The response was not the JSON the registry was expected to send.
+    #[error("could not decode the response: {0}")]
+    Decode(#[from] serde_json::Error),
+    /// The registry refused the request because too many were sent.
+    #[error("rate limited{}", retry_after.map(|after| format!(", retry after {}s", after.as_secs())).unwrap_or_default())]
+    RateLimited {
+        /// How long the registry asked to wait before retrying, if it said.
+        retry_after: Option<Duration>,
+    },
+    /// The requested package, version, or registry does not exist.
+    #[error("not found")]
+    NotFound,
+    /// The registry answered with an unsuccessful status code.
+    #[error("registry returned {status}{}", errors.first().map(|error| format!(": {}", error)).unwrap_or_default())]
+    Status {
+        status: StatusCode,
+        /// The start of the response body.
+        body: String,
+        /// The messages of the `errors` array of a JSON error response,
+        /// as sent by crates.io and others.
+        errors: Vec<String>,
+    },
+    /// The response body was larger than the client accepts.
+    #[error("response exceeds the limit of {limit} bytes")]
+    TooLarge {
+        /// The largest body accepted, in bytes.
+        limit: usize,
+    },
+    /// The search was abandoned because it did not finish in time.
+    #[error("no result within {}ms", .0.as_millis())]
+    DeadlineExceeded(Duration),
+    /// Results could not be written in the requested format.
+    #[error("could not encode the output: {0}")]
+    Encode(String),
+    /// A downloaded file does not have the checksum the registry published.
+    #[error("checksum mismatch: expected {expected}, got {actual}")]
+    ChecksumMismatch { expected: String, actual: String },
+    /// The query cannot be sent to the registry as given.
+    #[error("invalid query: {0}")]
+    InvalidQuery(String),
+    /// Reading or writing a local file, such as a cached index, failed.
+    #[error("I/O error: {0}")]
+    Io(#[from] io::Error),
+    /// Reading or writing a SQLite database failed.
+    #[cfg(all(feature = "sqlite", not(target_arch = "wasm32")))]
+    #[error("database error: {0}")]
+    Sqlite(#[from] rusqlite::Error),
+}
@@ -0,0 +1,125 @@
+use crate::error::SearchError;
+use crate::registry::{collect_hits, text, Registry, RegistryError, SearchHit, SearchQuery};
+use crate::ApiClient;
+use async_trait::async_trait;
+use serde_json::{json, Value};
+
+/// Searches for extensions on the Visual Studio Code Marketplace.
+///
+/// # Arguments
+///
+/// * `query` - The search query (optional).
+///
+/// # Returns
+///
+/// Returns the matching extensions as a `serde_json::Value` array or an error.
+pub async fn search_vscode(query: Option<&str>) -> Result<Value, SearchError> {
+    let body = json!({
+        "filters": [{
+            "criteria": [
+                { "filterType": 8, "value": "Microsoft.VisualStudio.Code" },
+                { "filterType": 10, "value": query.unwrap_or("") },
+            ],
+            "pageNumber": 1,
+            "pageSize": 25,
+            "sortBy": 0,
+            "sortOrder": 0,
+        }],
+        "assetTypes": [],
+        "flags": 914,
+    });
+
+    let response = ApiClient::new(
+        "https://marketplace.visualstudio.com/_apis/public/gallery/",
+        None,
+    )
+    .set_header("Accept", "application/json;api-version=3.0-preview.1")
+    .post("extensionquery", &body)
+    .await?;
+
+    Ok(response["results"][0]
+        .get("extensions")
+        .cloned()
+        .unwrap_or_else(|| json!([])))
+}
+
+/// Searches for extensions on the Open VSX Registry.
+///
+/// # Arguments
+///
+/// * `query` - The search query (optional).
+///
+/// # Returns
+///
+/// Returns the matching extensions as a `serde_json::Value` array or an error.
+pub async fn search_openvsx(query: Option<&str>) -> Result<Value, SearchError> {
+    let response = ApiClient::new("https://open-vsx.org/api/-/", None)
+        .set_param("query", query.unwrap_or(""))
+        .set_param("size", "25")
+        .get("search")
+        .await?;
+
+    Ok(response
+        .get("extensions")
+        .cloned()
+        .unwrap_or_else(|| json!([])))
+}
+
+/// The Visual Studio Code Marketplace.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct VsCodeMarketplace;
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl Registry for VsCodeMarketplace {
+    fn name(&self) -> &str {
+        "vscode"
+    }
+
+    async fn search(&self, query: &SearchQuery) -> Result<Vec<SearchHit>, RegistryError> {
+        let results = search_vscode(Some(&query.text))
+            .await
+            .map_err(|error| RegistryError::new(self.name(), error))?;
+        Ok(collect_hits(&results, query.limit, |extension| SearchHit {
+            name: text(&extension["publisher"]["publisherName"])
+                .zip(text(&extension["extensionName"]))
+                .map(|(publisher, name)| format!("{}.{}", publisher, name))
+                .unwrap_or_default(),
+            version: text(&extension["versions"][0]["version"]),
+            description: text(&extension["shortDescription"]),
+            homepage: None,
+            source: self.name().to_string(),
+            ..Default::default()
+        }))
+    }
+}
+
+/// The Open VSX Registry.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct OpenVsx;
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl Registry for OpenVsx {
+    fn name(&self) -> &str {
+        "openvsx"
+    }
+
+    async fn search(&self, query: &SearchQuery) -> Result<Vec<SearchHit>, RegistryError> {
+        let results = search_openvsx(Some(&query.text))
+            .await
+            .map_err(|error| RegistryError::new(self.name(), error))?;
+        Ok(collect_hits(&results, query.limit, |extension| SearchHit {
+            name: text(&extension["namespace"])
+                .zip(text(&extension["name"]))
+                .map(|(namespace, name)| format!("{}.{}", namespace, name))
+                .unwrap_or_default(),
+            version: text(&extension["version"]),
+            description: text(&extension["description"]),
+            homepage: None,
+            downloads: extension["downloadCount"].as_u64(),
+            source: self.name().to_string(),
+            ..Default::default()
+        }))
+    }
+}
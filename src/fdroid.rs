@@ -0,0 +1,102 @@
+use crate::cache::cached_index;
+use crate::error::SearchError;
+use crate::matching::match_score;
+use crate::registry::{collect_hits, text, Registry, RegistryError, SearchHit, SearchQuery};
+use async_trait::async_trait;
+use serde_json::{json, Value};
+use std::time::Duration;
+
+const REPO_INDEX: &str = "https://f-droid.org/repo/index-v2.json";
+const INDEX_MAX_AGE: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Searches for Android apps in the main F-Droid repository.
+///
+/// The repository index is downloaded (and cached for a day) and matched
+/// locally against package ids, app names, and summaries.
+///
+/// # Arguments
+///
+/// * `query` - The search query (optional).
+///
+/// # Returns
+///
+/// Returns the matching apps as a `serde_json::Value` array of
+/// `package`/`name`/`version`/`summary` objects or an error.
+pub async fn search_fdroid(query: Option<&str>) -> Result<Value, SearchError> {
+    let query = query.unwrap_or("");
+    let index = cached_index("fdroid-index-v2.json", REPO_INDEX, INDEX_MAX_AGE).await?;
+
+    let mut hits: Vec<(u8, Value)> = index["packages"]
+        .as_object()
+        .into_iter()
+        .flatten()
+        .filter_map(|(package, app)| {
+            let metadata = &app["metadata"];
+            let name = localized(&metadata["name"]).unwrap_or(package);
+            let summary = localized(&metadata["summary"]).unwrap_or_default();
+            let score = match_score(query, name, summary)
+                .into_iter()
+                .chain(match_score(query, package, ""))
+                .min()?;
+            Some((
+                score,
+                json!({
+                    "package": package,
+                    "name": name,
+                    "version": latest_version_name(&app["versions"]),
+                    "summary": summary,
+                }),
+            ))
+        })
+        .collect();
+
+    hits.sort_by_key(|(score, _)| *score);
+    Ok(hits.into_iter().map(|(_, hit)| hit).collect())
+}
+
+/// Picks the `en-US` text of a localized field, or any translation if missing.
+fn localized(field: &Value) -> Option<&str> {
+    let translations = field.as_object()?;
+    translations
+        .get("en-US")
+        .or_else(|| translations.values().next())?
+        .as_str()
+}
+
+fn latest_version_name(versions: &Value) -> Value {
+    versions
+        .as_object()
+        .into_iter()
+        .flatten()
+        .map(|(_, version)| &version["manifest"])
+        .max_by_key(|manifest| manifest["versionCode"].as_u64().unwrap_or_default())
+        .map(|manifest| manifest["versionName"].clone())
+        .unwrap_or(Value::Null)
+}
+
+/// The main F-Droid repository.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FDroid;
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl Registry for FDroid {
+    fn name(&self) -> &str {
+        "fdroid"
+    }
+
+    async fn search(&self, query: &SearchQuery) -> Result<Vec<SearchHit>, RegistryError> {
+        let results = search_fdroid(Some(&query.text))
+            .await
+            .map_err(|error| RegistryError::new(self.name(), error))?;
+        Ok(collect_hits(&results, query.limit, |app| SearchHit {
+            name: text(&app["package"]).unwrap_or_default(),
+            version: text(&app["version"]),
+            description: text(&app["summary"]),
+            homepage: text(&app["package"])
+                .map(|package| format!("https://f-droid.org/packages/{}/", package)),
+            source: self.name().to_string(),
+            ..Default::default()
+        }))
+    }
+}
@@ -0,0 +1,63 @@
+//! Trimming results down to the fields a caller needs, instead of the
+//! registries' full payloads.
+//!
+//! Fields are named by dot-separated paths into the JSON form of a result,
+//! e.g. `name`, `links.repository`, or `versions.0.num`, where a number
+//! indexes into an array.
+
+use crate::error::SearchError;
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+/// Projects a value onto `fields`.
+///
+/// An array is projected element by element. Anything else becomes an
+/// object with one key per field path, in the order given, set to the value
+/// at that path or `null` where there is none.
+///
+/// # Arguments
+///
+/// * `value` - The value to project, e.g. a hit or a list of hits.
+/// * `fields` - The paths of the fields to keep.
+///
+/// # Returns
+///
+/// Returns the projected value.
+pub fn project(value: &Value, fields: &[&str]) -> Value {
+    match value {
+        Value::Array(items) => items.iter().map(|item| project(item, fields)).collect(),
+        value => {
+            let object: Map<String, Value> = fields
+                .iter()
+                .map(|field| {
+                    let selected = lookup(value, field).cloned().unwrap_or(Value::Null);
+                    (field.to_string(), selected)
+                })
+                .collect();
+            Value::Object(object)
+        }
+    }
+}
+
+/// Serializes data and projects it onto `fields`; see [`project`].
+///
+/// # Arguments
+///
+/// * `data` - The data to project, which must implement `Serialize`, e.g.
+///   a slice of [`SearchHit`](crate::registry::SearchHit)s.
+/// * `fields` - The paths of the fields to keep.
+///
+/// # Returns
+///
+/// Returns the projected value or an error.
+pub fn select_fields<T: Serialize>(data: &T, fields: &[&str]) -> Result<Value, SearchError> {
+    Ok(project(&serde_json::to_value(data)?, fields))
+}
+
+/// Returns the value at a dot-separated `path`, if there is one.
+fn lookup<'a>(value: &'a Value, path: &str) -> Option<&'a Value> {
+    path.split('.').try_fold(value, |value, key| match value {
+        Value::Array(items) => items.get(key.parse::<usize>().ok()?),
+        value => value.get(key),
+    })
+}
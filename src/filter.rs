@@ -0,0 +1,229 @@
+//! Filtering hits after a search by what most registries can't filter on
+//! server-side: downloads, license, recency, and deprecation.
+
+use crate::registry::SearchHit;
+use std::time::Duration;
+use web_time::{SystemTime, UNIX_EPOCH};
+
+/// Conditions a [`SearchHit`] must meet to be kept.
+///
+/// Every condition set must hold. Hits that don't report a field a
+/// condition needs, such as npm hits without download counts, are dropped
+/// unless [`keep_unknown`](Self::keep_unknown) is set.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ResultFilter {
+    /// The fewest downloads a hit may have.
+    pub min_downloads: Option<u64>,
+    /// The SPDX identifiers of the allowed licenses; any license is allowed
+    /// when empty.
+    pub licenses: Vec<String>,
+    /// How recently a hit must have been published.
+    pub updated_within: Option<Duration>,
+    /// Whether to drop deprecated, abandoned, or yanked hits.
+    pub exclude_deprecated: bool,
+    /// Whether to keep hits that don't report a field a condition needs.
+    pub keep_unknown: bool,
+}
+
+impl ResultFilter {
+    /// Creates a filter that keeps every hit.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Keeps only hits with at least `downloads` downloads.
+    pub fn min_downloads(mut self, downloads: u64) -> Self {
+        self.min_downloads = Some(downloads);
+        self
+    }
+
+    /// Allows the license with this SPDX identifier, e.g. `MIT`; once any
+    /// license is allowed, hits under other licenses are dropped.
+    pub fn license(mut self, license: &str) -> Self {
+        self.licenses.push(license.to_string());
+        self
+    }
+
+    /// Keeps only hits published within `age` of now.
+    pub fn updated_within(mut self, age: Duration) -> Self {
+        self.updated_within = Some(age);
+        self
+    }
+
+    /// Drops deprecated, abandoned, and yanked hits.
+    pub fn exclude_deprecated(mut self) -> Self {
+        self.exclude_deprecated = true;
+        self
+    }
+
+    /// Keeps hits that don't report a field a condition needs.
+    pub fn keep_unknown(mut self) -> Self {
+        self.keep_unknown = true;
+        self
+    }
+
+    /// Whether the filter drops nothing.
+    pub fn is_empty(&self) -> bool {
+        self.min_downloads.is_none()
+            && self.licenses.is_empty()
+            && self.updated_within.is_none()
+            && !self.exclude_deprecated
+    }
+
+    /// Returns whether `hit` meets every condition.
+    pub fn matches(&self, hit: &SearchHit) -> bool {
+        if self.exclude_deprecated && hit.deprecated {
+            return false;
+        }
+        if let Some(min) = self.min_downloads {
+            if !hit
+                .downloads
+                .map_or(self.keep_unknown, |downloads| downloads >= min)
+            {
+                return false;
+            }
+        }
+        if !self.licenses.is_empty() {
+            let allowed = |expression: &str| license_allowed(expression, &self.licenses);
+            if !hit.license.as_deref().map_or(self.keep_unknown, allowed) {
+                return false;
+            }
+        }
+        if let Some(age) = self.updated_within {
+            let now = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs();
+            let cutoff = now.saturating_sub(age.as_secs());
+            let updated = hit.updated.as_deref().and_then(parse_timestamp);
+            if !updated.map_or(self.keep_unknown, |updated| updated >= cutoff) {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Returns the hits that meet every condition, in their order.
+    pub fn apply(&self, hits: Vec<SearchHit>) -> Vec<SearchHit> {
+        hits.into_iter().filter(|hit| self.matches(hit)).collect()
+    }
+}
+
+/// Returns whether an SPDX license expression such as `MIT OR Apache-2.0`
+/// can be satisfied with the `allowed` licenses alone.
+///
+/// Parentheses are ignored, so the expression is read as alternatives
+/// joined by `OR` (or `/`, as older crates write it), each of which is a
+/// set of licenses joined by `AND` that must all be allowed.
+fn license_allowed(expression: &str, allowed: &[String]) -> bool {
+    let expression = expression.replace(['(', ')'], " ");
+    expression
+        .split(" OR ")
+        .flat_map(|alternative| alternative.split('/'))
+        .any(|alternative| {
+            alternative.split(" AND ").all(|license| {
+                let license = license.trim();
+                allowed
+                    .iter()
+                    .any(|allowed| allowed.eq_ignore_ascii_case(license))
+            })
+        })
+}
+
+/// Parses the date and time of an RFC 3339 timestamp, such as
+/// `2024-05-01T12:30:00.123Z`, into seconds since the Unix epoch.
+///
+/// Fractions of a second and the UTC offset are ignored; a bare date is
+/// read as midnight.
+fn parse_timestamp(text: &str) -> Option<u64> {
+    let number = |range: std::ops::Range<usize>| text.get(range)?.parse::<u64>().ok();
+    let (year, month, day) = (number(0..4)?, number(5..7)?, number(8..10)?);
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) || year < 1970 {
+        return None;
+    }
+    let seconds = match text.get(10..11) {
+        Some("T" | " ") => number(11..13)? * 3600 + number(14..16)? * 60 + number(17..19)?,
+        _ => 0,
+    };
+
+    // Days since the epoch of the proleptic Gregorian date, after Howard
+    // Hinnant's `days_from_civil`, with years starting in March.
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year / 400;
+    let year_of_era = year - era * 400;
+    let month_index = (month + 9) % 12;
+    let day_of_year = (153 * month_index + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    let days = (era * 146097 + day_of_era).checked_sub(719468)?;
+    Some(days * 86400 + seconds)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn allowed(licenses: &[&str]) -> Vec<String> {
+        licenses.iter().map(|license| license.to_string()).collect()
+    }
+
+    #[test]
+    fn license_expressions_need_one_allowed_alternative() {
+        assert!(license_allowed("MIT OR Apache-2.0", &allowed(&["MIT"])));
+        assert!(license_allowed("MIT/Apache-2.0", &allowed(&["apache-2.0"])));
+        assert!(license_allowed("(MIT)", &allowed(&["MIT"])));
+        assert!(!license_allowed("GPL-3.0-only", &allowed(&["MIT"])));
+    }
+
+    #[test]
+    fn license_conjunctions_need_every_license_allowed() {
+        assert!(!license_allowed("MIT AND BSD-3-Clause", &allowed(&["MIT"])));
+        assert!(license_allowed(
+            "MIT AND BSD-3-Clause",
+            &allowed(&["MIT", "BSD-3-Clause"])
+        ));
+    }
+
+    #[test]
+    fn parses_timestamps_and_dates() {
+        assert_eq!(parse_timestamp("1970-01-01"), Some(0));
+        assert_eq!(parse_timestamp("1970-01-02T00:00:01Z"), Some(86401));
+        assert_eq!(
+            parse_timestamp("2024-05-01T12:30:00.123Z"),
+            Some(1714566600)
+        );
+        assert_eq!(parse_timestamp("2024-05-01 12:30:00"), Some(1714566600));
+    }
+
+    #[test]
+    fn rejects_invalid_timestamps() {
+        assert_eq!(parse_timestamp("2024-13-01"), None);
+        assert_eq!(parse_timestamp("1969-12-31"), None);
+        assert_eq!(parse_timestamp("yesterday"), None);
+        assert_eq!(parse_timestamp("2024-05-01T12"), None);
+    }
+
+    #[test]
+    fn keeps_hits_missing_a_field_only_when_asked() {
+        let hit = SearchHit {
+            name: "serde".to_string(),
+            ..SearchHit::default()
+        };
+        let filter = ResultFilter::new().license("MIT");
+        assert!(!filter.matches(&hit));
+        assert!(filter.keep_unknown().matches(&hit));
+    }
+
+    #[test]
+    fn drops_hits_published_too_long_ago() {
+        let hit = SearchHit {
+            name: "left-pad".to_string(),
+            updated: Some("1970-01-02T00:00:00Z".to_string()),
+            ..SearchHit::default()
+        };
+        let day = Duration::from_secs(24 * 60 * 60);
+        assert!(!ResultFilter::new().updated_within(day).matches(&hit));
+        assert!(ResultFilter::new()
+            .updated_within(Duration::from_secs(u64::MAX))
+            .matches(&hit));
+    }
+}
@@ -0,0 +1,48 @@
+use crate::error::SearchError;
+use crate::registry::{collect_hits, text, Registry, RegistryError, SearchHit, SearchQuery};
+use crate::ApiClient;
+use async_trait::async_trait;
+use serde_json::{json, Value};
+
+/// Searches for applications on Flathub.
+///
+/// # Arguments
+///
+/// * `query` - The search query (optional).
+///
+/// # Returns
+///
+/// Returns the matching applications as a `serde_json::Value` array or an error.
+pub async fn search_flathub(query: Option<&str>) -> Result<Value, SearchError> {
+    let response = ApiClient::new("https://flathub.org/api/v2/", None)
+        .post("search", &json!({ "query": query.unwrap_or("") }))
+        .await?;
+
+    Ok(response.get("hits").cloned().unwrap_or_else(|| json!([])))
+}
+
+/// The Flathub app store.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Flathub;
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl Registry for Flathub {
+    fn name(&self) -> &str {
+        "flathub"
+    }
+
+    async fn search(&self, query: &SearchQuery) -> Result<Vec<SearchHit>, RegistryError> {
+        let results = search_flathub(Some(&query.text))
+            .await
+            .map_err(|error| RegistryError::new(self.name(), error))?;
+        Ok(collect_hits(&results, query.limit, |app| SearchHit {
+            name: text(&app["app_id"]).unwrap_or_default(),
+            version: None,
+            description: text(&app["summary"]),
+            homepage: text(&app["app_id"]).map(|id| format!("https://flathub.org/apps/{}", id)),
+            source: self.name().to_string(),
+            ..Default::default()
+        }))
+    }
+}
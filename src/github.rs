@@ -0,0 +1,110 @@
+use crate::error::SearchError;
+use crate::registry::{collect_hits, text, Registry, RegistryError, SearchHit, SearchQuery};
+use crate::ApiClient;
+use async_trait::async_trait;
+use serde_json::Value;
+
+/// The orderings supported by GitHub repository search.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GitHubSort {
+    #[default]
+    BestMatch,
+    Stars,
+    Forks,
+    HelpWantedIssues,
+    Updated,
+}
+
+impl GitHubSort {
+    /// Returns the value GitHub expects for the `sort` parameter, or `None`
+    /// for the default best-match ordering.
+    pub fn as_str(&self) -> Option<&'static str> {
+        match self {
+            GitHubSort::BestMatch => None,
+            GitHubSort::Stars => Some("stars"),
+            GitHubSort::Forks => Some("forks"),
+            GitHubSort::HelpWantedIssues => Some("help-wanted-issues"),
+            GitHubSort::Updated => Some("updated"),
+        }
+    }
+}
+
+/// Searches for repositories on GitHub.
+///
+/// # Arguments
+///
+/// * `query` - The search query (optional).
+/// * `sort` - The result ordering.
+/// * `language` - Restricts results to repositories in this language (optional).
+/// * `token` - A GitHub token, which raises the unauthenticated rate limit (optional).
+///
+/// # Returns
+///
+/// Returns the search results as a `serde_json::Value` or an error.
+pub async fn search_repos(
+    query: Option<&str>,
+    sort: GitHubSort,
+    language: Option<&str>,
+    token: Option<&str>,
+) -> Result<Value, SearchError> {
+    let mut q = query.unwrap_or("").to_string();
+    if let Some(language) = language {
+        q.push_str(&format!(" language:{}", language));
+    }
+
+    let mut client = ApiClient::new("https://api.github.com/search/", None)
+        .set_param("q", q.trim())
+        .set_param("per_page", "25")
+        .set_header("Accept", "application/vnd.github+json");
+
+    if let Some(sort) = sort.as_str() {
+        client = client.set_param("sort", sort);
+    }
+    if let Some(token) = token {
+        client = client.set_header("Authorization", &format!("Bearer {}", token));
+    }
+
+    client.get("repositories").await
+}
+
+/// Repositories on GitHub.
+#[derive(Debug, Clone, Default)]
+pub struct GitHub {
+    /// The result ordering.
+    pub sort: GitHubSort,
+    /// Restricts results to repositories in this language.
+    pub language: Option<String>,
+    /// A GitHub token, which raises the unauthenticated rate limit.
+    pub token: Option<String>,
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl Registry for GitHub {
+    fn name(&self) -> &str {
+        "github"
+    }
+
+    async fn search(&self, query: &SearchQuery) -> Result<Vec<SearchHit>, RegistryError> {
+        let results = search_repos(
+            Some(&query.text),
+            self.sort,
+            self.language.as_deref(),
+            self.token.as_deref(),
+        )
+        .await
+        .map_err(|error| RegistryError::new(self.name(), error))?;
+        Ok(collect_hits(&results["items"], query.limit, |repo| {
+            SearchHit {
+                name: text(&repo["full_name"]).unwrap_or_default(),
+                version: None,
+                description: text(&repo["description"]),
+                homepage: text(&repo["homepage"]).or_else(|| text(&repo["html_url"])),
+                repository: text(&repo["html_url"]),
+                license: text(&repo["license"]["spdx_id"]),
+                source: self.name().to_string(),
+                ..Default::default()
+            }
+        }))
+    }
+}
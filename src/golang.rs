@@ -0,0 +1,85 @@
+use crate::error::SearchError;
+use crate::html::{extract_text, tag_texts};
+use crate::registry::{collect_hits, text, Registry, RegistryError, SearchHit, SearchQuery};
+use crate::ApiClient;
+use async_trait::async_trait;
+use serde_json::{json, Value};
+
+/// Searches for Go packages on pkg.go.dev.
+///
+/// pkg.go.dev only serves HTML, so every search snippet is parsed into an
+/// object with `name`, `path`, `version`, and `description` fields.
+///
+/// # Arguments
+///
+/// * `query` - The search query (optional).
+/// * `limit` - The maximum number of results to return.
+///
+/// # Returns
+///
+/// Returns the search results as a `serde_json::Value` array or an error.
+pub async fn search_go(query: Option<&str>, limit: u32) -> Result<Value, SearchError> {
+    let html = ApiClient::new("https://pkg.go.dev/search", None)
+        .set_param("q", query.unwrap_or(""))
+        .set_param("limit", &limit.to_string())
+        .set_param("m", "package")
+        .get_text("")
+        .await?;
+
+    Ok(Value::Array(parse_search_results(&html)))
+}
+
+fn parse_search_results(html: &str) -> Vec<Value> {
+    html.split("class=\"SearchSnippet\"")
+        .skip(1)
+        .map(|snippet| {
+            let path = extract_text(snippet, "class=\"SearchSnippet-header-path\"")
+                .trim_matches(|c| c == '(' || c == ')')
+                .to_string();
+            let name = path.rsplit('/').next().unwrap_or_default().to_string();
+            let version = tag_texts(snippet, "strong")
+                .into_iter()
+                .find(|text| is_version(text))
+                .unwrap_or_default();
+
+            json!({
+                "name": name,
+                "path": path,
+                "version": version,
+                "description": extract_text(snippet, "class=\"SearchSnippet-synopsis\""),
+            })
+        })
+        .collect()
+}
+
+fn is_version(text: &str) -> bool {
+    text.strip_prefix('v')
+        .and_then(|rest| rest.chars().next())
+        .is_some_and(|c| c.is_ascii_digit())
+}
+
+/// Go packages as indexed by pkg.go.dev.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct GoPackages;
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl Registry for GoPackages {
+    fn name(&self) -> &str {
+        "go"
+    }
+
+    async fn search(&self, query: &SearchQuery) -> Result<Vec<SearchHit>, RegistryError> {
+        let results = search_go(Some(&query.text), query.limit as u32)
+            .await
+            .map_err(|error| RegistryError::new(self.name(), error))?;
+        Ok(collect_hits(&results, query.limit, |package| SearchHit {
+            name: text(&package["path"]).unwrap_or_default(),
+            version: text(&package["version"]),
+            description: text(&package["description"]),
+            homepage: text(&package["path"]).map(|path| format!("https://pkg.go.dev/{}", path)),
+            source: self.name().to_string(),
+            ..Default::default()
+        }))
+    }
+}
@@ -0,0 +1,52 @@
+use crate::error::SearchError;
+use crate::registry::{collect_hits, text, Registry, RegistryError, SearchHit, SearchQuery};
+use crate::ApiClient;
+use async_trait::async_trait;
+use serde_json::Value;
+
+/// Searches for Haskell packages on Hackage.
+///
+/// # Arguments
+///
+/// * `query` - The search query (optional).
+///
+/// # Returns
+///
+/// Returns the search results as a `serde_json::Value` or an error.
+pub async fn search_hackage(query: Option<&str>) -> Result<Value, SearchError> {
+    ApiClient::new("https://hackage.haskell.org/packages/search", None)
+        .set_param("terms", query.unwrap_or(""))
+        .set_header("Accept", "application/json")
+        .get("")
+        .await
+}
+
+/// The Hackage Haskell package repository.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Hackage;
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl Registry for Hackage {
+    fn name(&self) -> &str {
+        "hackage"
+    }
+
+    async fn search(&self, query: &SearchQuery) -> Result<Vec<SearchHit>, RegistryError> {
+        let results = search_hackage(Some(&query.text))
+            .await
+            .map_err(|error| RegistryError::new(self.name(), error))?;
+        Ok(collect_hits(&results, query.limit, |package| SearchHit {
+            name: text(&package["name"]["display"])
+                .or_else(|| text(&package["name"]))
+                .unwrap_or_default(),
+            version: None,
+            description: text(&package["description"]),
+            homepage: text(&package["name"]["display"])
+                .or_else(|| text(&package["name"]))
+                .map(|name| format!("https://hackage.haskell.org/package/{}", name)),
+            source: self.name().to_string(),
+            ..Default::default()
+        }))
+    }
+}
@@ -0,0 +1,85 @@
+use crate::error::SearchError;
+use crate::registry::{collect_hits, text, Registry, RegistryError, SearchHit, SearchQuery};
+use crate::ApiClient;
+use async_trait::async_trait;
+use serde_json::Value;
+
+/// The orderings supported by the Hex.pm package listing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum HexSort {
+    Name,
+    #[default]
+    TotalDownloads,
+    RecentDownloads,
+    InsertedAt,
+    UpdatedAt,
+}
+
+impl HexSort {
+    /// Returns the value Hex.pm expects for the `sort` parameter.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HexSort::Name => "name",
+            HexSort::TotalDownloads => "total_downloads",
+            HexSort::RecentDownloads => "recent_downloads",
+            HexSort::InsertedAt => "inserted_at",
+            HexSort::UpdatedAt => "updated_at",
+        }
+    }
+}
+
+/// Searches for Elixir and Erlang packages on Hex.pm.
+///
+/// # Arguments
+///
+/// * `query` - The search query (optional).
+/// * `page` - The 1-based result page.
+/// * `sort` - The result ordering.
+///
+/// # Returns
+///
+/// Returns the search results as a `serde_json::Value` or an error.
+pub async fn search_hex(
+    query: Option<&str>,
+    page: u32,
+    sort: HexSort,
+) -> Result<Value, SearchError> {
+    ApiClient::new("https://hex.pm/api/", None)
+        .set_param("search", query.unwrap_or(""))
+        .set_param("page", &page.to_string())
+        .set_param("sort", sort.as_str())
+        .get("packages")
+        .await
+}
+
+/// The Hex.pm registry.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HexPm {
+    /// The result ordering.
+    pub sort: HexSort,
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl Registry for HexPm {
+    fn name(&self) -> &str {
+        "hex"
+    }
+
+    async fn search(&self, query: &SearchQuery) -> Result<Vec<SearchHit>, RegistryError> {
+        let results = search_hex(Some(&query.text), 1, self.sort)
+            .await
+            .map_err(|error| RegistryError::new(self.name(), error))?;
+        Ok(collect_hits(&results, query.limit, |package| SearchHit {
+            name: text(&package["name"]).unwrap_or_default(),
+            version: text(&package["latest_stable_version"])
+                .or_else(|| text(&package["latest_version"])),
+            description: text(&package["meta"]["description"]),
+            homepage: text(&package["html_url"]),
+            downloads: package["downloads"]["all"].as_u64(),
+            license: text(&package["meta"]["licenses"][0]),
+            source: self.name().to_string(),
+            ..Default::default()
+        }))
+    }
+}
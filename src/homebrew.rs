@@ -0,0 +1,138 @@
+use crate::cache::cached_index;
+use crate::error::SearchError;
+use crate::matching::match_score;
+use crate::registry::{collect_hits, text, Registry, RegistryError, SearchHit, SearchQuery};
+use async_trait::async_trait;
+use serde_json::{json, Value};
+use std::time::Duration;
+
+const FORMULA_INDEX: &str = "https://formulae.brew.sh/api/formula.json";
+const CASK_INDEX: &str = "https://formulae.brew.sh/api/cask.json";
+const INDEX_MAX_AGE: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Searches Homebrew formulae and casks.
+///
+/// Homebrew has no search endpoint, so the full formula and cask indexes are
+/// downloaded (and cached for a day) and matched locally. Results are ordered
+/// by how closely the name matches the query.
+///
+/// # Arguments
+///
+/// * `query` - The search query (optional).
+///
+/// # Returns
+///
+/// Returns the matching formulae and casks as a `serde_json::Value` array or an error.
+pub async fn search_homebrew(query: Option<&str>) -> Result<Value, SearchError> {
+    let query = query.unwrap_or("");
+    let mut hits = formula_hits(query).await?;
+    hits.extend(cask_hits(query).await?);
+    hits.sort_by(|(a_score, a), (b_score, b)| {
+        a_score
+            .cmp(b_score)
+            .then_with(|| a["name"].as_str().cmp(&b["name"].as_str()))
+    });
+    Ok(hits.into_iter().map(|(_, hit)| hit).collect())
+}
+
+/// Searches Homebrew formulae only.
+///
+/// # Arguments
+///
+/// * `query` - The search query (optional).
+///
+/// # Returns
+///
+/// Returns the matching formulae as a `serde_json::Value` array or an error.
+pub async fn search_formulae(query: Option<&str>) -> Result<Value, SearchError> {
+    let mut hits = formula_hits(query.unwrap_or("")).await?;
+    hits.sort_by_key(|(score, _)| *score);
+    Ok(hits.into_iter().map(|(_, hit)| hit).collect())
+}
+
+/// Searches Homebrew casks only.
+///
+/// # Arguments
+///
+/// * `query` - The search query (optional).
+///
+/// # Returns
+///
+/// Returns the matching casks as a `serde_json::Value` array or an error.
+pub async fn search_casks(query: Option<&str>) -> Result<Value, SearchError> {
+    let mut hits = cask_hits(query.unwrap_or("")).await?;
+    hits.sort_by_key(|(score, _)| *score);
+    Ok(hits.into_iter().map(|(_, hit)| hit).collect())
+}
+
+async fn formula_hits(query: &str) -> Result<Vec<(u8, Value)>, SearchError> {
+    let index = cached_index("homebrew-formula.json", FORMULA_INDEX, INDEX_MAX_AGE).await?;
+    Ok(entries(&index)
+        .filter_map(|formula| {
+            let name = formula["name"].as_str().unwrap_or_default();
+            let description = formula["desc"].as_str().unwrap_or_default();
+            let score = match_score(query, name, description)?;
+            Some((
+                score,
+                json!({
+                    "name": name,
+                    "version": formula["versions"]["stable"],
+                    "description": description,
+                    "homepage": formula["homepage"],
+                    "kind": "formula",
+                }),
+            ))
+        })
+        .collect())
+}
+
+async fn cask_hits(query: &str) -> Result<Vec<(u8, Value)>, SearchError> {
+    let index = cached_index("homebrew-cask.json", CASK_INDEX, INDEX_MAX_AGE).await?;
+    Ok(entries(&index)
+        .filter_map(|cask| {
+            let token = cask["token"].as_str().unwrap_or_default();
+            let description = cask["desc"].as_str().unwrap_or_default();
+            let score = match_score(query, token, description)?;
+            Some((
+                score,
+                json!({
+                    "name": token,
+                    "version": cask["version"],
+                    "description": description,
+                    "homepage": cask["homepage"],
+                    "kind": "cask",
+                }),
+            ))
+        })
+        .collect())
+}
+
+fn entries(index: &Value) -> impl Iterator<Item = &Value> {
+    index.as_array().into_iter().flatten()
+}
+
+/// Homebrew formulae and casks.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Homebrew;
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl Registry for Homebrew {
+    fn name(&self) -> &str {
+        "brew"
+    }
+
+    async fn search(&self, query: &SearchQuery) -> Result<Vec<SearchHit>, RegistryError> {
+        let results = search_homebrew(Some(&query.text))
+            .await
+            .map_err(|error| RegistryError::new(self.name(), error))?;
+        Ok(collect_hits(&results, query.limit, |formula| SearchHit {
+            name: text(&formula["name"]).unwrap_or_default(),
+            version: text(&formula["version"]),
+            description: text(&formula["description"]),
+            homepage: text(&formula["homepage"]),
+            source: self.name().to_string(),
+            ..Default::default()
+        }))
+    }
+}
@@ -0,0 +1,39 @@
+//! Helpers for scraping sources that only offer an HTML search page.
+
+/// Returns the text content of the first element carrying `marker`,
+/// e.g. `class="package-snippet__name"`.
+pub(crate) fn extract_text(fragment: &str, marker: &str) -> String {
+    fragment
+        .find(marker)
+        .and_then(|start| {
+            let rest = &fragment[start..];
+            let open = rest.find('>')? + 1;
+            let close = rest[open..].find('<')?;
+            Some(unescape(rest[open..open + close].trim()))
+        })
+        .unwrap_or_default()
+}
+
+/// Returns the text of every `<tag>` element in `fragment`, in order.
+pub(crate) fn tag_texts(fragment: &str, tag: &str) -> Vec<String> {
+    let open = format!("<{}>", tag);
+    let close = format!("</{}>", tag);
+    fragment
+        .split(open.as_str())
+        .skip(1)
+        .filter_map(|rest| {
+            rest.find(close.as_str())
+                .map(|end| unescape(rest[..end].trim()))
+        })
+        .collect()
+}
+
+/// Decodes the handful of HTML entities registries emit in plain text.
+pub(crate) fn unescape(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&#x27;", "'")
+        .replace("&amp;", "&")
+}
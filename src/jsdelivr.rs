@@ -0,0 +1,220 @@
+use crate::client::{encode_path, encode_path_segment};
+use crate::error::SearchError;
+use crate::registry::{Registry, RegistryError, SearchHit, SearchQuery};
+use crate::results::SearchResults;
+use crate::ApiClient;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+const DATA_API: &str = "https://data.jsdelivr.com/v1/";
+const CDN: &str = "https://cdn.jsdelivr.net/npm/";
+
+/// The attributes [`search`] retrieves when no others are needed.
+pub const DEFAULT_ATTRIBUTES: &[&str] = &["name", "version", "description", "homepage"];
+
+/// An npm package returned by jsDelivr search.
+///
+/// Only the [`DEFAULT_ATTRIBUTES`] are modeled; any others requested are
+/// available through [`SearchResults::raw`].
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct JsDelivrHit {
+    #[serde(default)]
+    pub name: String,
+    pub version: Option<String>,
+    pub description: Option<String>,
+    pub homepage: Option<String>,
+}
+
+impl From<JsDelivrHit> for SearchHit {
+    fn from(package: JsDelivrHit) -> Self {
+        Self {
+            name: package.name,
+            version: package.version,
+            description: package.description,
+            homepage: package.homepage,
+            repository: None,
+            downloads: None,
+            license: None,
+            updated: None,
+            deprecated: false,
+            source: "jsdelivr".to_string(),
+        }
+    }
+}
+
+/// Searches for npm packages through the Algolia index behind jsdelivr.com.
+///
+/// # Arguments
+///
+/// * `query` - The search query (optional).
+/// * `page` - The 0-based result page.
+/// * `hits_per_page` - The number of results per page.
+/// * `attributes` - The package attributes to retrieve for each hit.
+///
+/// # Returns
+///
+/// Returns the matching packages or an error.
+pub async fn search(
+    query: Option<&str>,
+    page: u32,
+    hits_per_page: u32,
+    attributes: &[&str],
+) -> Result<SearchResults<JsDelivrHit>, SearchError> {
+    let query = query.unwrap_or("");
+
+    // `params` is a URL query string, so `&`, `=`, and `#` in the query must
+    // not end up in it as-is.
+    let payload = serde_json::json!({
+        "params": format!(
+            "query={}&page={}&hitsPerPage={}&attributesToHighlight=%5B%5D&attributesToRetrieve={}",
+            encode_path_segment(query),
+            page,
+            hits_per_page,
+            encode_path_segment(&serde_json::to_string(attributes)?)
+        )
+    });
+
+    let response = ApiClient::new(
+        "https://ofcncog2cu-dsn.algolia.net/1/indexes/npm-search/",
+        None,
+    )
+    .set_header(
+        "x-algolia-agent",
+        "Algolia for JavaScript (3.35.1); Browser (lite)",
+    )
+    .set_header("x-algolia-application-id", "OFCNCOG2CU")
+    .set_header("x-algolia-api-key", "f54e21fa3a2a0160595bb058179bfb1e")
+    .post("query", &payload)
+    .await?;
+
+    SearchResults::from_raw(response, "/hits")
+}
+
+/// Lists the files of an npm package version served by jsDelivr.
+///
+/// # Arguments
+///
+/// * `package` - The npm package name.
+/// * `version` - The exact package version.
+///
+/// # Returns
+///
+/// Returns the package's default file and a flat list of its files (path,
+/// hash, and size) as a `serde_json::Value` or an error.
+pub async fn list_files(package: &str, version: &str) -> Result<Value, SearchError> {
+    ApiClient::new(DATA_API, None)
+        .set_param("structure", "flat")
+        .get(&format!(
+            "{}@{}",
+            package_endpoint(package),
+            encode_path_segment(version)
+        ))
+        .await
+}
+
+/// Fetches the CDN request statistics of an npm package.
+///
+/// # Arguments
+///
+/// * `name` - The npm package name.
+/// * `period` - `day`, `week`, `month`, `quarter`, or `year`.
+///
+/// # Returns
+///
+/// Returns the hit and bandwidth statistics as a `serde_json::Value` or an error.
+pub async fn package_stats(name: &str, period: &str) -> Result<Value, SearchError> {
+    ApiClient::new(DATA_API, None)
+        .set_param("period", period)
+        .get(&format!("stats/{}", package_endpoint(name)))
+        .await
+}
+
+/// Resolves a semver range to the concrete version jsDelivr would serve.
+///
+/// # Arguments
+///
+/// * `name` - The npm package name.
+/// * `range` - A version, range, or dist-tag, e.g. `^4` or `latest`.
+///
+/// # Returns
+///
+/// Returns the resolved version, or `None` if no version matches, or an error.
+pub async fn resolve_version(name: &str, range: &str) -> Result<Option<String>, SearchError> {
+    let resolved = ApiClient::new(DATA_API, None)
+        .set_param("specifier", range)
+        .get(&format!("{}/resolved", package_endpoint(name)))
+        .await?;
+
+    Ok(resolved["version"].as_str().map(str::to_string))
+}
+
+/// Returns the data API endpoint of an npm package, keeping the slash of a
+/// scoped name.
+fn package_endpoint(name: &str) -> String {
+    format!("packages/npm/{}", encode_path(name))
+}
+
+/// Builds the jsDelivr CDN URL of a file in an npm package.
+///
+/// # Arguments
+///
+/// * `package` - The npm package name.
+/// * `version` - A version or semver range, e.g. `3.7.1` or `3`.
+/// * `file` - The file path as returned by [`list_files`], e.g. `/dist/jquery.min.js`.
+///
+/// # Returns
+///
+/// Returns the URL, ready to paste into a `<script>` or `<link>` tag.
+pub fn cdn_url(package: &str, version: &str, file: &str) -> String {
+    format!(
+        "{}{}@{}/{}",
+        CDN,
+        encode_path(package),
+        encode_path_segment(version),
+        encode_path(file.trim_start_matches('/'))
+    )
+}
+
+/// The npm registry as indexed by jsDelivr.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct JsDelivr;
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl Registry for JsDelivr {
+    fn name(&self) -> &str {
+        "jsdelivr"
+    }
+
+    async fn search(&self, query: &SearchQuery) -> Result<Vec<SearchHit>, RegistryError> {
+        let results = search(Some(&query.text), 0, query.limit as u32, DEFAULT_ATTRIBUTES)
+            .await
+            .map_err(|error| RegistryError::new(self.name(), error))?;
+        Ok(results
+            .into_iter()
+            .take(query.limit)
+            .map(SearchHit::from)
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_the_scope_of_package_names_in_endpoints() {
+        assert_eq!(package_endpoint("jquery"), "packages/npm/jquery");
+        assert_eq!(package_endpoint("@babel/core"), "packages/npm/@babel/core");
+        assert_eq!(package_endpoint("a b"), "packages/npm/a%20b");
+    }
+
+    #[test]
+    fn builds_cdn_urls() {
+        assert_eq!(
+            cdn_url("@scope/pkg", "1.0.0", "/dist/a b.js"),
+            format!("{}@scope/pkg@1.0.0/dist/a%20b.js", CDN)
+        );
+    }
+}
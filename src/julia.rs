@@ -0,0 +1,79 @@
+use crate::cache::cached_index;
+use crate::error::SearchError;
+use crate::matching::match_score;
+use crate::registry::{collect_hits, text, Registry, RegistryError, SearchHit, SearchQuery};
+use async_trait::async_trait;
+use serde_json::{json, Value};
+use std::time::Duration;
+
+const PACKAGE_INDEX: &str = "https://juliahub.com/app/packages/info";
+const INDEX_MAX_AGE: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Searches for Julia packages in the General registry via JuliaHub.
+///
+/// JuliaHub publishes its package metadata as a single document, which is
+/// cached for a day and matched locally.
+///
+/// # Arguments
+///
+/// * `query` - The search query (optional).
+///
+/// # Returns
+///
+/// Returns the matching packages as a `serde_json::Value` array of
+/// `name`/`version`/`repository`/`description` objects or an error.
+pub async fn search_julia(query: Option<&str>) -> Result<Value, SearchError> {
+    let query = query.unwrap_or("");
+    let index = cached_index("juliahub-packages.json", PACKAGE_INDEX, INDEX_MAX_AGE).await?;
+
+    let mut hits: Vec<(u8, Value)> = index["packages"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|package| {
+            let name = package["name"].as_str().unwrap_or_default();
+            let metadata = &package["metadata"];
+            let description = metadata["description"].as_str().unwrap_or_default();
+            let score = match_score(query, name, description)?;
+            Some((
+                score,
+                json!({
+                    "name": name,
+                    "version": metadata["version"],
+                    "repository": metadata["repo"],
+                    "description": description,
+                }),
+            ))
+        })
+        .collect();
+
+    hits.sort_by_key(|(score, _)| *score);
+    Ok(hits.into_iter().map(|(_, hit)| hit).collect())
+}
+
+/// The Julia General registry as indexed by JuliaHub.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct JuliaHub;
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl Registry for JuliaHub {
+    fn name(&self) -> &str {
+        "julia"
+    }
+
+    async fn search(&self, query: &SearchQuery) -> Result<Vec<SearchHit>, RegistryError> {
+        let results = search_julia(Some(&query.text))
+            .await
+            .map_err(|error| RegistryError::new(self.name(), error))?;
+        Ok(collect_hits(&results, query.limit, |package| SearchHit {
+            name: text(&package["name"]).unwrap_or_default(),
+            version: text(&package["version"]),
+            description: text(&package["description"]),
+            homepage: text(&package["repository"]),
+            repository: text(&package["repository"]),
+            source: self.name().to_string(),
+            ..Default::default()
+        }))
+    }
+}
@@ -0,0 +1,83 @@
+//! Search package registries from Rust.
+
+pub mod advisories;
+pub mod aggregate;
+pub mod alfred;
+pub mod ansible;
+pub mod apple;
+pub mod apt;
+pub mod artifacthub;
+pub mod aur;
+pub mod auth;
+pub mod availability;
+#[cfg(all(feature = "blocking", not(target_arch = "wasm32")))]
+pub mod blocking;
+pub mod cache;
+pub mod cancel;
+pub mod client;
+pub mod clojars;
+pub mod composer;
+pub mod conan;
+pub mod cpan;
+pub mod cran;
+pub mod crates;
+pub mod deno;
+pub mod dependencies;
+pub mod docker;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod download;
+pub mod error;
+pub mod extensions;
+pub mod fdroid;
+pub mod fields;
+pub mod filter;
+pub mod flathub;
+pub mod github;
+pub mod golang;
+pub mod hackage;
+pub mod hex;
+pub mod homebrew;
+mod html;
+pub mod jsdelivr;
+pub mod julia;
+pub mod librariesio;
+pub mod live;
+pub mod luarocks;
+mod matching;
+pub mod maven;
+pub mod metrics;
+pub mod middleware;
+pub mod nix;
+pub mod npm;
+pub mod nuget;
+pub mod oci;
+pub mod output;
+pub mod paginate;
+pub mod pubdev;
+pub mod pypi;
+pub mod rate_limit;
+pub mod registry;
+pub mod repository;
+pub mod results;
+pub mod rubygems;
+pub mod snap;
+#[cfg(all(feature = "sqlite", not(target_arch = "wasm32")))]
+pub mod sqlite;
+pub mod terraform;
+pub mod vcpkg;
+pub mod vcr;
+pub mod versions;
+pub mod windows;
+
+pub use aggregate::{
+    rank_hits, search_all, search_all_with_deadline, AggregateResults, RankingStrategy,
+};
+pub use auth::Auth;
+pub use availability::{check_name_availability, Availability, NameAvailability};
+pub use cache::ResponseCache;
+pub use client::{compat, default_client, set_default_user_agent, ApiClient, ApiClientBuilder};
+pub use error::SearchError;
+pub use filter::ResultFilter;
+pub use paginate::{collect_n, paginate, Page};
+pub use registry::{Registry, RegistryConfig, RegistryError, SearchHit, SearchQuery};
+pub use results::SearchResults;
@@ -0,0 +1,68 @@
+use crate::error::SearchError;
+use crate::registry::{collect_hits, text, Registry, RegistryError, SearchHit, SearchQuery};
+use crate::ApiClient;
+use async_trait::async_trait;
+use serde_json::Value;
+
+/// Searches for packages across every ecosystem indexed by Libraries.io.
+///
+/// # Arguments
+///
+/// * `query` - The search query (optional).
+/// * `platforms` - A comma-separated list of platforms to restrict results to,
+///   e.g. `cargo,npm` (optional).
+/// * `api_key` - A Libraries.io API key, which every request requires.
+///
+/// # Returns
+///
+/// Returns the search results as a `serde_json::Value` or an error.
+pub async fn search_librariesio(
+    query: Option<&str>,
+    platforms: Option<&str>,
+    api_key: &str,
+) -> Result<Value, SearchError> {
+    let mut client = ApiClient::new("https://libraries.io/api/", None)
+        .set_param("q", query.unwrap_or(""))
+        .set_param("per_page", "25")
+        .set_param("api_key", api_key);
+
+    if let Some(platforms) = platforms {
+        client = client.set_param("platforms", platforms);
+    }
+
+    client.get("search").await
+}
+
+/// The Libraries.io cross-ecosystem index.
+#[derive(Debug, Clone)]
+pub struct LibrariesIo {
+    /// A comma-separated list of platforms to restrict results to.
+    pub platforms: Option<String>,
+    /// A Libraries.io API key.
+    pub api_key: String,
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl Registry for LibrariesIo {
+    fn name(&self) -> &str {
+        "librariesio"
+    }
+
+    async fn search(&self, query: &SearchQuery) -> Result<Vec<SearchHit>, RegistryError> {
+        let results =
+            search_librariesio(Some(&query.text), self.platforms.as_deref(), &self.api_key)
+                .await
+                .map_err(|error| RegistryError::new(self.name(), error))?;
+        Ok(collect_hits(&results, query.limit, |package| SearchHit {
+            name: text(&package["name"]).unwrap_or_default(),
+            version: text(&package["latest_release_number"]),
+            description: text(&package["description"]),
+            homepage: text(&package["homepage"]).or_else(|| text(&package["repository_url"])),
+            repository: text(&package["repository_url"]),
+            license: text(&package["normalized_licenses"][0]),
+            source: self.name().to_string(),
+            ..Default::default()
+        }))
+    }
+}
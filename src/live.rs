@@ -0,0 +1,271 @@
+//! Type-ahead searching for Alfred, Raycast, fzf and similar frontends.
+
+use futures::future::poll_fn;
+use futures::stream::{self, Stream, StreamExt};
+use futures::FutureExt;
+use futures_timer::Delay;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::Poll;
+use std::time::Duration;
+
+/// How long the query has to stay unchanged by default before it is
+/// searched.
+pub const DEFAULT_DEBOUNCE: Duration = Duration::from_millis(150);
+
+/// Turns a stream of queries, such as the contents of a search box after
+/// each keystroke, into a stream of results.
+///
+/// A query is only searched once no newer one has arrived for the debounce
+/// period. A newer query cancels the search of the previous one by dropping
+/// it, so results never arrive out of order and stale results are never
+/// yielded.
+///
+/// ```no_run
+/// # async fn run(keystrokes: impl futures::Stream<Item = String>) {
+/// use futures::StreamExt;
+/// use search_libraries::live::LiveSearch;
+/// use search_libraries::search_all;
+///
+/// let results = LiveSearch::new(|query: String| async move {
+///     search_all(&query, &["crates", "npm"], 10).await
+/// })
+/// .min_length(2)
+/// .run(keystrokes);
+/// futures::pin_mut!(results);
+/// while let Some((query, results)) = results.next().await {
+///     println!("{}: {} hits", query, results.hits.len());
+/// }
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct LiveSearch<F> {
+    search: F,
+    debounce: Duration,
+    min_length: usize,
+}
+
+impl<F, Fut> LiveSearch<F>
+where
+    F: FnMut(String) -> Fut,
+    Fut: Future,
+{
+    /// Creates a live search that runs `search` for each settled query.
+    pub fn new(search: F) -> Self {
+        Self {
+            search,
+            debounce: DEFAULT_DEBOUNCE,
+            min_length: 1,
+        }
+    }
+
+    /// Sets how long a query has to stay unchanged before it is searched.
+    /// Defaults to [`DEFAULT_DEBOUNCE`].
+    pub fn debounce(mut self, debounce: Duration) -> Self {
+        self.debounce = debounce;
+        self
+    }
+
+    /// Sets the number of characters, ignoring surrounding whitespace, a
+    /// query needs before it is searched. Defaults to 1, so empty queries
+    /// are skipped.
+    pub fn min_length(mut self, min_length: usize) -> Self {
+        self.min_length = min_length;
+        self
+    }
+
+    /// Searches the settled queries of `queries`.
+    ///
+    /// # Arguments
+    ///
+    /// * `queries` - The queries, each replacing the one before it.
+    ///
+    /// # Returns
+    ///
+    /// Returns a stream of each searched query with its results. The stream
+    /// ends once `queries` has ended and the last search has finished.
+    pub fn run<S>(self, queries: S) -> impl Stream<Item = (String, Fut::Output)>
+    where
+        S: Stream<Item = String>,
+    {
+        let state = State {
+            search: self.search,
+            debounce: self.debounce,
+            min_length: self.min_length,
+            queries: Box::pin(queries),
+            exhausted: false,
+            pending: None,
+            timer: None,
+            in_flight: None,
+            last: None,
+        };
+        stream::unfold(state, |mut state| async move {
+            let output = state.next().await?;
+            Some((output, state))
+        })
+    }
+}
+
+enum Event<T> {
+    Query(Option<String>),
+    Settled,
+    Done(T),
+}
+
+struct State<F, Fut: Future, S> {
+    search: F,
+    debounce: Duration,
+    min_length: usize,
+    queries: Pin<Box<S>>,
+    exhausted: bool,
+    pending: Option<String>,
+    timer: Option<Delay>,
+    in_flight: Option<(String, Pin<Box<Fut>>)>,
+    last: Option<String>,
+}
+
+impl<F, Fut, S> State<F, Fut, S>
+where
+    F: FnMut(String) -> Fut,
+    Fut: Future,
+    S: Stream<Item = String>,
+{
+    /// Waits for the next search to finish, handling the queries and
+    /// debounce timers that come first.
+    async fn next(&mut self) -> Option<(String, Fut::Output)> {
+        loop {
+            if self.exhausted && self.timer.is_none() && self.in_flight.is_none() {
+                return None;
+            }
+
+            match self.event().await {
+                Event::Query(Some(query)) => {
+                    // The new query supersedes the one being searched.
+                    if self.in_flight.take().is_some() {
+                        self.last = None;
+                    }
+                    self.pending = Some(query);
+                    self.timer = Some(Delay::new(self.debounce));
+                }
+                Event::Query(None) => self.exhausted = true,
+                Event::Settled => {
+                    self.timer = None;
+                    let Some(query) = self.pending.take() else {
+                        continue;
+                    };
+                    let query = query.trim().to_string();
+                    if query.chars().count() < self.min_length || self.last.as_ref() == Some(&query)
+                    {
+                        continue;
+                    }
+                    self.last = Some(query.clone());
+                    let search = Box::pin((self.search)(query.clone()));
+                    self.in_flight = Some((query, search));
+                }
+                Event::Done(output) => {
+                    let (query, _) = self.in_flight.take()?;
+                    return Some((query, output));
+                }
+            }
+        }
+    }
+
+    /// Waits for a search to finish, a query to arrive, or the debounce
+    /// timer to fire, in that order of preference.
+    async fn event(&mut self) -> Event<Fut::Output> {
+        poll_fn(|cx| {
+            if let Some((_, search)) = &mut self.in_flight {
+                if let Poll::Ready(output) = search.as_mut().poll(cx) {
+                    return Poll::Ready(Event::Done(output));
+                }
+            }
+            if !self.exhausted {
+                if let Poll::Ready(query) = self.queries.poll_next_unpin(cx) {
+                    return Poll::Ready(Event::Query(query));
+                }
+            }
+            if let Some(timer) = &mut self.timer {
+                if timer.poll_unpin(cx).is_ready() {
+                    return Poll::Ready(Event::Settled);
+                }
+            }
+            Poll::Pending
+        })
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+
+    const DEBOUNCE: Duration = Duration::from_millis(20);
+
+    /// Yields each query after its delay, as a user typing would.
+    fn typed(queries: Vec<(u64, &str)>) -> impl Stream<Item = String> + '_ {
+        stream::iter(queries).then(|(delay, query)| async move {
+            Delay::new(Duration::from_millis(delay)).await;
+            query.to_string()
+        })
+    }
+
+    /// Runs a live search whose searches take `search_time`, returning the
+    /// queries searched and those whose results were yielded.
+    async fn run(
+        queries: Vec<(u64, &str)>,
+        search_time: Duration,
+        min_length: usize,
+    ) -> (Vec<String>, Vec<String>) {
+        let searched = RefCell::new(Vec::new());
+        let results: Vec<_> = LiveSearch::new(|query: String| {
+            searched.borrow_mut().push(query.clone());
+            async move {
+                Delay::new(search_time).await;
+                query
+            }
+        })
+        .debounce(DEBOUNCE)
+        .min_length(min_length)
+        .run(typed(queries))
+        .map(|(query, result)| {
+            assert_eq!(query, result);
+            query
+        })
+        .collect()
+        .await;
+        (searched.into_inner(), results)
+    }
+
+    #[tokio::test]
+    async fn searches_only_the_query_a_burst_settles_on() {
+        let queries = vec![(0, "s"), (0, "se"), (0, "ser"), (0, "serde")];
+        let (searched, results) = run(queries, Duration::ZERO, 1).await;
+        assert_eq!(searched, ["serde"]);
+        assert_eq!(results, ["serde"]);
+    }
+
+    #[tokio::test]
+    async fn searches_each_query_that_settles() {
+        let queries = vec![(0, "serde"), (100, "tokio")];
+        let (searched, results) = run(queries, Duration::ZERO, 1).await;
+        assert_eq!(searched, ["serde", "tokio"]);
+        assert_eq!(results, ["serde", "tokio"]);
+    }
+
+    #[tokio::test]
+    async fn drops_the_search_a_newer_query_supersedes() {
+        let queries = vec![(0, "serde"), (100, "tokio")];
+        let (searched, results) = run(queries, Duration::from_millis(300), 1).await;
+        assert_eq!(searched, ["serde", "tokio"]);
+        assert_eq!(results, ["tokio"]);
+    }
+
+    #[tokio::test]
+    async fn skips_short_and_repeated_queries() {
+        let queries = vec![(0, "s"), (100, " serde "), (100, "serde")];
+        let (searched, results) = run(queries, Duration::ZERO, 2).await;
+        assert_eq!(searched, ["serde"]);
+        assert_eq!(results, ["serde"]);
+    }
+}
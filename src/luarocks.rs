@@ -0,0 +1,82 @@
+use crate::cache::cached_index;
+use crate::error::SearchError;
+use crate::matching::{compare_versions, match_score};
+use crate::registry::{collect_hits, text, Registry, RegistryError, SearchHit, SearchQuery};
+use async_trait::async_trait;
+use serde_json::{json, Value};
+use std::time::Duration;
+
+const ROOT_MANIFEST: &str = "https://luarocks.org/manifest.json";
+const INDEX_MAX_AGE: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Searches for rocks on LuaRocks.
+///
+/// The root manifest lists every rock and its versions; it is cached for a
+/// day and matched locally, keeping only the latest version of each rock.
+///
+/// # Arguments
+///
+/// * `query` - The search query (optional).
+///
+/// # Returns
+///
+/// Returns the matching rocks as a `serde_json::Value` array of
+/// `name`/`version`/`versions` objects or an error.
+pub async fn search_luarocks(query: Option<&str>) -> Result<Value, SearchError> {
+    let query = query.unwrap_or("");
+    let manifest = cached_index("luarocks-manifest.json", ROOT_MANIFEST, INDEX_MAX_AGE).await?;
+
+    let mut hits: Vec<(u8, Value)> = manifest["repository"]
+        .as_object()
+        .into_iter()
+        .flatten()
+        .filter_map(|(name, versions)| {
+            let score = match_score(query, name, "")?;
+            let mut versions: Vec<&str> = versions
+                .as_object()
+                .into_iter()
+                .flatten()
+                .map(|(version, _)| version.as_str())
+                .collect();
+            versions.sort_by(|a, b| compare_versions(b, a));
+            Some((
+                score,
+                json!({
+                    "name": name,
+                    "version": versions.first(),
+                    "versions": versions,
+                }),
+            ))
+        })
+        .collect();
+
+    hits.sort_by_key(|(score, _)| *score);
+    Ok(hits.into_iter().map(|(_, hit)| hit).collect())
+}
+
+/// The LuaRocks repository.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LuaRocks;
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl Registry for LuaRocks {
+    fn name(&self) -> &str {
+        "luarocks"
+    }
+
+    async fn search(&self, query: &SearchQuery) -> Result<Vec<SearchHit>, RegistryError> {
+        let results = search_luarocks(Some(&query.text))
+            .await
+            .map_err(|error| RegistryError::new(self.name(), error))?;
+        Ok(collect_hits(&results, query.limit, |rock| SearchHit {
+            name: text(&rock["name"]).unwrap_or_default(),
+            version: text(&rock["version"]),
+            description: None,
+            homepage: text(&rock["name"])
+                .map(|name| format!("https://luarocks.org/search?q={}", name)),
+            source: self.name().to_string(),
+            ..Default::default()
+        }))
+    }
+}
@@ -1,210 +1,1028 @@
-use reqwest::Client;
-use serde::Serialize;
-use serde_json::Value;
-use std::collections::HashMap;
+mod config;
+#[cfg(feature = "tui")]
+mod tui;
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use config::Config;
+use search_libraries::aggregate::{search_all, RankingStrategy};
+use search_libraries::alfred::ScriptFilter;
+use search_libraries::dependencies::dependency_tree;
+use search_libraries::download::download_artifact;
+use search_libraries::fields::select_fields;
+#[cfg(feature = "toml")]
+use search_libraries::output::write_toml;
+#[cfg(feature = "yaml")]
+use search_libraries::output::write_yaml;
+use search_libraries::output::{
+    write_alfred, write_csv, write_html, write_json, write_markdown, write_ndjson, write_plain,
+    write_table, write_tsv,
+};
+use search_libraries::registry::{registry_by_name, Registry, SearchHit, SearchQuery};
+use search_libraries::repository::find_by_repository;
+#[cfg(feature = "sqlite")]
+use search_libraries::sqlite::save_hits;
+use search_libraries::versions::resolve_version;
+use search_libraries::{
+    advisories, ansible, apple, apt, artifacthub, aur, clojars, composer, conan, cpan, cran,
+    crates, deno, docker, extensions, fdroid, flathub, github, golang, hackage, hex, homebrew,
+    jsdelivr, julia, librariesio, luarocks, maven, nix, npm, nuget, oci, pubdev, pypi, rubygems,
+    snap, terraform, vcpkg, windows, ResultFilter, SearchError, SearchResults,
+};
+use serde::Deserialize;
+use serde_json::{json, Value};
 use std::error::Error;
+use std::fmt::Display;
 use std::fs::File;
-use std::io::Write;
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::time::Duration;
+
+/// The sources the `search` subcommand supports.
+const SEARCH_SOURCES: &str = "'npm', 'docker', 'jsdelivr', 'crates', 'composer', 'pypi', 'gems', 'maven', 'nuget', 'go', 'hex', 'brew', 'aur', 'apt', 'hackage', 'pub', 'cocoapods', 'swiftpm', 'conan', 'vcpkg', 'cran', 'julia', 'luarocks', 'nix', 'flathub', 'snap', 'choco', 'winget', 'scoop', 'galaxy', 'terraform', 'artifacthub', 'cpan', 'clojars', 'jsr', 'denoland', 'fdroid', 'vscode', 'openvsx', 'github', 'ghcr', 'quay', and 'librariesio'";
+
+/// How the exit status is described in `--help`.
+const EXIT_STATUS: &str = "Exit status: 0 if anything was found, 1 if nothing was, 2 on a usage error, and 3 if a registry could not be reached or failed.";
+
+/// Searches package registries from the command line.
+#[derive(Debug, Parser)]
+#[command(name = "search", version, about, after_help = EXIT_STATUS)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+    /// Print nothing to stdout, so that only the exit status tells whether
+    /// anything was found.
+    #[arg(long, short, global = true)]
+    quiet: bool,
+}
 
-/// A client for making API requests.
-pub struct ApiClient {
-    search_url: String,
-    params: HashMap<String, String>,
-    user_agent: Option<String>,
+/// The exit status of the binary.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Status {
+    /// Something was found.
+    Found = 0,
+    /// Nothing was found.
+    Empty = 1,
+    /// The command line or the config file is invalid.
+    Usage = 2,
+    /// A registry could not be reached or answered with an error.
+    Failed = 3,
+}
+
+impl Status {
+    /// Returns the status of a search or lookup that returned `result`, of
+    /// which `found` tells whether it holds anything.
+    fn of<T>(result: &Result<T, SearchError>, found: impl Fn(&T) -> bool) -> Self {
+        match result {
+            Ok(data) if found(data) => Self::Found,
+            Ok(_) | Err(SearchError::NotFound) => Self::Empty,
+            Err(SearchError::InvalidQuery(_)) => Self::Usage,
+            Err(_) => Self::Failed,
+        }
+    }
 }
 
-impl ApiClient {
-    /// Creates a new `ApiClient` with the specified search URL and optional user agent.
-    pub fn new(search_url: &str, user_agent: Option<&str>) -> Self {
-        Self {
-            search_url: search_url.to_string(),
-            params: HashMap::new(),
-            user_agent: user_agent.map(|ua| ua.to_string()),
+impl From<Status> for ExitCode {
+    fn from(status: Status) -> Self {
+        ExitCode::from(status as u8)
+    }
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Search a registry for packages.
+    Search(SearchArgs),
+    /// Show the metadata of a package (crates, npm, composer).
+    Info(PackageArgs),
+    /// List the published versions of a package (crates, npm, composer, docker).
+    Versions(PackageArgs),
+    /// Resolve a version requirement, e.g. `^1.2`, to the newest matching
+    /// version of a package (crates, npm, composer).
+    Resolve {
+        #[command(flatten)]
+        package: PackageArgs,
+        /// The version requirement, e.g. `^1.2` or `>=1.0, <2.0`.
+        requirement: String,
+    },
+    /// List the dependencies of a package version (crates, npm, composer).
+    Deps {
+        #[command(flatten)]
+        package: PackageArgs,
+        /// The version to inspect; defaults to the latest stable release.
+        #[arg(long)]
+        version: Option<String>,
+    },
+    /// Show the dependency tree of a package version (crates, npm).
+    Tree {
+        #[command(flatten)]
+        package: PackageArgs,
+        /// The version to inspect; defaults to the latest release.
+        #[arg(long)]
+        version: Option<String>,
+        /// How many levels of dependencies to fetch.
+        #[arg(long, default_value_t = 2)]
+        depth: usize,
+        /// How to print the tree.
+        #[arg(long, value_enum, default_value_t = TreeStyle::Tree)]
+        style: TreeStyle,
+    },
+    /// List the packages on crates.io, npm, Packagist, and PyPI published
+    /// from a repository.
+    Repo {
+        /// The repository URL, e.g. `https://github.com/serde-rs/serde`.
+        url: String,
+    },
+    /// Download the published file of a package version, verified against
+    /// the registry's checksum (crates, npm, docker).
+    Download {
+        #[command(flatten)]
+        package: PackageArgs,
+        /// The exact version, or the image tag for docker.
+        version: String,
+        /// The file to write, or the directory for a docker image.
+        dest: PathBuf,
+    },
+    /// Show the download counts of a package (crates, npm, composer, jsdelivr).
+    Downloads {
+        #[command(flatten)]
+        package: PackageArgs,
+        /// The period to count, for npm and jsdelivr, e.g. `last-week` or `month`.
+        #[arg(long)]
+        period: Option<String>,
+    },
+    /// Browse search results interactively.
+    #[cfg(feature = "tui")]
+    Tui {
+        /// The number of results to fetch from each registry.
+        #[arg(long, default_value_t = 25)]
+        limit: usize,
+    },
+    /// Look up the security advisories of a package in the OSV database.
+    Advisories {
+        /// The OSV ecosystem, e.g. `crates.io`, `npm`, or `PyPI`.
+        ecosystem: String,
+        /// The package name.
+        name: String,
+        /// Only report advisories affecting this version.
+        version: Option<String>,
+    },
+}
+
+#[derive(Debug, Args)]
+struct SearchArgs {
+    /// The registry to search, e.g. `crates` or `npm`, then the search
+    /// query; only the query with `--all`, `--sources`, or a default source
+    /// in the config file.
+    #[arg(value_name = "SOURCE QUERY", num_args = 1..=2, required = true)]
+    terms: Vec<String>,
+    /// Search every registry that needs no credentials at once.
+    #[arg(long, conflicts_with = "sources")]
+    all: bool,
+    /// Search these registries at once, e.g. `crates,npm,pypi`.
+    #[arg(long, value_delimiter = ',')]
+    sources: Vec<String>,
+    /// The 1-based result page, for sources that page their results; only
+    /// with `--format json`.
+    #[arg(long, default_value_t = 1)]
+    page: u32,
+    /// The number of results per page, for sources that take one
+    /// [default: 25].
+    #[arg(long)]
+    limit: Option<u32>,
+    /// The result ordering, for crates, hex, and github, e.g. `downloads`;
+    /// only with `--format json`.
+    #[arg(long)]
+    sort: Option<String>,
+    /// Drop hits with fewer downloads than this.
+    #[arg(long, value_name = "COUNT")]
+    min_downloads: Option<u64>,
+    /// Keep only hits under these SPDX licenses, e.g. `MIT,Apache-2.0`.
+    #[arg(long, value_delimiter = ',')]
+    license: Vec<String>,
+    /// Keep only hits published within this many days.
+    #[arg(long, value_name = "DAYS")]
+    updated_within: Option<u64>,
+    /// Drop deprecated, abandoned, and yanked hits.
+    #[arg(long)]
+    exclude_deprecated: bool,
+    /// Keep hits that don't report what a filter needs, such as crates.io
+    /// hits, which have no license, under `--license`.
+    #[arg(long)]
+    keep_unknown: bool,
+    /// How to print the results; every format but `json` shows the
+    /// normalized hits of the first page. Defaults to `json`, or to `table`
+    /// when searching several registries.
+    #[arg(long, value_enum)]
+    format: Option<Format>,
+    /// Write the results to this file instead of stdout, in the format its
+    /// extension implies (.json, .ndjson, .csv, .tsv, .yaml, .toml, .md, .html, or .db) unless `--format` is
+    /// given.
+    #[arg(long, short, value_name = "PATH")]
+    output: Option<PathBuf>,
+    /// Print only these fields of each normalized hit, e.g.
+    /// `name,version,downloads`, with the json, ndjson, or yaml format.
+    #[arg(long, value_delimiter = ',')]
+    fields: Vec<String>,
+    /// The registry searched when only a query is given, from the config
+    /// file or the environment.
+    #[arg(skip)]
+    default_source: Option<String>,
+}
+
+impl SearchArgs {
+    /// Whether several registries are searched at once.
+    fn aggregate(&self) -> bool {
+        self.all || !self.sources.is_empty()
+    }
+
+    /// Returns the registry to search, unless several are.
+    fn source(&self) -> &str {
+        match (self.aggregate(), self.terms.len()) {
+            (true, _) => "",
+            (false, 2) => &self.terms[0],
+            (false, _) => self.default_source.as_deref().unwrap_or_default(),
         }
     }
 
-    /// Sets a query parameter for the API request.
-    pub fn set_param(mut self, key: &str, value: &str) -> Self {
-        self.params.insert(key.to_string(), value.to_string());
-        self
+    /// Returns the filter the hits must pass.
+    fn filter(&self) -> ResultFilter {
+        let mut filter = ResultFilter::new();
+        if let Some(downloads) = self.min_downloads {
+            filter = filter.min_downloads(downloads);
+        }
+        for license in &self.license {
+            filter = filter.license(license);
+        }
+        if let Some(days) = self.updated_within {
+            filter = filter.updated_within(Duration::from_secs(days.saturating_mul(24 * 60 * 60)));
+        }
+        if self.exclude_deprecated {
+            filter = filter.exclude_deprecated();
+        }
+        if self.keep_unknown {
+            filter = filter.keep_unknown();
+        }
+        filter
     }
 
-    /// Sends a GET request to the specified endpoint.
-    pub async fn get(&self, endpoint: &str) -> Result<Value, Box<dyn Error>> {
-        let url = format!("{}{}", self.search_url, endpoint);
-        let client = Client::new();
-        let mut request = client.get(&url).query(&self.params);
+    /// Returns the settings that only the registry's own response honours,
+    /// which a search of normalized hits can't apply.
+    fn raw_only_settings(&self, config: &Config) -> Vec<String> {
+        let mut settings = Vec::new();
+        if self.sort.is_some() {
+            settings.push("--sort".to_string());
+        }
+        if self.page > 1 {
+            settings.push("--page".to_string());
+        }
+        if config.registries.contains_key(self.source()) {
+            settings.push(format!("the [registries.{}] config", self.source()));
+        }
+        settings
+    }
+
+    /// Returns the number of results per page.
+    fn limit(&self) -> u32 {
+        self.limit.unwrap_or(25)
+    }
+
+    /// Returns the search query.
+    fn query(&self) -> &str {
+        self.terms.last().map(String::as_str).unwrap_or_default()
+    }
 
-        if let Some(user_agent) = &self.user_agent {
-            request = request.header("User-Agent", user_agent);
+    fn format(&self) -> Format {
+        let inferred = self.output.as_deref().and_then(Format::from_extension);
+        match self.format.or(inferred) {
+            Some(format) => format,
+            None if self.aggregate() => Format::Table,
+            None => Format::Json,
         }
+    }
 
-        let response = request.send().await?;
-        Ok(response.json().await?)
+    /// Returns a usage error if the positional arguments do not fit the
+    /// kind of search.
+    fn validate(&self) -> Result<(), String> {
+        match (self.aggregate(), self.terms.len()) {
+            (true, 2) => Err("--all and --sources take only a query, not a source".to_string()),
+            (false, 1) if self.default_source.is_none() => {
+                Err("a source and a query are required".to_string())
+            }
+            _ => match &self.output {
+                Some(path) if self.format.is_none() && Format::from_extension(path).is_none() => {
+                    Err(format!(
+                        "cannot tell the output format of '{}'; use a .json, .ndjson, .csv, .tsv, .yaml, .toml, .md, .html, or .db file, or --format",
+                        path.display()
+                    ))
+                }
+                _ if !self.fields.is_empty() && !self.format().projects() => Err(format!(
+                    "--fields does not work with --format {:?}; use json, ndjson, or yaml",
+                    self.format()
+                )),
+                #[cfg(feature = "sqlite")]
+                None if self.format() == Format::Sqlite => {
+                    Err("--format sqlite needs a database file to write to, given with --output".to_string())
+                }
+                _ => Ok(()),
+            },
+        }
     }
 }
 
-/// Searches for crates on crates.io.
-///
-/// # Arguments
-///
-/// * `query` - The search query (optional).
-///
-/// # Returns
-///
-/// Returns the search results as a `serde_json::Value` or an error.
-pub async fn search_crates(query: Option<&str>) -> Result<Value, Box<dyn Error>> {
-    ApiClient::new(
-        "https://crates.io/api/v1/",
-        Some("my_crawler (help@my_crawler.com)"),
-    )
-    .set_param("page", "1")
-    .set_param("per_page", "25")
-    .set_param("q", query.unwrap_or(""))
-    .get("crates")
-    .await
+/// How the `search` subcommand prints its results.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum Format {
+    /// The registry's response as pretty-printed JSON.
+    Json,
+    /// An aligned table of name, version, downloads, and description.
+    Table,
+    /// One compact JSON object per line.
+    Ndjson,
+    /// CSV with a header row.
+    Csv,
+    /// Tab-separated values with a header row.
+    Tsv,
+    /// One tab-separated line per hit, for piping into fzf.
+    Plain,
+    /// Alfred Script Filter JSON.
+    Alfred,
+    /// YAML, with the `yaml` feature.
+    #[cfg(feature = "yaml")]
+    Yaml,
+    /// TOML, with the `toml` feature.
+    #[cfg(feature = "toml")]
+    Toml,
+    /// A Markdown table, for pasting into issues and docs.
+    Markdown,
+    /// A standalone HTML page with a table of the hits.
+    Html,
+    /// Rows of the `hits` table of the `--output` SQLite database, with the
+    /// `sqlite` feature.
+    #[cfg(feature = "sqlite")]
+    Sqlite,
 }
 
-/// Searches for packages on npm.
-///
-/// # Arguments
-///
-/// * `query` - The search query (optional).
-///
-/// # Returns
-///
-/// Returns the search results as a `serde_json::Value` or an error.
-pub async fn search_npm(query: Option<&str>) -> Result<Value, Box<dyn Error>> {
-    ApiClient::new("https://api.npms.io/v2/search/", None)
-        .set_param("q", query.unwrap_or(""))
-        .set_param("size", "25")
-        .get("")
-        .await
+impl Format {
+    /// Whether `--fields` can trim the hits written in this format.
+    fn projects(self) -> bool {
+        match self {
+            Self::Json | Self::Ndjson => true,
+            #[cfg(feature = "yaml")]
+            Self::Yaml => true,
+            _ => false,
+        }
+    }
+
+    /// Returns the format a file name's extension implies, if any.
+    fn from_extension(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()?.to_ascii_lowercase().as_str() {
+            "json" => Some(Self::Json),
+            "ndjson" | "jsonl" => Some(Self::Ndjson),
+            "csv" => Some(Self::Csv),
+            "tsv" => Some(Self::Tsv),
+            #[cfg(feature = "yaml")]
+            "yaml" | "yml" => Some(Self::Yaml),
+            #[cfg(feature = "toml")]
+            "toml" => Some(Self::Toml),
+            "md" | "markdown" => Some(Self::Markdown),
+            "html" | "htm" => Some(Self::Html),
+            #[cfg(feature = "sqlite")]
+            "db" | "sqlite" | "sqlite3" => Some(Self::Sqlite),
+            _ => None,
+        }
+    }
 }
 
-/// Searches for packages on jsDelivr with Alfred-style output.
-///
-/// # Arguments
-///
-/// * `query` - The search query (optional).
-///
-/// # Returns
-///
-/// Returns the search results as a `serde_json::Value` or an error.
-pub async fn search_jsdelivr(query: Option<&str>) -> Result<Value, Box<dyn Error>> {
-    let query = query.unwrap_or("");
-    let attributes_to_retrieve = ["name", "version", "description", "homepage"];
-
-    let payload = serde_json::json!({
-        "params": format!(
-            "query={}&page=0&hitsPerPage=25&attributesToHighlight=[]&attributesToRetrieve={}",
-            query,
-            serde_json::to_string(&attributes_to_retrieve)?
-        )
-    });
-
-    let response = Client::new()
-        .post("https://ofcncog2cu-dsn.algolia.net/1/indexes/npm-search/query")
-        .header("x-algolia-agent", "Algolia for JavaScript (3.35.1); Browser (lite)")
-        .header("x-algolia-application-id", "OFCNCOG2CU")
-        .header("x-algolia-api-key", "f54e21fa3a2a0160595bb058179bfb1e")
-        .json(&payload)
-        .send()
-        .await?;
-
-    if response.status().is_success() {
-        let hits = response.json::<Value>().await?.get("hits").cloned().unwrap_or_else(|| serde_json::json!([]));
-        Ok(hits)
+/// How the `tree` subcommand prints a dependency tree.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum TreeStyle {
+    /// Branches drawn like the `tree` command.
+    Tree,
+    /// Two spaces of indentation per level.
+    Indent,
+    /// The tree as pretty-printed JSON.
+    Json,
+}
+
+#[derive(Debug, Args)]
+struct PackageArgs {
+    /// The registry of the package, e.g. `crates` or `npm`.
+    source: String,
+    /// The package name.
+    name: String,
+}
+
+/// Returns where to write results: the `output` file, or stdout unless
+/// `quiet` is set.
+fn writer(output: Option<&Path>, quiet: bool) -> io::Result<Box<dyn Write>> {
+    Ok(match output {
+        Some(path) => Box::new(BufWriter::new(File::create(path)?)),
+        None if quiet => Box::new(io::sink()),
+        None => Box::new(io::stdout().lock()),
+    })
+}
+
+/// Reports a failed lookup: as an Alfred error item on stdout, which Alfred
+/// shows in place of results, or on stderr. An `--output` file is left as it
+/// was.
+fn report_error(error: &SearchError, alfred: bool, quiet: bool) -> Result<(), SearchError> {
+    if alfred {
+        write_json(writer(None, quiet)?, &ScriptFilter::error(error))
     } else {
-        Err(Box::new(std::io::Error::new(
-            std::io::ErrorKind::Other,
-            response.text().await?,
-        )))
+        eprintln!("Error: {}", error);
+        Ok(())
     }
 }
 
-/// Searches for images on Docker Hub.
-/// * # Arguments
-/// * `query` - The search query (optional).
-/// * # Returns
-/// The search results as a `serde_json::Value` or an error.
-pub async fn search_docker(query: Option<&str>) -> Result<Value, Box<dyn Error>> {
-    ApiClient::new("https://index.docker.io/v1/search", None)
-        .set_param("q", query.unwrap_or(""))
-        .set_param("page", "1")
-        .get("")
-        .await
+/// Reports a usage error on stderr.
+fn usage_error(message: impl Display) -> Status {
+    eprintln!("Error: {}", message);
+    Status::Usage
 }
 
-/// Search for composer packages on Packagist.
-/// *# Arguments
-/// * `query` - The search query (optional).
-/// * # Returns
-/// The search results as a `serde_json::Value` or an error.
+/// Loads the config file and applies it, filling in the search arguments
+/// that the command line leaves out.
+fn configure(cli: &mut Cli) -> Result<Config, Box<dyn Error>> {
+    let config = Config::load()?;
+    config.apply();
 
-pub async fn search_composer(query: Option<&str>) -> Result<Value, Box<dyn Error>> {
-    ApiClient::new("https://packagist.org/search.json", None)
-        .set_param("q", query.unwrap_or(""))
-        .set_param("per_page", "25")
-        .get("")
-        .await
+    if let Command::Search(args) = &mut cli.command {
+        args.limit = config.limit(args.limit)?;
+        // The extension of an output file decides its format over the
+        // configured one.
+        if args.output.is_none() {
+            args.format = config.format(args.format)?;
+        }
+        args.default_source = config.source();
+    }
+    Ok(config)
 }
 
-/// Writes JSON data to a file.
-///
-/// # Arguments
-///
-/// * `data` - The data to write, which must implement `Serialize`.
-/// * `file_name` - The name of the file to write to.
+/// Returns whether a registry response holds anything: a non-empty array,
+/// or a non-empty object whose arrays, if it has any, are not all empty.
+/// OSV answers a query without advisories with `{}` or an empty `vulns`.
+fn has_results(value: &Value) -> bool {
+    match value {
+        Value::Null => false,
+        Value::Array(items) => !items.is_empty(),
+        Value::Object(fields) => {
+            let mut arrays = fields.values().filter_map(Value::as_array).peekable();
+            !fields.is_empty() && (arrays.peek().is_none() || arrays.any(|items| !items.is_empty()))
+        }
+        _ => true,
+    }
+}
+
+/// Picks the ordering named `sort` from `choices`, or the default.
+fn parse_sort<T: Copy + Default>(
+    sort: Option<&str>,
+    choices: &[(&str, T)],
+) -> Result<T, SearchError> {
+    let Some(sort) = sort else {
+        return Ok(T::default());
+    };
+    choices
+        .iter()
+        .find(|(name, _)| *name == sort)
+        .map(|(_, choice)| *choice)
+        .ok_or_else(|| {
+            let names: Vec<_> = choices.iter().map(|(name, _)| *name).collect();
+            SearchError::InvalidQuery(format!(
+                "unknown sort '{}'; expected one of {}",
+                sort,
+                names.join(", ")
+            ))
+        })
+}
+
+/// Returns the error for a subcommand that `source` does not support.
+fn unsupported(command: &str, source: &str, supported: &str) -> SearchError {
+    SearchError::InvalidQuery(format!(
+        "{} is not supported for '{}'; supported sources are {}",
+        command, source, supported
+    ))
+}
+
+/// Runs the `search` subcommand, or returns `None` for an unknown source.
 ///
-/// # Returns
+/// Sources that can be self-hosted are searched at the registry `config`
+/// sets for them.
+async fn search(args: &SearchArgs, config: &Config) -> Option<Result<Value, SearchError>> {
+    let query = Some(args.query());
+    let (page, limit) = (args.page.max(1), args.limit());
+    let registry = config.registry(args.source());
+    let offset = (page - 1) * limit;
+    let sort = args.sort.as_deref();
+
+    let result = match args.source() {
+        "npm" => {
+            let options = npm::NpmSearchOptions::new().size(limit).from(offset);
+            npm::search_npm_with(query, &options, &registry)
+                .await
+                .map(SearchResults::into_raw)
+        }
+        #[cfg(feature = "npms")]
+        "npms" => npm::search_npms(query).await.map(SearchResults::into_raw),
+        "docker" => docker::search_docker_page(query, page, limit)
+            .await
+            .map(SearchResults::into_raw),
+        "jsdelivr" => jsdelivr::search(query, page - 1, limit, jsdelivr::DEFAULT_ATTRIBUTES)
+            .await
+            .map(SearchResults::into_raw),
+        "crates" => {
+            let sort = parse_sort(
+                sort,
+                &[
+                    ("relevance", crates::CrateSort::Relevance),
+                    ("downloads", crates::CrateSort::Downloads),
+                    ("recent-downloads", crates::CrateSort::RecentDownloads),
+                    ("recent-updates", crates::CrateSort::RecentUpdates),
+                    ("new", crates::CrateSort::New),
+                ],
+            );
+            match sort {
+                Ok(sort) => crates::search_crates_with(query, sort, page, limit, &registry)
+                    .await
+                    .map(SearchResults::into_raw),
+                Err(error) => Err(error),
+            }
+        }
+        "composer" => {
+            let options = composer::ComposerSearchOptions::new()
+                .page(page)
+                .per_page(limit);
+            composer::search_composer_with(query, &options, &registry)
+                .await
+                .map(SearchResults::into_raw)
+        }
+        "pypi" => pypi::search_pypi(query, page).await,
+        "gems" => rubygems::search_gems_with(query, page, &registry).await,
+        "maven" => maven::search_maven(query, limit, offset).await,
+        "nuget" => nuget::search_nuget_with(query, offset, limit, false, &registry).await,
+        "go" => golang::search_go(query, limit).await,
+        "hex" => {
+            let sort = parse_sort(
+                sort,
+                &[
+                    ("name", hex::HexSort::Name),
+                    ("total-downloads", hex::HexSort::TotalDownloads),
+                    ("recent-downloads", hex::HexSort::RecentDownloads),
+                    ("inserted-at", hex::HexSort::InsertedAt),
+                    ("updated-at", hex::HexSort::UpdatedAt),
+                ],
+            );
+            match sort {
+                Ok(sort) => hex::search_hex(query, page, sort).await,
+                Err(error) => Err(error),
+            }
+        }
+        "brew" => homebrew::search_homebrew(query).await,
+        "aur" => aur::search_aur(query, aur::AurSearchField::default()).await,
+        "apt" => apt::search_apt(query, limit as usize).await,
+        "hackage" => hackage::search_hackage(query).await,
+        "pub" => pubdev::search_pub(query, page).await,
+        "cocoapods" => apple::search_cocoapods(query).await,
+        "swiftpm" => {
+            apple::search_swiftpm(query, std::env::var("SPI_API_TOKEN").ok().as_deref()).await
+        }
+        "conan" => conan::search_conan(query).await,
+        "vcpkg" => vcpkg::search_vcpkg(query).await,
+        "cran" => cran::search_cran(query, limit).await,
+        "julia" => julia::search_julia(query).await,
+        "luarocks" => luarocks::search_luarocks(query).await,
+        "nix" => nix::search_nixpkgs(query, "unstable").await,
+        "flathub" => flathub::search_flathub(query).await,
+        "snap" => snap::search_snap(query).await,
+        "choco" => windows::search_chocolatey(query).await,
+        "winget" => windows::search_winget(query).await,
+        "scoop" => windows::search_scoop(query).await,
+        "galaxy" => ansible::search_galaxy(query, ansible::GalaxyContentType::default()).await,
+        "terraform" => {
+            terraform::search_terraform(query, terraform::TerraformKind::default()).await
+        }
+        "artifacthub" => artifacthub::search_artifacthub(query, None, limit).await,
+        "cpan" => cpan::search_cpan(query, limit).await,
+        "clojars" => clojars::search_clojars(query, page).await,
+        "jsr" => deno::search_jsr(query).await,
+        "denoland" => deno::search_denoland(query).await,
+        "fdroid" => fdroid::search_fdroid(query).await,
+        "vscode" => extensions::search_vscode(query).await,
+        "openvsx" => extensions::search_openvsx(query).await,
+        "github" => {
+            let sort = parse_sort(
+                sort,
+                &[
+                    ("best-match", github::GitHubSort::BestMatch),
+                    ("stars", github::GitHubSort::Stars),
+                    ("forks", github::GitHubSort::Forks),
+                    ("help-wanted-issues", github::GitHubSort::HelpWantedIssues),
+                    ("updated", github::GitHubSort::Updated),
+                ],
+            );
+            match sort {
+                Ok(sort) => {
+                    let token = std::env::var("GITHUB_TOKEN").ok();
+                    github::search_repos(query, sort, None, token.as_deref()).await
+                }
+                Err(error) => Err(error),
+            }
+        }
+        "ghcr" => oci::search_ghcr(query, &std::env::var("GITHUB_TOKEN").unwrap_or_default()).await,
+        "quay" => oci::search_quay(query).await,
+        "librariesio" => {
+            let api_key = std::env::var("LIBRARIES_IO_API_KEY").unwrap_or_default();
+            librariesio::search_librariesio(query, None, &api_key).await
+        }
+        _ => return None,
+    };
+    Some(result)
+}
+
+/// Runs the `search` subcommand through the source's [`Registry`], or
+/// returns `None` for a source that has none.
 ///
-/// Returns `Ok(())` if the operation succeeds, or an error.
-pub fn write_json_to_file<T: Serialize>(data: &T, file_name: &str) -> Result<(), Box<dyn Error>> {
-    let json_string = serde_json::to_string_pretty(data)?;
-    File::create(file_name)?.write_all(json_string.as_bytes())?;
-    Ok(())
+/// Searches of several registries are merged and ranked, and the error of
+/// each registry that failed is reported on stderr.
+async fn search_hits(args: &SearchArgs) -> Option<Result<Vec<SearchHit>, SearchError>> {
+    if args.aggregate() {
+        let sources: Vec<&str> = args.sources.iter().map(String::as_str).collect();
+        let results = search_all(args.query(), &sources, args.limit() as usize)
+            .await
+            .ranked(args.query(), RankingStrategy::default());
+        let mut errors = results.errors.into_iter();
+        // Without any hits, a failed registry may have held the results, so
+        // the search failed rather than found nothing.
+        let failed = if results.hits.is_empty() {
+            errors.next()
+        } else {
+            None
+        };
+        for error in errors {
+            eprintln!("Warning: {}", error);
+        }
+        return Some(match failed {
+            Some(error) => Err(error.error),
+            None => Ok(args.filter().apply(results.hits)),
+        });
+    }
+
+    let registry = registry_by_name(args.source())?;
+    let query = SearchQuery::new(args.query()).limit(args.limit() as usize);
+    let result = registry.search(&query).await;
+    Some(
+        result
+            .map(|hits| args.filter().apply(hits))
+            .map_err(|error| error.error),
+    )
 }
 
-#[tokio::main]
-async fn main() -> Result<(), Box<dyn Error>> {
-    let args: Vec<String> = std::env::args().collect();
+/// A list of hits, in the table that a TOML document must be.
+#[cfg(feature = "toml")]
+#[derive(serde::Serialize)]
+struct Hits<'a> {
+    hits: &'a [SearchHit],
+}
+
+/// Prints the normalized hits of a search in the format of `args`, unless
+/// `quiet` is set.
+async fn print_hits(args: &SearchArgs, quiet: bool) -> Result<Status, Box<dyn Error>> {
+    let Some(result) = search_hits(args).await else {
+        return Ok(usage_error(format!(
+            "Unsupported source: {}. Only the sources searchable without credentials support --format {:?}.",
+            args.source(),
+            args.format()
+        )));
+    };
 
-    if args.len() < 3 {
-        eprintln!("Usage: {} <source> <query>", args[0]);
-        return Ok(());
+    let status = Status::of(&result, |hits| !hits.is_empty());
+    #[cfg(feature = "sqlite")]
+    if let (Format::Sqlite, Some(path)) = (args.format(), &args.output) {
+        // The database is updated in place rather than written out anew.
+        match result {
+            Ok(hits) => {
+                save_hits(path, &hits)?;
+            }
+            Err(error) => eprintln!("Error: {}", error),
+        }
+        return Ok(status);
     }
+    let hits = match result {
+        Ok(hits) => hits,
+        Err(error) => {
+            report_error(&error, args.format() == Format::Alfred, quiet)?;
+            return Ok(status);
+        }
+    };
+    let writer = writer(args.output.as_deref(), quiet)?;
+    if !args.fields.is_empty() {
+        let fields: Vec<&str> = args.fields.iter().map(String::as_str).collect();
+        let rows = select_fields(&hits, &fields)?;
+        match args.format() {
+            Format::Ndjson => write_ndjson(
+                writer,
+                rows.as_array().map(Vec::as_slice).unwrap_or_default(),
+            )?,
+            #[cfg(feature = "yaml")]
+            Format::Yaml => write_yaml(writer, &rows)?,
+            _ => write_json(writer, &rows)?,
+        }
+        return Ok(status);
+    }
+    match args.format() {
+        Format::Json => write_json(writer, &hits)?,
+        Format::Table => write_table(writer, &hits)?,
+        Format::Ndjson => write_ndjson(writer, &hits)?,
+        Format::Csv => write_csv(writer, &hits)?,
+        Format::Tsv => write_tsv(writer, &hits)?,
+        Format::Plain => write_plain(writer, &hits)?,
+        Format::Alfred => write_alfred(writer, &hits)?,
+        #[cfg(feature = "yaml")]
+        Format::Yaml => write_yaml(writer, &hits)?,
+        #[cfg(feature = "toml")]
+        Format::Toml => write_toml(writer, &Hits { hits: &hits })?,
+        Format::Markdown => write_markdown(writer, &hits)?,
+        Format::Html => {
+            let title = format!("Search results for \"{}\"", args.query());
+            write_html(writer, &hits, &title)?
+        }
+        #[cfg(feature = "sqlite")]
+        Format::Sqlite => unreachable!("validate requires --output for sqlite"),
+    }
+    Ok(status)
+}
 
-    let source = &args[1];
-    let query = &args[2];
+/// Runs the `info` subcommand.
+async fn info(args: &PackageArgs) -> Result<Value, SearchError> {
+    match args.source.as_str() {
+        "crates" => crates::get_crate(&args.name).await,
+        "npm" => npm::get_package(&args.name).await,
+        "composer" => composer::get_package(&args.name).await,
+        source => Err(unsupported(
+            "info",
+            source,
+            "'crates', 'npm', and 'composer'",
+        )),
+    }
+}
 
-    let result = match source.as_str() {
-        "npm" => search_npm(Some(query)).await,
-        "docker" => search_docker(Some(query)).await,
-        "jsdelivr" => search_jsdelivr(Some(query)).await,
-        "crates" => search_crates(Some(query)).await,
-        "composer" => search_composer(Some(query)).await,
-        _ => {
-            eprintln!("Unsupported source: {}. Supported sources are 'npm', 'docker', 'jsdelivr', 'crates', and 'composer'.", source);
-            return Ok(());
+/// Runs the `versions` subcommand.
+async fn versions(args: &PackageArgs) -> Result<Value, SearchError> {
+    match args.source.as_str() {
+        "crates" => Ok(serde_json::to_value(
+            crates::crate_versions(&args.name).await?,
+        )?),
+        "npm" => {
+            let package = npm::get_package(&args.name).await?;
+            let versions: Vec<&String> = package["versions"]
+                .as_object()
+                .into_iter()
+                .flat_map(|versions| versions.keys())
+                .collect();
+            Ok(json!(versions))
         }
-    };
+        "composer" => {
+            let package = composer::get_package(&args.name).await?;
+            let versions: Vec<&Value> = package["packages"][&args.name]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .map(|release| &release["version"])
+                .collect();
+            Ok(json!(versions))
+        }
+        "docker" => {
+            let (namespace, repo) = args
+                .name
+                .split_once('/')
+                .unwrap_or(("library", args.name.as_str()));
+            docker::list_tags(namespace, repo, 1).await
+        }
+        source => Err(unsupported(
+            "versions",
+            source,
+            "'crates', 'npm', 'composer', and 'docker'",
+        )),
+    }
+}
 
-    match result {
-        Ok(data) => {
-            println!("{}", serde_json::to_string_pretty(&data)?);
+/// Runs the `deps` subcommand.
+async fn deps(args: &PackageArgs, version: Option<&str>) -> Result<Value, SearchError> {
+    match args.source.as_str() {
+        "crates" => {
+            let version = match version {
+                Some(version) => version.to_string(),
+                None => {
+                    let krate = crates::get_crate(&args.name).await?;
+                    let latest = &krate["crate"];
+                    latest["max_stable_version"]
+                        .as_str()
+                        .or_else(|| latest["max_version"].as_str())
+                        .ok_or(SearchError::NotFound)?
+                        .to_string()
+                }
+            };
+            crates::crate_dependencies(&args.name, &version).await
         }
+        "npm" => {
+            let manifest =
+                npm::get_package_version(&args.name, version.unwrap_or("latest")).await?;
+            Ok(json!({
+                "dependencies": manifest["dependencies"],
+                "devDependencies": manifest["devDependencies"],
+                "peerDependencies": manifest["peerDependencies"],
+            }))
+        }
+        "composer" => {
+            let package = composer::get_package(&args.name).await?;
+            let releases = package["packages"][&args.name]
+                .as_array()
+                .cloned()
+                .unwrap_or_default();
+            let release = match version {
+                Some(version) => releases.into_iter().find(|release| {
+                    release["version"]
+                        .as_str()
+                        .map(|v| v.trim_start_matches('v'))
+                        == Some(version.trim_start_matches('v'))
+                }),
+                None => releases.into_iter().next(),
+            }
+            .ok_or(SearchError::NotFound)?;
+            Ok(json!({
+                "require": release["require"],
+                "require-dev": release["require-dev"],
+            }))
+        }
+        source => Err(unsupported(
+            "deps",
+            source,
+            "'crates', 'npm', and 'composer'",
+        )),
+    }
+}
+
+/// Runs the `downloads` subcommand.
+async fn downloads(args: &PackageArgs, period: Option<&str>) -> Result<Value, SearchError> {
+    match args.source.as_str() {
+        "crates" => Ok(serde_json::to_value(
+            crates::crate_downloads(&args.name).await?,
+        )?),
+        "npm" => npm::npm_downloads(&args.name, period.unwrap_or("last-month")).await,
+        "composer" => composer::package_stats(&args.name).await,
+        "jsdelivr" => jsdelivr::package_stats(&args.name, period.unwrap_or("month")).await,
+        source => Err(unsupported(
+            "downloads",
+            source,
+            "'crates', 'npm', 'composer', and 'jsdelivr'",
+        )),
+    }
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    match run(Cli::parse()).await {
+        Ok(status) => status.into(),
         Err(error) => {
-            let error_response = serde_json::json!({
-                "items": [
-                    {
-                        "title": "Error",
-                        "subtitle": error.to_string()
-                    }
-                ]
-            });
-            println!("{}", serde_json::to_string_pretty(&error_response)?);
-        }
-    }
-    Ok(())
+            eprintln!("Error: {}", error);
+            Status::Failed.into()
+        }
+    }
+}
+
+/// Runs the command line, returning the exit status it calls for.
+async fn run(mut cli: Cli) -> Result<Status, Box<dyn Error>> {
+    let config = match configure(&mut cli) {
+        Ok(config) => config,
+        Err(error) => return Ok(usage_error(error)),
+    };
+
+    match &cli.command {
+        Command::Search(args) => {
+            if let Err(message) = args.validate() {
+                return Ok(usage_error(message));
+            }
+            // Fields and filters apply to the normalized hits, not to the
+            // registry's response.
+            if args.aggregate()
+                || args.format() != Format::Json
+                || !args.fields.is_empty()
+                || !args.filter().is_empty()
+            {
+                let settings = args.raw_only_settings(&config);
+                if !settings.is_empty() {
+                    return Ok(usage_error(format!(
+                        "{} only apply to --format json from a single source, without --fields or filters",
+                        settings.join(" and ")
+                    )));
+                }
+                return print_hits(args, cli.quiet).await;
+            }
+        }
+        Command::Tree {
+            package,
+            version,
+            depth,
+            style: style @ (TreeStyle::Tree | TreeStyle::Indent),
+        } => {
+            let tree =
+                dependency_tree(&package.source, &package.name, version.as_deref(), *depth).await;
+            let status = Status::of(&tree, |_| true);
+            match tree {
+                Ok(_) if cli.quiet => {}
+                Ok(tree) if *style == TreeStyle::Tree => print!("{}", tree.render_tree()),
+                Ok(tree) => print!("{}", tree.render_indented()),
+                Err(error) => eprintln!("Error: {}", error),
+            }
+            return Ok(status);
+        }
+        #[cfg(feature = "tui")]
+        Command::Tui { limit } => return tui::run(*limit).await.map(|()| Status::Found),
+        _ => {}
+    }
+
+    let result = match &cli.command {
+        Command::Search(args) => match search(args, &config).await {
+            Some(result) => result,
+            None => {
+                return Ok(usage_error(format!(
+                    "Unsupported source: {}. Supported sources are {}.",
+                    args.source(),
+                    SEARCH_SOURCES
+                )));
+            }
+        },
+        Command::Info(args) => info(args).await,
+        Command::Versions(args) => versions(args).await,
+        Command::Resolve {
+            package,
+            requirement,
+        } => resolve_version(&package.source, &package.name, requirement)
+            .await
+            .and_then(|resolved| Ok(serde_json::to_value(resolved)?)),
+        Command::Deps { package, version } => deps(package, version.as_deref()).await,
+        Command::Tree {
+            package,
+            version,
+            depth,
+            ..
+        } => dependency_tree(&package.source, &package.name, version.as_deref(), *depth)
+            .await
+            .and_then(|tree| Ok(serde_json::to_value(tree)?)),
+        Command::Downloads { package, period } => downloads(package, period.as_deref()).await,
+        Command::Repo { url } => match find_by_repository(url).await {
+            Ok(results) => {
+                for error in &results.errors {
+                    eprintln!("Warning: {}", error);
+                }
+                Ok(serde_json::to_value(results.hits)?)
+            }
+            Err(error) => Err(error),
+        },
+        Command::Download {
+            package,
+            version,
+            dest,
+        } => download_artifact(&package.source, &package.name, version, dest)
+            .await
+            .map(|artifact| {
+                json!({
+                    "path": artifact.path,
+                    "size": artifact.size,
+                    "checksum": artifact.checksum,
+                })
+            }),
+        Command::Advisories {
+            ecosystem,
+            name,
+            version,
+        } => advisories::lookup_advisories(ecosystem, name, version.as_deref()).await,
+    };
+
+    let status = Status::of(&result, has_results);
+    let (output, alfred) = match &cli.command {
+        Command::Search(args) => (args.output.as_deref(), args.format() == Format::Alfred),
+        _ => (None, false),
+    };
+    match result {
+        Ok(data) => write_json(writer(output, cli.quiet)?, &data)?,
+        Err(error) => report_error(&error, alfred, cli.quiet)?,
+    }
+    Ok(status)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn treats_empty_responses_as_not_found() {
+        assert!(!has_results(&Value::Null));
+        assert!(!has_results(&json!([])));
+        assert!(!has_results(&json!({})));
+        assert!(!has_results(&json!({"vulns": []})));
+        assert!(has_results(&json!([{"id": "RUSTSEC-2024-0001"}])));
+        assert!(has_results(&json!({"name": "serde"})));
+        assert!(has_results(&json!({"versions": [], "tags": ["latest"]})));
+    }
 }
@@ -0,0 +1,56 @@
+//! Client-side matching for sources that are searched from a downloaded index.
+
+use std::cmp::Ordering;
+
+/// Scores how well `name` and `description` match `query`; lower is better.
+///
+/// Exact and prefix matches on the name rank first, then substring and
+/// fuzzy (in-order subsequence) name matches, then description matches.
+pub(crate) fn match_score(query: &str, name: &str, description: &str) -> Option<u8> {
+    let query = query.to_lowercase();
+    let name = name.to_lowercase();
+
+    if name == query {
+        Some(0)
+    } else if name.starts_with(&query) {
+        Some(1)
+    } else if name.contains(&query) {
+        Some(2)
+    } else if is_subsequence(&query, &name) {
+        Some(3)
+    } else if description.to_lowercase().contains(&query) {
+        Some(4)
+    } else {
+        None
+    }
+}
+
+fn is_subsequence(needle: &str, haystack: &str) -> bool {
+    let mut haystack = haystack.chars();
+    needle.chars().all(|c| haystack.any(|h| h == c))
+}
+
+/// Compares dotted versions numerically where possible, e.g. `1.10` > `1.9`.
+///
+/// Both `.` and `-` separate components, so LuaRocks-style `1.2-1` revisions
+/// compare correctly too.
+pub(crate) fn compare_versions(a: &str, b: &str) -> Ordering {
+    let mut a_parts = a.split(['.', '-']);
+    let mut b_parts = b.split(['.', '-']);
+    loop {
+        match (a_parts.next(), b_parts.next()) {
+            (None, None) => return Ordering::Equal,
+            (Some(_), None) => return Ordering::Greater,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(a), Some(b)) => {
+                let ordering = match (a.parse::<u64>(), b.parse::<u64>()) {
+                    (Ok(a), Ok(b)) => a.cmp(&b),
+                    _ => a.cmp(b),
+                };
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,74 @@
+use crate::error::SearchError;
+use crate::registry::{collect_hits, text, Registry, RegistryError, SearchHit, SearchQuery};
+use crate::ApiClient;
+use async_trait::async_trait;
+use serde_json::{json, Value};
+
+/// Searches for artifacts on Maven Central.
+///
+/// A query of the form `groupId:artifactId` is translated into a Solr
+/// coordinate query; anything else is passed through as a free-text search.
+///
+/// # Arguments
+///
+/// * `query` - The search query (optional).
+/// * `rows` - The number of results to return.
+/// * `start` - The offset of the first result.
+///
+/// # Returns
+///
+/// Returns the matching Solr documents as a `serde_json::Value` or an error.
+pub async fn search_maven(
+    query: Option<&str>,
+    rows: u32,
+    start: u32,
+) -> Result<Value, SearchError> {
+    let response = ApiClient::new("https://search.maven.org/solrsearch/", None)
+        .set_param("q", &solr_query(query.unwrap_or("")))
+        .set_param("rows", &rows.to_string())
+        .set_param("start", &start.to_string())
+        .set_param("wt", "json")
+        .get("select")
+        .await?;
+
+    Ok(response
+        .get("response")
+        .and_then(|response| response.get("docs"))
+        .cloned()
+        .unwrap_or_else(|| json!([])))
+}
+
+fn solr_query(query: &str) -> String {
+    match query.split_once(':') {
+        Some((group, artifact)) if !group.is_empty() && !artifact.is_empty() => {
+            format!("g:\"{}\" AND a:\"{}\"", group, artifact)
+        }
+        _ => query.to_string(),
+    }
+}
+
+/// The Maven Central repository.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MavenCentral;
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl Registry for MavenCentral {
+    fn name(&self) -> &str {
+        "maven"
+    }
+
+    async fn search(&self, query: &SearchQuery) -> Result<Vec<SearchHit>, RegistryError> {
+        let results = search_maven(Some(&query.text), query.limit as u32, 0)
+            .await
+            .map_err(|error| RegistryError::new(self.name(), error))?;
+        Ok(collect_hits(&results, query.limit, |artifact| SearchHit {
+            name: text(&artifact["id"]).unwrap_or_default(),
+            version: text(&artifact["latestVersion"]),
+            description: None,
+            homepage: None,
+            source: self.name().to_string(),
+            ..Default::default()
+        }))
+    }
+}
@@ -0,0 +1,196 @@
+//! Counters and latencies of requests and registry searches, for
+//! applications that run searches as part of a long-lived service.
+
+use crate::registry::RegistryError;
+use reqwest::StatusCode;
+use std::collections::HashMap;
+use std::sync::{Arc, LazyLock, Mutex, RwLock};
+use std::time::Duration;
+
+/// The upper bounds of the latency buckets of an [`InMemoryMetrics`]
+/// histogram; slower calls fall into a last, unbounded bucket.
+pub const LATENCY_BUCKETS: &[Duration] = &[
+    Duration::from_millis(50),
+    Duration::from_millis(100),
+    Duration::from_millis(250),
+    Duration::from_millis(500),
+    Duration::from_secs(1),
+    Duration::from_millis(2500),
+    Duration::from_secs(5),
+    Duration::from_secs(10),
+];
+
+static METRICS: LazyLock<RwLock<Option<Arc<dyn Metrics>>>> = LazyLock::new(|| RwLock::new(None));
+
+/// A completed HTTP request, after any retries.
+#[derive(Debug, Clone)]
+pub struct RequestMetrics<'a> {
+    /// The host the request went to, e.g. `crates.io`.
+    pub host: &'a str,
+    /// The status of the last response, or `None` if none arrived.
+    pub status: Option<StatusCode>,
+    /// The number of times the request was retried.
+    pub retries: u32,
+    /// The time from the first attempt to the final result.
+    pub latency: Duration,
+    /// Whether the request ended in an error.
+    pub failed: bool,
+}
+
+/// A completed search of one registry by [`search_all`](crate::search_all).
+#[derive(Debug, Clone)]
+pub struct SearchMetrics<'a> {
+    /// The name of the registry, e.g. `crates`.
+    pub registry: &'a str,
+    /// The number of hits returned.
+    pub hits: usize,
+    /// The time the search took.
+    pub latency: Duration,
+    /// The error the search failed with, if any.
+    pub error: Option<&'a RegistryError>,
+}
+
+/// Receives the metrics of every request and registry search in the
+/// process; see [`set_metrics`].
+///
+/// Both methods do nothing by default, so an implementation only needs the
+/// ones it cares about. They are called on the request path and should
+/// not block.
+pub trait Metrics: Send + Sync {
+    /// Records a completed HTTP request.
+    fn record_request(&self, _request: &RequestMetrics<'_>) {}
+
+    /// Records a completed registry search.
+    fn record_search(&self, _search: &SearchMetrics<'_>) {}
+}
+
+/// The counters of one host or registry in an [`InMemoryMetrics`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Counters {
+    /// The number of requests or searches.
+    pub calls: u64,
+    /// The number of them that failed.
+    pub errors: u64,
+    /// The number of calls per [`LATENCY_BUCKETS`] bucket, plus one for
+    /// calls slower than the last bound.
+    pub latency: Vec<u64>,
+    /// The sum of the latencies of all calls.
+    pub total_latency: Duration,
+}
+
+impl Counters {
+    /// Returns the fraction of calls that failed, from 0.0 to 1.0.
+    pub fn error_rate(&self) -> f64 {
+        if self.calls == 0 {
+            0.0
+        } else {
+            self.errors as f64 / self.calls as f64
+        }
+    }
+
+    /// Returns the mean latency of the calls.
+    pub fn mean_latency(&self) -> Duration {
+        match u32::try_from(self.calls) {
+            Ok(0) => Duration::ZERO,
+            Ok(calls) => self.total_latency / calls,
+            Err(_) => Duration::from_secs_f64(self.total_latency.as_secs_f64() / self.calls as f64),
+        }
+    }
+
+    fn record(&mut self, latency: Duration, failed: bool) {
+        if self.latency.is_empty() {
+            self.latency = vec![0; LATENCY_BUCKETS.len() + 1];
+        }
+        let bucket = LATENCY_BUCKETS
+            .iter()
+            .position(|bound| latency <= *bound)
+            .unwrap_or(LATENCY_BUCKETS.len());
+        self.latency[bucket] += 1;
+        self.calls += 1;
+        self.errors += u64::from(failed);
+        self.total_latency += latency;
+    }
+}
+
+/// A [`Metrics`] implementation that keeps counters and latency histograms
+/// in memory, per host for requests and per registry for searches.
+#[derive(Debug, Default)]
+pub struct InMemoryMetrics {
+    requests: Mutex<HashMap<String, Counters>>,
+    searches: Mutex<HashMap<String, Counters>>,
+}
+
+impl InMemoryMetrics {
+    /// Creates empty metrics.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the request counters, keyed by host.
+    pub fn requests(&self) -> HashMap<String, Counters> {
+        self.requests
+            .lock()
+            .unwrap_or_else(|error| error.into_inner())
+            .clone()
+    }
+
+    /// Returns the search counters, keyed by registry name.
+    pub fn searches(&self) -> HashMap<String, Counters> {
+        self.searches
+            .lock()
+            .unwrap_or_else(|error| error.into_inner())
+            .clone()
+    }
+
+    /// Resets every counter.
+    pub fn clear(&self) {
+        self.requests
+            .lock()
+            .unwrap_or_else(|error| error.into_inner())
+            .clear();
+        self.searches
+            .lock()
+            .unwrap_or_else(|error| error.into_inner())
+            .clear();
+    }
+}
+
+impl Metrics for InMemoryMetrics {
+    fn record_request(&self, request: &RequestMetrics<'_>) {
+        self.requests
+            .lock()
+            .unwrap_or_else(|error| error.into_inner())
+            .entry(request.host.to_string())
+            .or_default()
+            .record(request.latency, request.failed);
+    }
+
+    fn record_search(&self, search: &SearchMetrics<'_>) {
+        self.searches
+            .lock()
+            .unwrap_or_else(|error| error.into_inner())
+            .entry(search.registry.to_string())
+            .or_default()
+            .record(search.latency, search.error.is_some());
+    }
+}
+
+/// Sends the metrics of every request and registry search in the process
+/// to `metrics`. `None` stops recording again.
+///
+/// # Arguments
+///
+/// * `metrics` - The recorder, e.g. an [`InMemoryMetrics`] or an adapter to
+///   the application's metrics library.
+pub fn set_metrics(metrics: Option<Arc<dyn Metrics>>) {
+    let mut global = METRICS.write().unwrap_or_else(|error| error.into_inner());
+    *global = metrics;
+}
+
+/// Returns the recorder set with [`set_metrics`], if any.
+pub(crate) fn metrics() -> Option<Arc<dyn Metrics>> {
+    METRICS
+        .read()
+        .unwrap_or_else(|error| error.into_inner())
+        .clone()
+}
@@ -0,0 +1,81 @@
+//! Hooks that see every request before it is sent and every response as it
+//! arrives, for tracing headers, custom authentication schemes, or metrics.
+
+use reqwest::{RequestBuilder, Response};
+use std::fmt;
+use std::sync::{Arc, LazyLock, RwLock};
+
+static GLOBAL: LazyLock<RwLock<Middleware>> = LazyLock::new(|| RwLock::new(Middleware::default()));
+
+/// Changes a request before it is sent, e.g. by adding a header.
+pub type RequestMiddleware = Arc<dyn Fn(RequestBuilder) -> RequestBuilder + Send + Sync>;
+
+/// Observes a response before its status is checked and its body is read.
+pub type ResponseHook = Arc<dyn Fn(&Response) + Send + Sync>;
+
+/// The request middleware and response hooks of a client, applied in the
+/// order they were added.
+#[derive(Clone, Default)]
+pub(crate) struct Middleware {
+    requests: Vec<RequestMiddleware>,
+    responses: Vec<ResponseHook>,
+}
+
+impl Middleware {
+    pub(crate) fn add_request(&mut self, middleware: RequestMiddleware) {
+        self.requests.push(middleware);
+    }
+
+    pub(crate) fn add_response(&mut self, hook: ResponseHook) {
+        self.responses.push(hook);
+    }
+
+    /// Passes `request` through every request middleware.
+    pub(crate) fn apply(&self, request: RequestBuilder) -> RequestBuilder {
+        self.requests
+            .iter()
+            .fold(request, |request, middleware| middleware(request))
+    }
+
+    /// Shows `response` to every response hook.
+    pub(crate) fn observe(&self, response: &Response) {
+        for hook in &self.responses {
+            hook(response);
+        }
+    }
+}
+
+impl fmt::Debug for Middleware {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Middleware")
+            .field("requests", &self.requests.len())
+            .field("responses", &self.responses.len())
+            .finish()
+    }
+}
+
+/// Applies `middleware` to the requests of every client in the process,
+/// including those made by the search functions of the registry modules.
+///
+/// Global middleware runs before the middleware of the client itself.
+pub fn add_global_middleware(
+    middleware: impl Fn(RequestBuilder) -> RequestBuilder + Send + Sync + 'static,
+) {
+    let mut global = GLOBAL.write().unwrap_or_else(|error| error.into_inner());
+    global.add_request(Arc::new(middleware));
+}
+
+/// Shows the responses of every client in the process to `hook`.
+pub fn add_global_response_hook(hook: impl Fn(&Response) + Send + Sync + 'static) {
+    let mut global = GLOBAL.write().unwrap_or_else(|error| error.into_inner());
+    global.add_response(Arc::new(hook));
+}
+
+/// Returns the middleware added with [`add_global_middleware`] and
+/// [`add_global_response_hook`].
+pub(crate) fn global() -> Middleware {
+    GLOBAL
+        .read()
+        .unwrap_or_else(|error| error.into_inner())
+        .clone()
+}
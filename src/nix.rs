@@ -0,0 +1,98 @@
+use crate::error::SearchError;
+use crate::registry::{collect_hits, text, Registry, RegistryError, SearchHit, SearchQuery};
+use crate::ApiClient;
+use async_trait::async_trait;
+use serde_json::{json, Value};
+
+/// The index schema version used by search.nixos.org; bump it when the
+/// site migrates to a new backend index.
+const SCHEMA_VERSION: u32 = 44;
+
+/// The public read-only credentials embedded in the search.nixos.org frontend.
+const BACKEND_AUTH: &str = "Basic YVdWU0FMWHBadjpYOGdQSG56TDUyd0ZFZWt1eHNmUTljU2g=";
+
+/// Searches for packages in Nixpkgs via the search.nixos.org backend.
+///
+/// # Arguments
+///
+/// * `query` - The search query (optional).
+/// * `channel` - The NixOS channel to search, e.g. `unstable` or `24.05`.
+///
+/// # Returns
+///
+/// Returns the matching package records as a `serde_json::Value` array or an error.
+pub async fn search_nixpkgs(query: Option<&str>, channel: &str) -> Result<Value, SearchError> {
+    let body = json!({
+        "from": 0,
+        "size": 25,
+        "query": {
+            "bool": {
+                "filter": [{ "term": { "type": "package" } }],
+                "must": [{
+                    "multi_match": {
+                        "query": query.unwrap_or(""),
+                        "type": "cross_fields",
+                        "fields": [
+                            "package_attr_name^9",
+                            "package_pname^6",
+                            "package_description^1.3",
+                        ],
+                    },
+                }],
+            },
+        },
+    });
+
+    let response = ApiClient::new("https://search.nixos.org/backend/", None)
+        .set_header("Authorization", BACKEND_AUTH)
+        .post(
+            &format!("latest-{}-nixos-{}/_search", SCHEMA_VERSION, channel),
+            &body,
+        )
+        .await?;
+
+    Ok(response["hits"]["hits"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .map(|hit| hit["_source"].clone())
+        .collect())
+}
+
+/// Nixpkgs as indexed by search.nixos.org.
+#[derive(Debug, Clone)]
+pub struct Nixpkgs {
+    /// The NixOS channel to search, e.g. `unstable` or `24.05`.
+    pub channel: String,
+}
+
+impl Default for Nixpkgs {
+    fn default() -> Self {
+        Self {
+            channel: "unstable".to_string(),
+        }
+    }
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl Registry for Nixpkgs {
+    fn name(&self) -> &str {
+        "nix"
+    }
+
+    async fn search(&self, query: &SearchQuery) -> Result<Vec<SearchHit>, RegistryError> {
+        let results = search_nixpkgs(Some(&query.text), &self.channel)
+            .await
+            .map_err(|error| RegistryError::new(self.name(), error))?;
+        Ok(collect_hits(&results, query.limit, |package| SearchHit {
+            name: text(&package["package_attr_name"]).unwrap_or_default(),
+            version: text(&package["package_pversion"]),
+            description: text(&package["package_description"]),
+            homepage: text(&package["package_homepage"][0]),
+            license: text(&package["package_license_set"][0]),
+            source: self.name().to_string(),
+            ..Default::default()
+        }))
+    }
+}
@@ -0,0 +1,465 @@
+#[cfg(feature = "npms")]
+use crate::client::is_transient;
+use crate::client::{encode_path, encode_path_segment};
+use crate::error::SearchError;
+use crate::paginate::paginate;
+use crate::registry::{Registry, RegistryConfig, RegistryError, SearchHit, SearchQuery};
+use crate::results::SearchResults;
+use crate::ApiClient;
+use async_trait::async_trait;
+use futures::Stream;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+const REGISTRY: &str = "https://registry.npmjs.org/";
+const DOWNLOADS_API: &str = "https://api.npmjs.org/downloads/";
+
+/// A package returned by npm search.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct NpmHit {
+    pub package: NpmPackage,
+    pub score: Option<NpmScore>,
+    #[serde(rename = "searchScore")]
+    pub search_score: Option<f64>,
+}
+
+impl From<NpmHit> for SearchHit {
+    fn from(hit: NpmHit) -> Self {
+        let package = hit.package;
+        Self {
+            name: package.name,
+            version: Some(package.version),
+            description: package.description,
+            homepage: package.links.homepage,
+            repository: package.links.repository,
+            downloads: None,
+            license: package.license,
+            updated: package.date,
+            deprecated: false,
+            source: "npm".to_string(),
+        }
+    }
+}
+
+/// The package metadata of an [`NpmHit`].
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct NpmPackage {
+    pub name: String,
+    pub version: String,
+    pub description: Option<String>,
+    #[serde(default)]
+    pub keywords: Vec<String>,
+    pub date: Option<String>,
+    pub license: Option<String>,
+    #[serde(default)]
+    pub links: NpmLinks,
+}
+
+/// The links of an [`NpmPackage`].
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct NpmLinks {
+    pub npm: Option<String>,
+    pub homepage: Option<String>,
+    pub repository: Option<String>,
+    pub bugs: Option<String>,
+}
+
+/// The search score of an [`NpmHit`].
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct NpmScore {
+    #[serde(rename = "final")]
+    pub final_score: f64,
+    pub detail: NpmScoreDetail,
+}
+
+/// The components of an [`NpmScore`], each from 0.0 to 1.0.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct NpmScoreDetail {
+    pub quality: f64,
+    pub popularity: f64,
+    pub maintenance: f64,
+}
+
+/// Searches for packages on npm.
+///
+/// Uses the official registry search; see [`search_npm_registry`] for
+/// paging control. With the `npms` feature, a search the registry fails to
+/// answer, as when it is down or rate limits the client, falls back to
+/// npms.io.
+///
+/// # Arguments
+///
+/// * `query` - The search query (optional).
+///
+/// # Returns
+///
+/// Returns the matching packages or an error.
+pub async fn search_npm(query: Option<&str>) -> Result<SearchResults<NpmHit>, SearchError> {
+    match search_npm_registry(query, 25, 0).await {
+        #[cfg(feature = "npms")]
+        Err(error) if is_transient(&error) => search_npms(query).await,
+        results => results,
+    }
+}
+
+/// Options for the npm registry search endpoint.
+///
+/// The weights bias ranking towards one of npm's three scores; the
+/// qualifiers are appended to the search text as `author:`, `maintainer:`,
+/// `scope:`, `keywords:`, and `not:deprecated`.
+#[derive(Debug, Clone, Default)]
+pub struct NpmSearchOptions {
+    size: Option<u32>,
+    from: Option<u32>,
+    quality: Option<f64>,
+    popularity: Option<f64>,
+    maintenance: Option<f64>,
+    author: Option<String>,
+    maintainer: Option<String>,
+    scope: Option<String>,
+    keywords: Vec<String>,
+    exclude_deprecated: bool,
+}
+
+impl NpmSearchOptions {
+    /// Creates options that use the registry defaults.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the number of results to return (at most 250).
+    pub fn size(mut self, size: u32) -> Self {
+        self.size = Some(size);
+        self
+    }
+
+    /// Sets the offset of the first result.
+    pub fn from(mut self, from: u32) -> Self {
+        self.from = Some(from);
+        self
+    }
+
+    /// Sets how much the quality score weighs in ranking, from 0.0 to 1.0.
+    pub fn quality(mut self, weight: f64) -> Self {
+        self.quality = Some(weight);
+        self
+    }
+
+    /// Sets how much the popularity score weighs in ranking, from 0.0 to 1.0.
+    pub fn popularity(mut self, weight: f64) -> Self {
+        self.popularity = Some(weight);
+        self
+    }
+
+    /// Sets how much the maintenance score weighs in ranking, from 0.0 to 1.0.
+    pub fn maintenance(mut self, weight: f64) -> Self {
+        self.maintenance = Some(weight);
+        self
+    }
+
+    /// Restricts results to packages published by this npm user.
+    pub fn author(mut self, author: &str) -> Self {
+        self.author = Some(author.to_string());
+        self
+    }
+
+    /// Restricts results to packages maintained by this npm user.
+    pub fn maintainer(mut self, maintainer: &str) -> Self {
+        self.maintainer = Some(maintainer.to_string());
+        self
+    }
+
+    /// Restricts results to packages in this scope, without the leading `@`.
+    pub fn scope(mut self, scope: &str) -> Self {
+        self.scope = Some(scope.to_string());
+        self
+    }
+
+    /// Restricts results to packages tagged with this keyword.
+    pub fn keyword(mut self, keyword: &str) -> Self {
+        self.keywords.push(keyword.to_string());
+        self
+    }
+
+    /// Excludes deprecated packages.
+    pub fn exclude_deprecated(mut self) -> Self {
+        self.exclude_deprecated = true;
+        self
+    }
+
+    fn text(&self, query: &str) -> String {
+        let mut text = vec![query.to_string()];
+        if let Some(author) = &self.author {
+            text.push(format!("author:{}", author));
+        }
+        if let Some(maintainer) = &self.maintainer {
+            text.push(format!("maintainer:{}", maintainer));
+        }
+        if let Some(scope) = &self.scope {
+            text.push(format!("scope:{}", scope));
+        }
+        if !self.keywords.is_empty() {
+            text.push(format!("keywords:{}", self.keywords.join(",")));
+        }
+        if self.exclude_deprecated {
+            text.push("not:deprecated".to_string());
+        }
+        text.retain(|part| !part.is_empty());
+        text.join(" ")
+    }
+}
+
+/// Searches for packages using the npm registry's search endpoint.
+///
+/// # Arguments
+///
+/// * `query` - The search query (optional).
+/// * `size` - The number of results to return (at most 250).
+/// * `from` - The offset of the first result.
+///
+/// # Returns
+///
+/// Returns the matching packages or an error.
+pub async fn search_npm_registry(
+    query: Option<&str>,
+    size: u32,
+    from: u32,
+) -> Result<SearchResults<NpmHit>, SearchError> {
+    search_npm_registry_with(query, &NpmSearchOptions::new().size(size).from(from)).await
+}
+
+/// Searches for packages using the npm registry's search endpoint with
+/// ranking weights and qualifiers.
+///
+/// # Arguments
+///
+/// * `query` - The search query (optional).
+/// * `options` - The paging, ranking, and qualifier options.
+///
+/// # Returns
+///
+/// Returns the matching packages or an error.
+pub async fn search_npm_registry_with(
+    query: Option<&str>,
+    options: &NpmSearchOptions,
+) -> Result<SearchResults<NpmHit>, SearchError> {
+    search_npm_with(query, options, &RegistryConfig::new()).await
+}
+
+/// Searches for packages on an npm-compatible registry, such as a Verdaccio,
+/// Nexus, Artifactory or Cloudsmith mirror.
+///
+/// # Arguments
+///
+/// * `query` - The search query (optional).
+/// * `options` - The paging, ranking, and qualifier options.
+/// * `config` - The registry URL and credentials; the public registry is
+///   used when no URL is set.
+///
+/// # Returns
+///
+/// Returns the matching packages or an error.
+pub async fn search_npm_with(
+    query: Option<&str>,
+    options: &NpmSearchOptions,
+    config: &RegistryConfig,
+) -> Result<SearchResults<NpmHit>, SearchError> {
+    let mut client = config
+        .client(REGISTRY, None)
+        .set_param("text", &options.text(query.unwrap_or("")));
+
+    let params = [
+        ("size", options.size.map(|size| size.to_string())),
+        ("from", options.from.map(|from| from.to_string())),
+        ("quality", options.quality.map(|weight| weight.to_string())),
+        (
+            "popularity",
+            options.popularity.map(|weight| weight.to_string()),
+        ),
+        (
+            "maintenance",
+            options.maintenance.map(|weight| weight.to_string()),
+        ),
+    ];
+    for (key, value) in params {
+        if let Some(value) = value {
+            client = client.set_param(key, &value);
+        }
+    }
+
+    let response = client.get("-/v1/search").await?;
+    SearchResults::from_raw(response, "/objects")
+}
+
+/// Returns the npm registry search results as a stream of pages of 250
+/// packages, following the `from` offset.
+///
+/// # Arguments
+///
+/// * `query` - The search query.
+/// * `options` - The ranking and qualifier options; its paging options are
+///   ignored.
+///
+/// # Returns
+///
+/// Returns the stream of pages; see [`collect_n`](crate::paginate::collect_n)
+/// to take a number of packages from it.
+pub fn paginate_npm<'a>(
+    query: &'a str,
+    options: &'a NpmSearchOptions,
+) -> impl Stream<Item = Result<Vec<NpmHit>, SearchError>> + 'a {
+    paginate(250, move |page| async move {
+        let options = options.clone().size(page.size).from(page.offset());
+        Ok(search_npm_registry_with(Some(query), &options)
+            .await?
+            .into_hits())
+    })
+}
+
+/// Searches for packages on npms.io.
+///
+/// npms.io is no longer actively maintained and its index can lag behind the
+/// registry, so it is only available with the `npms` feature.
+///
+/// # Arguments
+///
+/// * `query` - The search query (optional).
+///
+/// # Returns
+///
+/// Returns the matching packages or an error.
+#[cfg(feature = "npms")]
+pub async fn search_npms(query: Option<&str>) -> Result<SearchResults<NpmHit>, SearchError> {
+    let response = ApiClient::new("https://api.npms.io/v2/search/", None)
+        .set_param("q", query.unwrap_or(""))
+        .set_param("size", "25")
+        .get("")
+        .await?;
+    SearchResults::from_raw(response, "/results")
+}
+
+/// Fetches the registry document of a package.
+///
+/// The document holds the package's `dist-tags` and the metadata of every
+/// published version, including dependencies and tarball URLs.
+///
+/// # Arguments
+///
+/// * `name` - The name of the package.
+///
+/// # Returns
+///
+/// Returns the package document as a `serde_json::Value` or an error.
+pub async fn get_package(name: &str) -> Result<Value, SearchError> {
+    ApiClient::new(REGISTRY, None)
+        .get(&encode_path_segment(name))
+        .await
+}
+
+/// Fetches the metadata of one version of a package.
+///
+/// # Arguments
+///
+/// * `name` - The name of the package.
+/// * `version` - A version number or dist-tag, e.g. `1.2.3` or `latest`.
+///
+/// # Returns
+///
+/// Returns the version's manifest, including `dependencies` and `dist.tarball`,
+/// as a `serde_json::Value` or an error.
+pub async fn get_package_version(name: &str, version: &str) -> Result<Value, SearchError> {
+    ApiClient::new(REGISTRY, None)
+        .get(&format!(
+            "{}/{}",
+            encode_path_segment(name),
+            encode_path_segment(version)
+        ))
+        .await
+}
+
+/// Fetches the total downloads of a package over a period.
+///
+/// # Arguments
+///
+/// * `name` - The name of the package.
+/// * `period` - `last-day`, `last-week`, `last-month`, `last-year`, or a
+///   `YYYY-MM-DD:YYYY-MM-DD` date range.
+///
+/// # Returns
+///
+/// Returns the download count as a `serde_json::Value` or an error.
+pub async fn npm_downloads(name: &str, period: &str) -> Result<Value, SearchError> {
+    ApiClient::new(DOWNLOADS_API, None)
+        .get(&downloads_endpoint("point", period, name))
+        .await
+}
+
+/// Fetches the daily downloads of a package over a period.
+///
+/// # Arguments
+///
+/// * `name` - The name of the package.
+/// * `period` - `last-day`, `last-week`, `last-month`, `last-year`, or a
+///   `YYYY-MM-DD:YYYY-MM-DD` date range.
+///
+/// # Returns
+///
+/// Returns the per-day download counts as a `serde_json::Value` or an error.
+pub async fn npm_downloads_range(name: &str, period: &str) -> Result<Value, SearchError> {
+    ApiClient::new(DOWNLOADS_API, None)
+        .get(&downloads_endpoint("range", period, name))
+        .await
+}
+
+/// Returns the endpoint of the download counts of `name`, keeping the
+/// slash of a scoped name as the downloads API expects.
+fn downloads_endpoint(kind: &str, period: &str, name: &str) -> String {
+    format!(
+        "{}/{}/{}",
+        kind,
+        encode_path_segment(period),
+        encode_path(name)
+    )
+}
+
+/// The npm registry.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Npm;
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl Registry for Npm {
+    fn name(&self) -> &str {
+        "npm"
+    }
+
+    async fn search(&self, query: &SearchQuery) -> Result<Vec<SearchHit>, RegistryError> {
+        // The registry returns at most 250 packages per search.
+        let size = query.limit.min(250) as u32;
+        let results = search_npm_registry(Some(&query.text), size, 0)
+            .await
+            .map_err(|error| RegistryError::new(self.name(), error))?;
+        Ok(results
+            .into_iter()
+            .take(query.limit)
+            .map(SearchHit::from)
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_the_scope_of_package_names_in_download_endpoints() {
+        assert_eq!(
+            downloads_endpoint("point", "last-week", "@babel/core"),
+            "point/last-week/@babel/core"
+        );
+        assert_eq!(
+            downloads_endpoint("range", "2024-01-01:2024-01-31", "left pad"),
+            "range/2024-01-01:2024-01-31/left%20pad"
+        );
+    }
+}
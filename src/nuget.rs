@@ -0,0 +1,90 @@
+use crate::error::SearchError;
+use crate::registry::{
+    collect_hits, text, Registry, RegistryConfig, RegistryError, SearchHit, SearchQuery,
+};
+use async_trait::async_trait;
+use serde_json::Value;
+
+const NUGET_SEARCH: &str = "https://azuresearch-usnc.nuget.org/query";
+
+/// Searches for packages on NuGet.
+///
+/// # Arguments
+///
+/// * `query` - The search query (optional).
+/// * `skip` - The number of results to skip.
+/// * `take` - The number of results to return.
+/// * `prerelease` - Whether to include prerelease packages.
+///
+/// # Returns
+///
+/// Returns the search results as a `serde_json::Value` or an error.
+pub async fn search_nuget(
+    query: Option<&str>,
+    skip: u32,
+    take: u32,
+    prerelease: bool,
+) -> Result<Value, SearchError> {
+    search_nuget_with(query, skip, take, prerelease, &RegistryConfig::new()).await
+}
+
+/// Searches for packages on a NuGet v3 feed, such as a Nexus, Artifactory
+/// or Azure Artifacts feed.
+///
+/// # Arguments
+///
+/// * `query` - The search query (optional).
+/// * `skip` - The number of results to skip.
+/// * `take` - The number of results to return.
+/// * `prerelease` - Whether to include prerelease packages.
+/// * `config` - The URL of the feed's `SearchQueryService` resource and the
+///   credentials; nuget.org is used when no URL is set.
+///
+/// # Returns
+///
+/// Returns the search results as a `serde_json::Value` or an error.
+pub async fn search_nuget_with(
+    query: Option<&str>,
+    skip: u32,
+    take: u32,
+    prerelease: bool,
+    config: &RegistryConfig,
+) -> Result<Value, SearchError> {
+    config
+        .client(NUGET_SEARCH, None)
+        .set_param("q", query.unwrap_or(""))
+        .set_param("skip", &skip.to_string())
+        .set_param("take", &take.to_string())
+        .set_param("prerelease", &prerelease.to_string())
+        .get("")
+        .await
+}
+
+/// The NuGet gallery.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NuGet;
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl Registry for NuGet {
+    fn name(&self) -> &str {
+        "nuget"
+    }
+
+    async fn search(&self, query: &SearchQuery) -> Result<Vec<SearchHit>, RegistryError> {
+        let results = search_nuget(Some(&query.text), 0, query.limit as u32, false)
+            .await
+            .map_err(|error| RegistryError::new(self.name(), error))?;
+        Ok(collect_hits(&results["data"], query.limit, |package| {
+            SearchHit {
+                name: text(&package["id"]).unwrap_or_default(),
+                version: text(&package["version"]),
+                description: text(&package["description"]),
+                homepage: text(&package["projectUrl"]),
+                downloads: package["totalDownloads"].as_u64(),
+                source: self.name().to_string(),
+                ..Default::default()
+            }
+        }))
+    }
+}
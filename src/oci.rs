@@ -0,0 +1,129 @@
+use crate::client::encode_path_segment;
+use crate::error::SearchError;
+use crate::registry::{collect_hits, text, Registry, RegistryError, SearchHit, SearchQuery};
+use crate::ApiClient;
+use async_trait::async_trait;
+use serde_json::{json, Value};
+
+/// Searches for container images on the GitHub Container Registry.
+///
+/// ghcr.io has no global search, so the query must name an owner, optionally
+/// followed by a name filter: `owner` lists every container package the
+/// owner publishes, and `owner/name` keeps only those whose name contains
+/// `name`. The GitHub packages API requires a token with `read:packages`.
+///
+/// # Arguments
+///
+/// * `query` - The owner and optional name filter (optional).
+/// * `token` - A GitHub token with the `read:packages` scope.
+///
+/// # Returns
+///
+/// Returns the matching packages as a `serde_json::Value` array or an error.
+pub async fn search_ghcr(query: Option<&str>, token: &str) -> Result<Value, SearchError> {
+    let query = query.unwrap_or("");
+    let (owner, filter) = query.split_once('/').unwrap_or((query, ""));
+
+    let packages = ApiClient::new("https://api.github.com/users/", None)
+        .set_param("package_type", "container")
+        .set_param("per_page", "100")
+        .set_header("Accept", "application/vnd.github+json")
+        .set_header("Authorization", &format!("Bearer {}", token))
+        .get(&format!("{}/packages", encode_path_segment(owner)))
+        .await?;
+
+    let filter = filter.to_lowercase();
+    Ok(packages
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter(|package| {
+            package["name"]
+                .as_str()
+                .is_some_and(|name| name.to_lowercase().contains(&filter))
+        })
+        .cloned()
+        .collect())
+}
+
+/// Searches for repositories on Quay.io.
+///
+/// # Arguments
+///
+/// * `query` - The search query (optional).
+///
+/// # Returns
+///
+/// Returns the matching repositories as a `serde_json::Value` array or an error.
+pub async fn search_quay(query: Option<&str>) -> Result<Value, SearchError> {
+    let response = ApiClient::new("https://quay.io/api/v1/find/", None)
+        .set_param("query", query.unwrap_or(""))
+        .get("repositories")
+        .await?;
+
+    Ok(response
+        .get("results")
+        .cloned()
+        .unwrap_or_else(|| json!([])))
+}
+
+/// The GitHub Container Registry.
+#[derive(Debug, Clone)]
+pub struct Ghcr {
+    /// A GitHub token with the `read:packages` scope.
+    pub token: String,
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl Registry for Ghcr {
+    fn name(&self) -> &str {
+        "ghcr"
+    }
+
+    async fn search(&self, query: &SearchQuery) -> Result<Vec<SearchHit>, RegistryError> {
+        let results = search_ghcr(Some(&query.text), &self.token)
+            .await
+            .map_err(|error| RegistryError::new(self.name(), error))?;
+        Ok(collect_hits(&results, query.limit, |package| SearchHit {
+            name: text(&package["owner"]["login"])
+                .zip(text(&package["name"]))
+                .map(|(owner, name)| format!("{}/{}", owner, name))
+                .unwrap_or_default(),
+            version: None,
+            description: None,
+            homepage: text(&package["html_url"]),
+            source: self.name().to_string(),
+            ..Default::default()
+        }))
+    }
+}
+
+/// The Quay.io container registry.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Quay;
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl Registry for Quay {
+    fn name(&self) -> &str {
+        "quay"
+    }
+
+    async fn search(&self, query: &SearchQuery) -> Result<Vec<SearchHit>, RegistryError> {
+        let results = search_quay(Some(&query.text))
+            .await
+            .map_err(|error| RegistryError::new(self.name(), error))?;
+        Ok(collect_hits(&results, query.limit, |repo| SearchHit {
+            name: text(&repo["namespace"]["name"])
+                .zip(text(&repo["name"]))
+                .map(|(namespace, name)| format!("{}/{}", namespace, name))
+                .unwrap_or_default(),
+            version: None,
+            description: text(&repo["description"]),
+            homepage: text(&repo["href"]).map(|href| format!("https://quay.io{}", href)),
+            source: self.name().to_string(),
+            ..Default::default()
+        }))
+    }
+}
@@ -0,0 +1,613 @@
+//! Writing search results out, as the `search` binary does.
+//!
+//! YAML output needs the `yaml` feature, and TOML output the `toml`
+//! feature.
+
+use crate::alfred::ScriptFilter;
+use crate::error::SearchError;
+use crate::registry::SearchHit;
+use futures::{Stream, StreamExt};
+use serde::Serialize;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+/// The number of characters of a description shown in a table.
+const TABLE_DESCRIPTION_LEN: usize = 60;
+
+/// The columns of CSV and TSV output.
+const RECORD_COLUMNS: [&str; 9] = [
+    "source",
+    "name",
+    "version",
+    "downloads",
+    "description",
+    "homepage",
+    "repository",
+    "license",
+    "install",
+];
+
+/// Writes data as pretty-printed JSON, followed by a newline.
+///
+/// # Arguments
+///
+/// * `writer` - Where to write, e.g. `std::io::stdout()`.
+/// * `data` - The data to write, which must implement `Serialize`.
+///
+/// # Returns
+///
+/// Returns `Ok(())` if the operation succeeds, or an error.
+pub fn write_json<W: Write, T: Serialize>(mut writer: W, data: &T) -> Result<(), SearchError> {
+    serde_json::to_writer_pretty(&mut writer, data)?;
+    writeln!(writer)?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// Writes JSON data to a file.
+///
+/// # Arguments
+///
+/// * `data` - The data to write, which must implement `Serialize`.
+/// * `path` - The file to write to; it is created or truncated.
+///
+/// # Returns
+///
+/// Returns `Ok(())` if the operation succeeds, or an error.
+pub fn write_json_to_file<T: Serialize>(
+    data: &T,
+    path: impl AsRef<Path>,
+) -> Result<(), SearchError> {
+    write_json(BufWriter::new(File::create(path)?), data)
+}
+
+/// Writes items as newline-delimited JSON, one compact JSON object per
+/// line, for `jq`, `xsv`, and log pipelines.
+///
+/// # Arguments
+///
+/// * `writer` - Where to write, e.g. `std::io::stdout()`.
+/// * `items` - The items to write, one per line, e.g. hits.
+///
+/// # Returns
+///
+/// Returns `Ok(())` if the operation succeeds, or an error.
+pub fn write_ndjson<W: Write, T: Serialize>(mut writer: W, items: &[T]) -> Result<(), SearchError> {
+    for item in items {
+        serde_json::to_writer(&mut writer, item)?;
+        writeln!(writer)?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// Writes a stream of result pages as newline-delimited JSON, flushing
+/// after every page so that readers see each result as soon as it arrives
+/// rather than once every page has been fetched.
+///
+/// # Arguments
+///
+/// * `writer` - Where to write, e.g. `std::io::stdout()`.
+/// * `pages` - The pages, usually from [`paginate`](crate::paginate::paginate).
+///
+/// # Returns
+///
+/// Returns the number of items written, or the first error of the stream
+/// once the items before it have been written.
+pub async fn write_ndjson_stream<W: Write, T: Serialize>(
+    mut writer: W,
+    pages: impl Stream<Item = Result<Vec<T>, SearchError>>,
+) -> Result<usize, SearchError> {
+    let mut pages = std::pin::pin!(pages);
+    let mut written = 0;
+    while let Some(page) = pages.next().await {
+        for item in page? {
+            serde_json::to_writer(&mut writer, &item)?;
+            writeln!(writer)?;
+            written += 1;
+        }
+        writer.flush()?;
+    }
+    Ok(written)
+}
+
+/// Serializes data as a YAML document.
+///
+/// # Arguments
+///
+/// * `data` - The data to serialize, which must implement `Serialize`.
+///
+/// # Returns
+///
+/// Returns the YAML text or an error.
+#[cfg(feature = "yaml")]
+pub fn to_yaml<T: Serialize>(data: &T) -> Result<String, SearchError> {
+    serde_yaml::to_string(data).map_err(|error| SearchError::Encode(error.to_string()))
+}
+
+/// Writes data as a YAML document.
+///
+/// # Arguments
+///
+/// * `writer` - Where to write, e.g. `std::io::stdout()`.
+/// * `data` - The data to write, which must implement `Serialize`.
+///
+/// # Returns
+///
+/// Returns `Ok(())` if the operation succeeds, or an error.
+#[cfg(feature = "yaml")]
+pub fn write_yaml<W: Write, T: Serialize>(mut writer: W, data: &T) -> Result<(), SearchError> {
+    writer.write_all(to_yaml(data)?.as_bytes())?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// Serializes data as a pretty-printed TOML document.
+///
+/// A TOML document is a table, so `data` must serialize as a map or a
+/// struct; wrap lists of hits in a struct with a `hits` field, for
+/// example, which is written as an array of `[[hits]]` tables.
+///
+/// # Arguments
+///
+/// * `data` - The data to serialize, which must implement `Serialize`.
+///
+/// # Returns
+///
+/// Returns the TOML text or an error.
+#[cfg(feature = "toml")]
+pub fn to_toml<T: Serialize>(data: &T) -> Result<String, SearchError> {
+    toml::to_string_pretty(data).map_err(|error| SearchError::Encode(error.to_string()))
+}
+
+/// Writes data as a pretty-printed TOML document; see [`to_toml`].
+///
+/// # Arguments
+///
+/// * `writer` - Where to write, e.g. `std::io::stdout()`.
+/// * `data` - The data to write, which must implement `Serialize`.
+///
+/// # Returns
+///
+/// Returns `Ok(())` if the operation succeeds, or an error.
+#[cfg(feature = "toml")]
+pub fn write_toml<W: Write, T: Serialize>(mut writer: W, data: &T) -> Result<(), SearchError> {
+    writer.write_all(to_toml(data)?.as_bytes())?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// Writes hits as an aligned table of their source, name, version,
+/// downloads, install command, and the start of their description.
+///
+/// # Arguments
+///
+/// * `writer` - Where to write, e.g. `std::io::stdout()`.
+/// * `hits` - The hits to write, one per row.
+///
+/// # Returns
+///
+/// Returns `Ok(())` if the operation succeeds, or an error.
+pub fn write_table<W: Write>(mut writer: W, hits: &[SearchHit]) -> Result<(), SearchError> {
+    let header = [
+        "SOURCE",
+        "NAME",
+        "VERSION",
+        "DOWNLOADS",
+        "INSTALL",
+        "DESCRIPTION",
+    ]
+    .map(str::to_string);
+    let rows: Vec<[String; 6]> = hits
+        .iter()
+        .map(|hit| {
+            [
+                hit.source.clone(),
+                hit.name.clone(),
+                hit.version.clone().unwrap_or_default(),
+                hit.downloads
+                    .map(|downloads| downloads.to_string())
+                    .unwrap_or_default(),
+                hit.install_command().unwrap_or_default(),
+                truncate(
+                    &first_line(hit.description.as_deref()),
+                    TABLE_DESCRIPTION_LEN,
+                ),
+            ]
+        })
+        .collect();
+
+    let mut widths = [0; 6];
+    for row in std::iter::once(&header).chain(&rows) {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    for row in std::iter::once(&header).chain(&rows) {
+        let mut line = String::new();
+        for (column, (cell, width)) in row.iter().zip(widths).enumerate() {
+            if column == 3 {
+                // Right-align the download counts.
+                line.push_str(&format!("{:>width$}  ", cell, width = width));
+            } else {
+                line.push_str(&format!("{:<width$}  ", cell, width = width));
+            }
+        }
+        writeln!(writer, "{}", line.trim_end())?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// Writes hits as CSV with a header row, quoting fields as RFC 4180
+/// requires. Fields that start like a formula are prefixed with `'`, so
+/// that spreadsheets don't evaluate them.
+///
+/// # Arguments
+///
+/// * `writer` - Where to write, e.g. `std::io::stdout()`.
+/// * `hits` - The hits to write, one per record.
+///
+/// # Returns
+///
+/// Returns `Ok(())` if the operation succeeds, or an error.
+pub fn write_csv<W: Write>(mut writer: W, hits: &[SearchHit]) -> Result<(), SearchError> {
+    writeln!(writer, "{}", RECORD_COLUMNS.join(","))?;
+    for hit in hits {
+        let record: Vec<_> = record(hit).iter().map(|field| csv_field(field)).collect();
+        writeln!(writer, "{}", record.join(","))?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// Writes hits as tab-separated values with a header row, in the same
+/// columns as [`write_csv`].
+///
+/// TSV has no quoting, so tabs and line breaks within a field are written
+/// as `\t`, `\n`, and `\r`, and backslashes as `\\`.
+///
+/// # Arguments
+///
+/// * `writer` - Where to write, e.g. `std::io::stdout()`.
+/// * `hits` - The hits to write, one per record.
+///
+/// # Returns
+///
+/// Returns `Ok(())` if the operation succeeds, or an error.
+pub fn write_tsv<W: Write>(mut writer: W, hits: &[SearchHit]) -> Result<(), SearchError> {
+    writeln!(writer, "{}", RECORD_COLUMNS.join("\t"))?;
+    for hit in hits {
+        let record: Vec<_> = record(hit).iter().map(|field| tsv_field(field)).collect();
+        writeln!(writer, "{}", record.join("\t"))?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// Writes hits as a Markdown table of their name, linked to their http(s)
+/// homepage or repository, version, downloads, source, and the first line
+/// of their description.
+///
+/// # Arguments
+///
+/// * `writer` - Where to write, e.g. `std::io::stdout()`.
+/// * `hits` - The hits to write, one per row.
+///
+/// # Returns
+///
+/// Returns `Ok(())` if the operation succeeds, or an error.
+pub fn write_markdown<W: Write>(mut writer: W, hits: &[SearchHit]) -> Result<(), SearchError> {
+    writeln!(
+        writer,
+        "| Name | Version | Downloads | Source | Description |"
+    )?;
+    writeln!(writer, "| --- | --- | ---: | --- | --- |")?;
+    for hit in hits {
+        let name = markdown_cell(&hit.name);
+        let link = hit
+            .homepage
+            .as_deref()
+            .or(hit.repository.as_deref())
+            .filter(|url| url.starts_with("https://") || url.starts_with("http://"));
+        let name = match link {
+            Some(url) => format!("[{}]({})", name, markdown_url(url)),
+            None => name,
+        };
+        writeln!(
+            writer,
+            "| {} | {} | {} | {} | {} |",
+            name,
+            markdown_cell(hit.version.as_deref().unwrap_or_default()),
+            hit.downloads
+                .map(|downloads| downloads.to_string())
+                .unwrap_or_default(),
+            markdown_cell(&hit.source),
+            markdown_cell(&first_line(hit.description.as_deref())),
+        )?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// Writes hits as a standalone HTML page with a styled table of their name,
+/// linked to their homepage or repository, version, downloads, source, and
+/// description.
+///
+/// # Arguments
+///
+/// * `writer` - Where to write, e.g. `std::io::stdout()`.
+/// * `hits` - The hits to write, one per row.
+/// * `title` - The title of the page, e.g. the query.
+///
+/// # Returns
+///
+/// Returns `Ok(())` if the operation succeeds, or an error.
+pub fn write_html<W: Write>(
+    mut writer: W,
+    hits: &[SearchHit],
+    title: &str,
+) -> Result<(), SearchError> {
+    let title = html_escape(title);
+    write!(
+        writer,
+        r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<title>{title}</title>
+<style>
+body {{ font-family: system-ui, sans-serif; margin: 2rem; color: #222; }}
+table {{ border-collapse: collapse; width: 100%; }}
+th, td {{ padding: 0.4rem 0.8rem; border-bottom: 1px solid #ddd; text-align: left; vertical-align: top; }}
+th {{ background: #f5f5f5; }}
+td.downloads {{ text-align: right; font-variant-numeric: tabular-nums; }}
+a {{ color: #0366d6; text-decoration: none; }}
+</style>
+</head>
+<body>
+<h1>{title}</h1>
+<table>
+<thead><tr><th>Name</th><th>Version</th><th>Downloads</th><th>Source</th><th>Description</th></tr></thead>
+<tbody>
+"#
+    )?;
+    for hit in hits {
+        let name = html_escape(&hit.name);
+        let link = hit
+            .homepage
+            .as_deref()
+            .or(hit.repository.as_deref())
+            .filter(|url| url.starts_with("https://") || url.starts_with("http://"));
+        let name = match link {
+            Some(url) => format!("<a href=\"{}\">{}</a>", html_escape(url), name),
+            None => name,
+        };
+        writeln!(
+            writer,
+            "<tr><td>{}</td><td>{}</td><td class=\"downloads\">{}</td><td>{}</td><td>{}</td></tr>",
+            name,
+            html_escape(hit.version.as_deref().unwrap_or_default()),
+            hit.downloads
+                .map(|downloads| downloads.to_string())
+                .unwrap_or_default(),
+            html_escape(&hit.source),
+            html_escape(&first_line(hit.description.as_deref())),
+        )?;
+    }
+    writeln!(writer, "</tbody>\n</table>\n</body>\n</html>")?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// Writes one tab-separated line per hit (name, version, source, and
+/// description), for piping into `fzf`, `grep`, or `cut`.
+///
+/// # Arguments
+///
+/// * `writer` - Where to write, e.g. `std::io::stdout()`.
+/// * `hits` - The hits to write, one per line.
+///
+/// # Returns
+///
+/// Returns `Ok(())` if the operation succeeds, or an error.
+pub fn write_plain<W: Write>(mut writer: W, hits: &[SearchHit]) -> Result<(), SearchError> {
+    for hit in hits {
+        writeln!(
+            writer,
+            "{}\t{}\t{}\t{}",
+            hit.name,
+            hit.version.as_deref().unwrap_or("-"),
+            hit.source,
+            first_line(hit.description.as_deref())
+        )?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// Writes hits as Alfred Script Filter JSON, one item per hit; see
+/// [`ScriptFilter::from_hits`].
+///
+/// # Arguments
+///
+/// * `writer` - Where to write, e.g. `std::io::stdout()`.
+/// * `hits` - The hits to write, one per item.
+///
+/// # Returns
+///
+/// Returns `Ok(())` if the operation succeeds, or an error.
+pub fn write_alfred<W: Write>(writer: W, hits: &[SearchHit]) -> Result<(), SearchError> {
+    write_json(writer, &ScriptFilter::from_hits(hits))
+}
+
+/// Returns the first line of a description, or an empty string.
+fn first_line(description: Option<&str>) -> String {
+    description
+        .and_then(|description| description.lines().next())
+        .unwrap_or_default()
+        .trim()
+        .to_string()
+}
+
+/// Shortens `text` to at most `len` characters, ending in an ellipsis if
+/// anything was cut.
+fn truncate(text: &str, len: usize) -> String {
+    if text.chars().count() <= len {
+        return text.to_string();
+    }
+    let mut truncated: String = text.chars().take(len.saturating_sub(1)).collect();
+    truncated.push('…');
+    truncated
+}
+
+/// Escapes the characters that HTML text and attribute values reserve.
+fn html_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+/// Escapes the characters that would end or break a Markdown table cell.
+fn markdown_cell(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('|', "\\|")
+}
+
+/// Percent-encodes the characters that would end a Markdown link
+/// destination or split a table cell.
+fn markdown_url(url: &str) -> String {
+    let mut encoded = String::with_capacity(url.len());
+    for c in url.chars() {
+        match c {
+            ' ' | '(' | ')' | '|' | '<' | '>' => encoded.push_str(&format!("%{:02X}", c as u8)),
+            c => encoded.push(c),
+        }
+    }
+    encoded
+}
+
+/// Returns the fields of a hit in the order of [`RECORD_COLUMNS`].
+fn record(hit: &SearchHit) -> [String; 9] {
+    [
+        hit.source.clone(),
+        hit.name.clone(),
+        hit.version.clone().unwrap_or_default(),
+        hit.downloads
+            .map(|downloads| downloads.to_string())
+            .unwrap_or_default(),
+        hit.description.clone().unwrap_or_default(),
+        hit.homepage.clone().unwrap_or_default(),
+        hit.repository.clone().unwrap_or_default(),
+        hit.license.clone().unwrap_or_default(),
+        hit.install_command().unwrap_or_default(),
+    ]
+}
+
+/// Quotes a CSV field if it contains a separator, quote, or line break.
+///
+/// A field that a spreadsheet would read as a formula, such as `=1+1` or
+/// `@SUM(A1)`, is prefixed with `'` so that it stays text.
+fn csv_field(field: &str) -> String {
+    let field = if field.starts_with(['=', '+', '-', '@', '\t', '\r']) {
+        format!("'{}", field)
+    } else {
+        field.to_string()
+    };
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field
+    }
+}
+
+/// Escapes the characters that would split a TSV field.
+fn tsv_field(field: &str) -> String {
+    field
+        .replace('\\', "\\\\")
+        .replace('\t', "\\t")
+        .replace('\n', "\\n")
+        .replace('\r', "\\r")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quotes_csv_fields_only_when_needed() {
+        assert_eq!(csv_field("serde"), "serde");
+        assert_eq!(csv_field("a, b"), "\"a, b\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(csv_field("two\nlines"), "\"two\nlines\"");
+    }
+
+    #[test]
+    fn keeps_csv_fields_from_reading_as_formulas() {
+        assert_eq!(csv_field("=1+1"), "'=1+1");
+        assert_eq!(csv_field("@SUM(A1)"), "'@SUM(A1)");
+        assert_eq!(csv_field("-2,3"), "\"'-2,3\"");
+        assert_eq!(csv_field("1-2"), "1-2");
+    }
+
+    #[test]
+    fn escapes_tsv_separators() {
+        assert_eq!(tsv_field("a\tb\nc\r\\"), "a\\tb\\nc\\r\\\\");
+        assert_eq!(tsv_field("plain"), "plain");
+    }
+
+    #[test]
+    fn writes_csv_with_a_header_row() {
+        let hit = SearchHit {
+            name: "serde".to_string(),
+            version: Some("1.0.219".to_string()),
+            source: "crates".to_string(),
+            ..SearchHit::default()
+        };
+        let mut out = Vec::new();
+        write_csv(&mut out, &[hit]).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        let mut lines = out.lines();
+        assert_eq!(lines.next(), Some(RECORD_COLUMNS.join(",").as_str()));
+        assert!(lines.next().unwrap().starts_with("crates,serde,1.0.219,"));
+        assert_eq!(lines.next(), None);
+    }
+
+    #[test]
+    fn escapes_html() {
+        assert_eq!(
+            html_escape("<a href=\"x\">Tom & Jerry's</a>"),
+            "&lt;a href=&quot;x&quot;&gt;Tom &amp; Jerry&#39;s&lt;/a&gt;"
+        );
+    }
+
+    #[test]
+    fn encodes_markdown_link_destinations() {
+        assert_eq!(
+            markdown_url("https://example.com/a (b)|<c>"),
+            "https://example.com/a%20%28b%29%7C%3Cc%3E"
+        );
+    }
+
+    #[test]
+    fn links_only_http_urls_in_markdown() {
+        let hit = |homepage: &str| SearchHit {
+            name: "left|pad".to_string(),
+            homepage: Some(homepage.to_string()),
+            ..SearchHit::default()
+        };
+        let mut out = Vec::new();
+        write_markdown(
+            &mut out,
+            &[hit("https://example.com/x"), hit("javascript:alert(1)")],
+        )
+        .unwrap();
+        let out = String::from_utf8(out).unwrap();
+        let rows: Vec<_> = out.lines().skip(2).collect();
+        assert!(rows[0].starts_with("| [left\\|pad](https://example.com/x) |"));
+        assert!(rows[1].starts_with("| left\\|pad |"));
+    }
+}
@@ -0,0 +1,169 @@
+//! Following paginated search results as a stream.
+
+use crate::error::SearchError;
+use futures::stream::{self, Stream, StreamExt};
+use std::future::Future;
+
+/// A page of results to request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Page {
+    /// The 1-based page number.
+    pub number: u32,
+    /// The number of results per page.
+    pub size: u32,
+}
+
+impl Page {
+    /// Returns the offset of the page's first result, for registries that
+    /// page by offset (`from`, `skip`, `start`) instead of page number.
+    pub fn offset(&self) -> u32 {
+        (self.number - 1) * self.size
+    }
+}
+
+/// Returns a stream of result pages fetched one after the other by `fetch`.
+///
+/// The stream ends after a page with fewer than `page_size` results, or
+/// after the first error.
+///
+/// # Arguments
+///
+/// * `page_size` - The number of results to request per page.
+/// * `fetch` - Fetches the results of a page, e.g. by calling a search
+///   function with [`Page::number`] or [`Page::offset`].
+///
+/// # Returns
+///
+/// Returns the stream of pages.
+pub fn paginate<T, F, Fut>(
+    page_size: u32,
+    mut fetch: F,
+) -> impl Stream<Item = Result<Vec<T>, SearchError>>
+where
+    F: FnMut(Page) -> Fut,
+    Fut: Future<Output = Result<Vec<T>, SearchError>>,
+{
+    stream::unfold((1, false), move |(number, done)| {
+        let page = (!done).then(|| {
+            fetch(Page {
+                number,
+                size: page_size,
+            })
+        });
+        async move {
+            match page?.await {
+                Ok(hits) if hits.is_empty() => None,
+                Ok(hits) => {
+                    let last = hits.len() < page_size as usize;
+                    Some((Ok(hits), (number + 1, last)))
+                }
+                Err(error) => Some((Err(error), (number, true))),
+            }
+        }
+    })
+}
+
+/// Collects the first `n` results of a stream of pages.
+///
+/// # Arguments
+///
+/// * `pages` - The pages, usually from [`paginate`].
+/// * `n` - The number of results to collect.
+///
+/// # Returns
+///
+/// Returns up to `n` results, fewer if the pages run out, or the first
+/// error.
+pub async fn collect_n<T>(
+    pages: impl Stream<Item = Result<Vec<T>, SearchError>>,
+    n: usize,
+) -> Result<Vec<T>, SearchError> {
+    let mut pages = std::pin::pin!(pages);
+    let mut hits = Vec::new();
+    while hits.len() < n {
+        match pages.next().await {
+            Some(page) => hits.extend(page?),
+            None => break,
+        }
+    }
+    hits.truncate(n);
+    Ok(hits)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+
+    /// Serves `total` numbered results in pages, recording each page asked for.
+    fn results(
+        total: u32,
+        requested: &RefCell<Vec<Page>>,
+    ) -> impl FnMut(Page) -> std::future::Ready<Result<Vec<u32>, SearchError>> + '_ {
+        move |page| {
+            requested.borrow_mut().push(page);
+            let end = (page.offset() + page.size).min(total);
+            std::future::ready(Ok((page.offset().min(end)..end).collect()))
+        }
+    }
+
+    #[test]
+    fn offsets_pages_by_their_size() {
+        assert_eq!(
+            Page {
+                number: 1,
+                size: 25
+            }
+            .offset(),
+            0
+        );
+        assert_eq!(
+            Page {
+                number: 3,
+                size: 25
+            }
+            .offset(),
+            50
+        );
+    }
+
+    #[tokio::test]
+    async fn stops_after_a_short_page() {
+        let requested = RefCell::new(Vec::new());
+        let pages: Vec<_> = paginate(10, results(25, &requested)).collect().await;
+        let sizes: Vec<_> = pages.into_iter().map(|page| page.unwrap().len()).collect();
+        assert_eq!(sizes, [10, 10, 5]);
+        assert_eq!(requested.borrow().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn stops_at_an_empty_page() {
+        let requested = RefCell::new(Vec::new());
+        let pages: Vec<_> = paginate(10, results(20, &requested)).collect().await;
+        assert_eq!(pages.len(), 2);
+        assert_eq!(requested.borrow().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn stops_after_an_error() {
+        let mut calls = 0;
+        let pages: Vec<Result<Vec<u32>, _>> = paginate(10, |_| {
+            calls += 1;
+            std::future::ready(Err(SearchError::NotFound))
+        })
+        .collect()
+        .await;
+        assert_eq!(pages.len(), 1);
+        assert_eq!(calls, 1);
+    }
+
+    #[tokio::test]
+    async fn collects_only_the_pages_needed() {
+        let requested = RefCell::new(Vec::new());
+        let hits = collect_n(paginate(10, results(100, &requested)), 15)
+            .await
+            .unwrap();
+        assert_eq!(hits, (0..15).collect::<Vec<_>>());
+        assert_eq!(requested.borrow().len(), 2);
+    }
+}
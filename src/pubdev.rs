@@ -0,0 +1,86 @@
+use crate::client::encode_path_segment;
+use crate::error::SearchError;
+use crate::registry::{collect_hits, text, Registry, RegistryError, SearchHit, SearchQuery};
+use crate::ApiClient;
+use async_trait::async_trait;
+use futures::future::join_all;
+use serde_json::{json, Value};
+
+const PUB_API: &str = "https://pub.dev/api/";
+
+/// Searches for Dart and Flutter packages on pub.dev.
+///
+/// The search endpoint only returns package names, so each result's latest
+/// version and description are fetched with follow-up requests, all at once.
+/// A package whose request fails is listed by name alone.
+///
+/// # Arguments
+///
+/// * `query` - The search query (optional).
+/// * `page` - The 1-based result page.
+///
+/// # Returns
+///
+/// Returns the search results as a `serde_json::Value` array or an error.
+pub async fn search_pub(query: Option<&str>, page: u32) -> Result<Value, SearchError> {
+    let response = ApiClient::new(PUB_API, None)
+        .set_param("q", query.unwrap_or(""))
+        .set_param("page", &page.to_string())
+        .get("search")
+        .await?;
+
+    let names: Vec<&str> = response["packages"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|package| package["package"].as_str())
+        .collect();
+
+    let packages = join_all(names.iter().map(|name| get_package(name))).await;
+    let hits = names
+        .into_iter()
+        .zip(packages)
+        .map(|(name, package)| {
+            let latest = package.map_or(Value::Null, |package| package["latest"].clone());
+            json!({
+                "name": name,
+                "version": latest["version"],
+                "description": latest["pubspec"]["description"],
+                "homepage": latest["pubspec"]["homepage"],
+            })
+        })
+        .collect();
+    Ok(Value::Array(hits))
+}
+
+async fn get_package(name: &str) -> Result<Value, SearchError> {
+    ApiClient::new(PUB_API, None)
+        .get(&format!("packages/{}", encode_path_segment(name)))
+        .await
+}
+
+/// The pub.dev Dart package repository.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PubDev;
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl Registry for PubDev {
+    fn name(&self) -> &str {
+        "pub"
+    }
+
+    async fn search(&self, query: &SearchQuery) -> Result<Vec<SearchHit>, RegistryError> {
+        let results = search_pub(Some(&query.text), 1)
+            .await
+            .map_err(|error| RegistryError::new(self.name(), error))?;
+        Ok(collect_hits(&results, query.limit, |package| SearchHit {
+            name: text(&package["name"]).unwrap_or_default(),
+            version: text(&package["version"]),
+            description: text(&package["description"]),
+            homepage: text(&package["homepage"]),
+            source: self.name().to_string(),
+            ..Default::default()
+        }))
+    }
+}
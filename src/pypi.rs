@@ -0,0 +1,88 @@
+use crate::client::encode_path_segment;
+use crate::error::SearchError;
+use crate::html::extract_text;
+use crate::registry::{collect_hits, text, Registry, RegistryError, SearchHit, SearchQuery};
+use crate::ApiClient;
+use async_trait::async_trait;
+use serde_json::{json, Value};
+
+/// Searches for packages on PyPI.
+///
+/// PyPI has no JSON search API, so this scrapes the warehouse search page
+/// and extracts the name, version, and description of every result.
+///
+/// # Arguments
+///
+/// * `query` - The search query (optional).
+/// * `page` - The 1-based result page.
+///
+/// # Returns
+///
+/// Returns the search results as a `serde_json::Value` array or an error.
+pub async fn search_pypi(query: Option<&str>, page: u32) -> Result<Value, SearchError> {
+    let html = ApiClient::new("https://pypi.org/search/", None)
+        .set_param("q", query.unwrap_or(""))
+        .set_param("page", &page.to_string())
+        .get_text("")
+        .await?;
+
+    Ok(Value::Array(parse_search_results(&html)))
+}
+
+/// Fetches the metadata of a project from PyPI's JSON API.
+///
+/// The response's `info` holds the latest release's summary, license, and
+/// `project_urls`, such as its source repository.
+///
+/// # Arguments
+///
+/// * `name` - The name of the project.
+///
+/// # Returns
+///
+/// Returns the project metadata as a `serde_json::Value` or an error.
+pub async fn get_project(name: &str) -> Result<Value, SearchError> {
+    ApiClient::new("https://pypi.org/pypi/", None)
+        .get(&format!("{}/json", encode_path_segment(name)))
+        .await
+}
+
+fn parse_search_results(html: &str) -> Vec<Value> {
+    html.split("class=\"package-snippet\"")
+        .skip(1)
+        .map(|snippet| {
+            json!({
+                "name": extract_text(snippet, "class=\"package-snippet__name\""),
+                "version": extract_text(snippet, "class=\"package-snippet__version\""),
+                "description": extract_text(snippet, "class=\"package-snippet__description\""),
+            })
+        })
+        .collect()
+}
+
+/// The Python Package Index.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PyPi;
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl Registry for PyPi {
+    fn name(&self) -> &str {
+        "pypi"
+    }
+
+    async fn search(&self, query: &SearchQuery) -> Result<Vec<SearchHit>, RegistryError> {
+        let results = search_pypi(Some(&query.text), 1)
+            .await
+            .map_err(|error| RegistryError::new(self.name(), error))?;
+        Ok(collect_hits(&results, query.limit, |package| SearchHit {
+            name: text(&package["name"]).unwrap_or_default(),
+            version: text(&package["version"]),
+            description: text(&package["description"]),
+            homepage: text(&package["name"])
+                .map(|name| format!("https://pypi.org/project/{}/", name)),
+            source: self.name().to_string(),
+            ..Default::default()
+        }))
+    }
+}
@@ -0,0 +1,151 @@
+//! Per-host request rate limiting shared by every [`ApiClient`](crate::ApiClient),
+//! and limits of single clients.
+
+use futures_timer::Delay;
+use std::collections::HashMap;
+use std::sync::{Arc, LazyLock, Mutex};
+use std::time::Duration;
+use web_time::Instant;
+
+/// The limits applied to hosts that ask crawlers to slow down, in requests
+/// per second.
+const DEFAULT_LIMITS: &[(&str, f64)] = &[
+    // https://crates.io/data-access#api
+    ("crates.io", 1.0),
+];
+
+static BUCKETS: LazyLock<Mutex<HashMap<String, TokenBucket>>> = LazyLock::new(|| {
+    Mutex::new(
+        DEFAULT_LIMITS
+            .iter()
+            .map(|&(host, rate)| (host.to_string(), TokenBucket::new(rate)))
+            .collect(),
+    )
+});
+
+/// A token bucket that refills at `rate` tokens per second and holds at
+/// most one second's worth of them.
+#[derive(Debug)]
+struct TokenBucket {
+    rate: f64,
+    tokens: f64,
+    updated: Instant,
+}
+
+impl TokenBucket {
+    fn new(rate: f64) -> Self {
+        Self {
+            rate,
+            tokens: rate.max(1.0),
+            updated: Instant::now(),
+        }
+    }
+
+    /// Takes a token, or returns how long to wait until one is available.
+    fn take(&mut self) -> Option<Duration> {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.updated).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.rate).min(self.rate.max(1.0));
+        self.updated = now;
+
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            None
+        } else {
+            Some(Duration::from_secs_f64((1.0 - self.tokens) / self.rate))
+        }
+    }
+}
+
+/// Limits every client in the process to `requests_per_second` requests to
+/// `host`, e.g. `crates.io`.
+///
+/// crates.io is limited to one request per second by default, as its
+/// crawler policy asks. A rate of 0 or less removes the limit.
+pub fn set_rate_limit(host: &str, requests_per_second: f64) {
+    let mut buckets = BUCKETS.lock().unwrap_or_else(|error| error.into_inner());
+    if requests_per_second > 0.0 {
+        buckets.insert(host.to_string(), TokenBucket::new(requests_per_second));
+    } else {
+        buckets.remove(host);
+    }
+}
+
+/// The limit of one client, set with
+/// [`ApiClientBuilder::rate_limit`](crate::ApiClientBuilder::rate_limit).
+#[derive(Debug)]
+pub(crate) struct RateLimiter {
+    bucket: Arc<Mutex<TokenBucket>>,
+}
+
+impl RateLimiter {
+    pub(crate) fn new(requests_per_second: f64) -> Self {
+        Self {
+            bucket: Arc::new(Mutex::new(TokenBucket::new(requests_per_second))),
+        }
+    }
+
+    /// Waits until a request is allowed by the limit.
+    pub(crate) async fn acquire(&self) {
+        loop {
+            let wait = self
+                .bucket
+                .lock()
+                .unwrap_or_else(|error| error.into_inner())
+                .take();
+            match wait {
+                Some(wait) => Delay::new(wait).await,
+                None => return,
+            }
+        }
+    }
+}
+
+/// Waits until a request to `host` is allowed by its limit, if it has one.
+pub(crate) async fn acquire(host: &str) {
+    loop {
+        let wait = {
+            let mut buckets = BUCKETS.lock().unwrap_or_else(|error| error.into_inner());
+            match buckets.get_mut(host) {
+                Some(bucket) => bucket.take(),
+                None => None,
+            }
+        };
+        match wait {
+            Some(wait) => Delay::new(wait).await,
+            None => return,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn takes_tokens_until_the_bucket_is_empty() {
+        let mut bucket = TokenBucket::new(2.0);
+        assert_eq!(bucket.take(), None);
+        assert_eq!(bucket.take(), None);
+        let wait = bucket.take().expect("the bucket is empty");
+        assert!(wait > Duration::ZERO && wait <= Duration::from_millis(500));
+    }
+
+    #[test]
+    fn holds_at_least_one_token_below_one_request_per_second() {
+        let mut bucket = TokenBucket::new(0.5);
+        assert_eq!(bucket.take(), None);
+        let wait = bucket.take().expect("the bucket is empty");
+        assert!(wait > Duration::from_secs(1) && wait <= Duration::from_secs(2));
+    }
+
+    #[test]
+    fn client_limits_leave_the_host_limits_alone() {
+        let _client = crate::ApiClient::builder("https://limits.example/")
+            .rate_limit(5.0)
+            .build()
+            .unwrap();
+        let buckets = BUCKETS.lock().unwrap_or_else(|error| error.into_inner());
+        assert!(!buckets.contains_key("limits.example"));
+    }
+}
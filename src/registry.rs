@@ -0,0 +1,365 @@
+//! A common interface over every supported registry.
+
+use crate::auth::Auth;
+use crate::error::SearchError;
+use crate::ApiClient;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use thiserror::Error;
+
+/// A search request understood by every [`Registry`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchQuery {
+    /// The text to search for.
+    pub text: String,
+    /// The maximum number of hits to return.
+    pub limit: usize,
+}
+
+impl SearchQuery {
+    /// Creates a query for `text` returning up to 25 hits.
+    pub fn new(text: &str) -> Self {
+        Self {
+            text: text.to_string(),
+            limit: 25,
+        }
+    }
+
+    /// Sets the maximum number of hits to return.
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = limit;
+        self
+    }
+}
+
+/// A package found by a [`Registry`], normalized so that frontends can
+/// render hits from any source the same way.
+///
+/// Registries fill in whatever their search response provides; fields the
+/// source does not report are left as `None`.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+pub struct SearchHit {
+    pub name: String,
+    pub version: Option<String>,
+    pub description: Option<String>,
+    pub homepage: Option<String>,
+    /// The URL of the source repository.
+    pub repository: Option<String>,
+    /// The total download (or pull, or install) count.
+    pub downloads: Option<u64>,
+    /// The license, preferably as an SPDX identifier.
+    pub license: Option<String>,
+    /// When the package was last published, as an RFC 3339 timestamp.
+    pub updated: Option<String>,
+    /// Whether the package is deprecated or abandoned, or its latest
+    /// version yanked.
+    #[serde(default)]
+    pub deprecated: bool,
+    /// The [`Registry::name`] of the registry the hit came from.
+    pub source: String,
+}
+
+impl SearchHit {
+    /// Returns the shell command that installs or adds this package, e.g.
+    /// `cargo add serde` or `pip install requests`.
+    ///
+    /// The command installs the latest version, except where the tool needs
+    /// one. Sources without a command-line installer, such as Terraform or
+    /// F-Droid, return `None`, as does Debian, whose search finds source
+    /// packages rather than installable ones.
+    ///
+    /// The name and version come from the registry and are pasted into a
+    /// shell, so hits whose name, or version where the command needs one,
+    /// holds anything but letters, digits, and `@/._+:-`, or starts with `-`
+    /// or `.`, return `None`.
+    pub fn install_command(&self) -> Option<String> {
+        let name = &self.name;
+        if !is_shell_word(name) {
+            return None;
+        }
+        let version = || {
+            self.version
+                .as_deref()
+                .filter(|version| is_shell_word(version))
+        };
+        let command = match self.source.as_str() {
+            "crates" => format!("cargo add {}", name),
+            "npm" | "jsdelivr" => format!("npm i {}", name),
+            "pypi" => format!("pip install {}", name),
+            "gems" => format!("gem install {}", name),
+            "composer" => format!("composer require {}", name),
+            "docker" => format!("docker pull {}", name),
+            "quay" => format!("docker pull quay.io/{}", name),
+            "maven" => format!("mvn dependency:get -Dartifact={}:{}", name, version()?),
+            "nuget" => format!("dotnet add package {}", name),
+            "go" => format!("go get {}", name),
+            "brew" => format!("brew install {}", name),
+            "aur" => format!("yay -S {}", name),
+            "hackage" => format!("cabal install {}", name),
+            "pub" => format!("dart pub add {}", name),
+            "conan" => format!("conan install --requires={}/{}", name, version()?),
+            "vcpkg" => format!("vcpkg install {}", name),
+            "cran" => format!("Rscript -e 'install.packages(\"{}\")'", name),
+            "julia" => format!("julia -e 'using Pkg; Pkg.add(\"{}\")'", name),
+            "luarocks" => format!("luarocks install {}", name),
+            "nix" => format!("nix profile install nixpkgs#{}", name),
+            "flathub" => format!("flatpak install flathub {}", name),
+            "snap" => format!("sudo snap install {}", name),
+            "choco" => format!("choco install {}", name),
+            "winget" => format!("winget install --id {}", name),
+            "scoop" => format!("scoop install {}", name),
+            "galaxy" if name.contains('.') => format!("ansible-galaxy collection install {}", name),
+            "galaxy" => format!("ansible-galaxy role install {}", name),
+            "cpan" => format!("cpanm {}", name),
+            "jsr" => format!("deno add jsr:{}", name),
+            "vscode" | "openvsx" => format!("code --install-extension {}", name),
+            "github" => format!("git clone https://github.com/{}.git", name),
+            _ => return None,
+        };
+        Some(command)
+    }
+}
+
+/// Returns whether `text` can be pasted into a shell command line as one
+/// word without quoting, and isn't read as an option.
+fn is_shell_word(text: &str) -> bool {
+    !text.is_empty()
+        && !text.starts_with(['-', '.'])
+        && text
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "@/._+:-".contains(c))
+}
+
+/// Where and how to reach a registry, for the `_with` search functions of
+/// sources that can be self-hosted, such as
+/// [`search_npm_with`](crate::npm::search_npm_with) for Verdaccio, Nexus,
+/// Artifactory or Cloudsmith.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RegistryConfig {
+    /// The URL to use instead of the public registry's, e.g.
+    /// `https://npm.corp.example/`. Request paths are resolved below it, so
+    /// a trailing `/` is optional, but any path it has is kept.
+    pub base_url: Option<String>,
+    /// The credentials to send with every request.
+    pub auth: Option<Auth>,
+}
+
+impl RegistryConfig {
+    /// Creates a config for the public registry without credentials.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the URL to use instead of the public registry's.
+    pub fn base_url(mut self, base_url: &str) -> Self {
+        self.base_url = Some(base_url.to_string());
+        self
+    }
+
+    /// Sets the credentials to send with every request.
+    pub fn auth(mut self, auth: Auth) -> Self {
+        self.auth = Some(auth);
+        self
+    }
+
+    /// Returns a client for the configured registry, falling back to
+    /// `default_url`.
+    pub(crate) fn client(&self, default_url: &str, user_agent: Option<&str>) -> ApiClient {
+        ApiClient::new(self.base_url.as_deref().unwrap_or(default_url), user_agent)
+            .set_auth(self.auth.clone())
+    }
+}
+
+/// An error returned by a [`Registry`], tagged with the registry it came from.
+#[derive(Debug, Error)]
+#[error("{registry}: {error}")]
+pub struct RegistryError {
+    /// The [`Registry::name`] of the registry that failed.
+    pub registry: String,
+    #[source]
+    pub error: SearchError,
+}
+
+impl RegistryError {
+    /// Wraps an error raised while querying `registry`.
+    pub fn new(registry: &str, error: SearchError) -> Self {
+        Self {
+            registry: registry.to_string(),
+            error,
+        }
+    }
+}
+
+/// A package registry that can be searched.
+///
+/// Every source module provides an implementation, and custom registries can
+/// be added by implementing this trait.
+///
+/// Outside WebAssembly, registries are `Send` and `Sync` and their searches
+/// are `Send`, so that they can run on a multi-threaded executor, e.g. with
+/// `tokio::spawn`. In the browser, where futures can't be sent between
+/// threads, neither is required.
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+pub trait Registry: MaybeSend {
+    /// Returns the short name the registry is selected by, e.g. `crates`.
+    fn name(&self) -> &str;
+
+    /// Searches the registry.
+    async fn search(&self, query: &SearchQuery) -> Result<Vec<SearchHit>, RegistryError>;
+}
+
+/// `Send` and `Sync` outside WebAssembly, and implemented by every type in
+/// the browser; see [`Registry`].
+#[cfg(not(target_arch = "wasm32"))]
+pub trait MaybeSend: Send + Sync {}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl<T: Send + Sync + ?Sized> MaybeSend for T {}
+
+/// `Send` and `Sync` outside WebAssembly, and implemented by every type in
+/// the browser; see [`Registry`].
+#[cfg(target_arch = "wasm32")]
+pub trait MaybeSend {}
+
+#[cfg(target_arch = "wasm32")]
+impl<T: ?Sized> MaybeSend for T {}
+
+/// Returns every built-in registry that can be searched without credentials.
+pub fn all_registries() -> Vec<Box<dyn Registry>> {
+    vec![
+        Box::new(crate::crates::CratesIo),
+        Box::new(crate::npm::Npm),
+        Box::new(crate::jsdelivr::JsDelivr),
+        Box::new(crate::docker::DockerHub),
+        Box::new(crate::composer::Packagist),
+        Box::new(crate::pypi::PyPi),
+        Box::new(crate::rubygems::RubyGems),
+        Box::new(crate::maven::MavenCentral),
+        Box::new(crate::nuget::NuGet),
+        Box::new(crate::golang::GoPackages),
+        Box::new(crate::hex::HexPm::default()),
+        Box::new(crate::homebrew::Homebrew),
+        Box::new(crate::aur::Aur::default()),
+        Box::new(crate::apt::Debian),
+        Box::new(crate::hackage::Hackage),
+        Box::new(crate::pubdev::PubDev),
+        Box::new(crate::apple::CocoaPods),
+        Box::new(crate::conan::ConanCenter),
+        Box::new(crate::vcpkg::Vcpkg),
+        Box::new(crate::cran::Cran),
+        Box::new(crate::julia::JuliaHub),
+        Box::new(crate::luarocks::LuaRocks),
+        Box::new(crate::nix::Nixpkgs::default()),
+        Box::new(crate::flathub::Flathub),
+        Box::new(crate::snap::SnapStore),
+        Box::new(crate::windows::Chocolatey),
+        Box::new(crate::windows::Winget),
+        Box::new(crate::windows::Scoop),
+        Box::new(crate::ansible::Galaxy::default()),
+        Box::new(crate::terraform::TerraformRegistry::default()),
+        Box::new(crate::artifacthub::ArtifactHub::default()),
+        Box::new(crate::cpan::MetaCpan),
+        Box::new(crate::clojars::Clojars),
+        Box::new(crate::deno::Jsr),
+        Box::new(crate::deno::DenoLand),
+        Box::new(crate::fdroid::FDroid),
+        Box::new(crate::extensions::VsCodeMarketplace),
+        Box::new(crate::extensions::OpenVsx),
+        Box::new(crate::github::GitHub::default()),
+        Box::new(crate::oci::Quay),
+    ]
+}
+
+/// Returns the built-in registry with the given [`Registry::name`], if it
+/// can be searched without credentials.
+pub fn registry_by_name(name: &str) -> Option<Box<dyn Registry>> {
+    all_registries()
+        .into_iter()
+        .find(|registry| registry.name() == name)
+}
+
+/// Returns a string or number as text, treating empty strings as missing.
+pub(crate) fn text(value: &Value) -> Option<String> {
+    match value {
+        Value::String(text) if !text.is_empty() => Some(text.clone()),
+        Value::Number(number) => Some(number.to_string()),
+        _ => None,
+    }
+}
+
+/// Converts up to `limit` entries of a JSON array into hits.
+pub(crate) fn collect_hits(
+    results: &Value,
+    limit: usize,
+    to_hit: impl Fn(&Value) -> SearchHit,
+) -> Vec<SearchHit> {
+    results
+        .as_array()
+        .into_iter()
+        .flatten()
+        .take(limit)
+        .map(to_hit)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn install(source: &str, name: &str, version: Option<&str>) -> Option<String> {
+        SearchHit {
+            name: name.to_string(),
+            version: version.map(str::to_string),
+            source: source.to_string(),
+            ..SearchHit::default()
+        }
+        .install_command()
+    }
+
+    #[test]
+    fn builds_the_install_command_of_each_tool() {
+        assert_eq!(
+            install("crates", "serde", None).as_deref(),
+            Some("cargo add serde")
+        );
+        assert_eq!(
+            install("npm", "@babel/core", Some("7.0.0")).as_deref(),
+            Some("npm i @babel/core")
+        );
+        assert_eq!(
+            install("galaxy", "community.general", None).as_deref(),
+            Some("ansible-galaxy collection install community.general")
+        );
+        assert_eq!(
+            install("galaxy", "geerlingguy_docker", None).as_deref(),
+            Some("ansible-galaxy role install geerlingguy_docker")
+        );
+        assert_eq!(install("terraform", "hashicorp/aws", None), None);
+    }
+
+    #[test]
+    fn requires_a_version_where_the_tool_needs_one() {
+        assert_eq!(
+            install("maven", "org.slf4j:slf4j-api", Some("2.0.17")).as_deref(),
+            Some("mvn dependency:get -Dartifact=org.slf4j:slf4j-api:2.0.17")
+        );
+        assert_eq!(install("maven", "org.slf4j:slf4j-api", None), None);
+        assert_eq!(install("conan", "zlib", Some("1.3; rm -rf ~")), None);
+    }
+
+    #[test]
+    fn refuses_names_a_shell_would_misread() {
+        for name in [
+            "a b", "a;b", "$(id)", "`id`", "a|b", "a'b", "-rf", ".hidden", "",
+        ] {
+            assert_eq!(install("crates", name, None), None, "{:?}", name);
+        }
+        // A version the command doesn't use doesn't matter.
+        assert_eq!(
+            install("crates", "serde", Some("1.0 && id")).as_deref(),
+            Some("cargo add serde")
+        );
+    }
+}
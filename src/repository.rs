@@ -0,0 +1,107 @@
+//! Finding the packages published from a source repository.
+
+use crate::aggregate::{normalize_repository, AggregateResults};
+use crate::composer::Packagist;
+use crate::crates::CratesIo;
+use crate::error::SearchError;
+use crate::npm::Npm;
+use crate::pypi::{self, PyPi};
+use crate::registry::{text, Registry, RegistryError, SearchHit, SearchQuery};
+use futures::future::{join, join_all};
+use serde_json::Value;
+
+/// How many search hits of each registry are checked.
+const CANDIDATES: usize = 20;
+
+/// How many PyPI hits are checked; each needs a request of its own, since
+/// PyPI's search results don't include the repository.
+const PYPI_CANDIDATES: usize = 5;
+
+/// Finds the packages on crates.io, npm, Packagist, and PyPI whose metadata
+/// points at a repository, e.g. every crate and npm package built from one
+/// GitHub repo.
+///
+/// Each registry is searched for the repository's name, and the hits whose
+/// repository URL is the given one, or a directory inside it, are kept.
+/// Packages named unlike their repository may be missed.
+///
+/// # Arguments
+///
+/// * `repository_url` - The URL of the repository, e.g.
+///   `https://github.com/serde-rs/serde`; `git@` and `.git` forms work too.
+///
+/// # Returns
+///
+/// Returns the packages found, with the errors of registries that failed,
+/// or an error if the URL doesn't name a repository.
+pub async fn find_by_repository(repository_url: &str) -> Result<AggregateResults, SearchError> {
+    let target = normalize_repository(repository_url);
+    let name = match target.split('/').collect::<Vec<_>>()[..] {
+        [_host, _owner, name] if !name.is_empty() => name,
+        _ => {
+            return Err(SearchError::InvalidQuery(format!(
+                "'{}' is not a repository URL such as https://github.com/owner/name",
+                repository_url
+            )))
+        }
+    };
+
+    let query = SearchQuery::new(name).limit(CANDIDATES);
+    let registries: [Box<dyn Registry>; 3] =
+        [Box::new(CratesIo), Box::new(Npm), Box::new(Packagist)];
+    let searches = registries.iter().map(|registry| registry.search(&query));
+    let (searches, pypi) = join(join_all(searches), pypi_hits(name, &target)).await;
+
+    let mut results = AggregateResults::default();
+    for result in searches.into_iter().chain([pypi]) {
+        match result {
+            Ok(hits) => results.hits.extend(
+                hits.into_iter()
+                    .filter(|hit| points_at(hit.repository.as_deref(), &target)),
+            ),
+            Err(error) => results.errors.push(error),
+        }
+    }
+    Ok(results)
+}
+
+/// Returns whether a repository URL is `target`, normalized, or a directory
+/// inside it, as monorepos link their packages.
+fn points_at(url: Option<&str>, target: &str) -> bool {
+    url.map(normalize_repository).is_some_and(|url| {
+        url == target
+            || url
+                .strip_prefix(target)
+                .is_some_and(|rest| rest.starts_with('/'))
+    })
+}
+
+/// Searches PyPI for `name` and fills in the repository of the first hits
+/// from their project metadata.
+async fn pypi_hits(name: &str, target: &str) -> Result<Vec<SearchHit>, RegistryError> {
+    let registry = PyPi;
+    let hits = registry
+        .search(&SearchQuery::new(name).limit(PYPI_CANDIDATES))
+        .await?;
+    let projects = join_all(hits.iter().map(|hit| pypi::get_project(&hit.name))).await;
+    let hits = hits
+        .into_iter()
+        .zip(projects)
+        .filter_map(|(mut hit, project)| {
+            let info = &project.ok()?["info"];
+            let urls = info["project_urls"]
+                .as_object()
+                .into_iter()
+                .flat_map(|urls| urls.values())
+                .chain([&info["home_page"]])
+                .filter_map(Value::as_str);
+            hit.repository = urls
+                .filter(|url| points_at(Some(url), target))
+                .map(str::to_string)
+                .next();
+            hit.license = text(&info["license"]);
+            Some(hit)
+        })
+        .collect();
+    Ok(hits)
+}
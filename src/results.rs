@@ -0,0 +1,56 @@
+//! Typed search results that keep the registry's raw response.
+
+use crate::error::SearchError;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+/// The typed hits of a search along with the response they were parsed from.
+#[derive(Debug, Clone)]
+pub struct SearchResults<T> {
+    hits: Vec<T>,
+    raw: Value,
+}
+
+impl<T: DeserializeOwned> SearchResults<T> {
+    /// Parses the array at the JSON pointer `hits` of `raw`, e.g. `/crates`;
+    /// an empty pointer parses the whole response.
+    pub(crate) fn from_raw(raw: Value, hits: &str) -> Result<Self, SearchError> {
+        let hits = match raw.pointer(hits) {
+            Some(hits) => serde_json::from_value(hits.clone())?,
+            None => Vec::new(),
+        };
+        Ok(Self { hits, raw })
+    }
+}
+
+impl<T> SearchResults<T> {
+    /// Returns the typed hits.
+    pub fn hits(&self) -> &[T] {
+        &self.hits
+    }
+
+    /// Consumes the results, returning the typed hits.
+    pub fn into_hits(self) -> Vec<T> {
+        self.hits
+    }
+
+    /// Returns the response as sent by the registry, including any fields
+    /// the typed hits do not model.
+    pub fn raw(&self) -> &Value {
+        &self.raw
+    }
+
+    /// Consumes the results, returning the raw response.
+    pub fn into_raw(self) -> Value {
+        self.raw
+    }
+}
+
+impl<T> IntoIterator for SearchResults<T> {
+    type Item = T;
+    type IntoIter = std::vec::IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.hits.into_iter()
+    }
+}
@@ -0,0 +1,75 @@
+use crate::error::SearchError;
+use crate::registry::{
+    collect_hits, text, Registry, RegistryConfig, RegistryError, SearchHit, SearchQuery,
+};
+use async_trait::async_trait;
+use serde_json::Value;
+
+/// Searches for gems on RubyGems.
+///
+/// # Arguments
+///
+/// * `query` - The search query (optional).
+/// * `page` - The 1-based result page.
+///
+/// # Returns
+///
+/// Returns the search results as a `serde_json::Value` or an error.
+pub async fn search_gems(query: Option<&str>, page: u32) -> Result<Value, SearchError> {
+    search_gems_with(query, page, &RegistryConfig::new()).await
+}
+
+/// Searches for gems on a RubyGems-compatible server, such as Gemstash or
+/// an Artifactory gems repository.
+///
+/// # Arguments
+///
+/// * `query` - The search query (optional).
+/// * `page` - The 1-based result page.
+/// * `config` - The server's API URL (ending in `api/v1`) and credentials;
+///   rubygems.org is used when no URL is set.
+///
+/// # Returns
+///
+/// Returns the search results as a `serde_json::Value` or an error.
+pub async fn search_gems_with(
+    query: Option<&str>,
+    page: u32,
+    config: &RegistryConfig,
+) -> Result<Value, SearchError> {
+    config
+        .client("https://rubygems.org/api/v1/", None)
+        .set_param("query", query.unwrap_or(""))
+        .set_param("page", &page.to_string())
+        .get("search.json")
+        .await
+}
+
+/// The RubyGems registry.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RubyGems;
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl Registry for RubyGems {
+    fn name(&self) -> &str {
+        "gems"
+    }
+
+    async fn search(&self, query: &SearchQuery) -> Result<Vec<SearchHit>, RegistryError> {
+        let results = search_gems(Some(&query.text), 1)
+            .await
+            .map_err(|error| RegistryError::new(self.name(), error))?;
+        Ok(collect_hits(&results, query.limit, |gem| SearchHit {
+            name: text(&gem["name"]).unwrap_or_default(),
+            version: text(&gem["version"]),
+            description: text(&gem["info"]),
+            homepage: text(&gem["homepage_uri"]).or_else(|| text(&gem["project_uri"])),
+            repository: text(&gem["source_code_uri"]),
+            downloads: gem["downloads"].as_u64(),
+            license: text(&gem["licenses"][0]),
+            source: self.name().to_string(),
+            ..Default::default()
+        }))
+    }
+}
@@ -0,0 +1,50 @@
+use crate::error::SearchError;
+use crate::registry::{collect_hits, text, Registry, RegistryError, SearchHit, SearchQuery};
+use crate::ApiClient;
+use async_trait::async_trait;
+use serde_json::Value;
+
+/// Searches for snaps on the Snap Store.
+///
+/// # Arguments
+///
+/// * `query` - The search query (optional).
+///
+/// # Returns
+///
+/// Returns the search results as a `serde_json::Value` or an error.
+pub async fn search_snap(query: Option<&str>) -> Result<Value, SearchError> {
+    ApiClient::new("https://api.snapcraft.io/v2/snaps/", None)
+        .set_param("q", query.unwrap_or(""))
+        .set_header("Snap-Device-Series", "16")
+        .get("find")
+        .await
+}
+
+/// The Snap Store.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SnapStore;
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl Registry for SnapStore {
+    fn name(&self) -> &str {
+        "snap"
+    }
+
+    async fn search(&self, query: &SearchQuery) -> Result<Vec<SearchHit>, RegistryError> {
+        let results = search_snap(Some(&query.text))
+            .await
+            .map_err(|error| RegistryError::new(self.name(), error))?;
+        Ok(collect_hits(&results["results"], query.limit, |result| {
+            SearchHit {
+                name: text(&result["name"]).unwrap_or_default(),
+                version: text(&result["revision"]["version"]),
+                description: text(&result["snap"]["summary"]),
+                homepage: text(&result["snap"]["website"]),
+                source: self.name().to_string(),
+                ..Default::default()
+            }
+        }))
+    }
+}
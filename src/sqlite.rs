@@ -0,0 +1,115 @@
+//! Saving search hits to a local SQLite database, for querying them offline
+//! and diffing them against earlier searches.
+//!
+//! Hits are kept in a `hits` table with one row per source, name, and
+//! version; saving a hit again updates its row and its `searched_at`
+//! timestamp, so a new release shows up as a new row.
+
+use crate::error::SearchError;
+use crate::registry::SearchHit;
+use rusqlite::{params, Connection};
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The schema of the `hits` table. Versions are stored as `''` when the
+/// registry reports none, since `NULL`s never conflict in a primary key.
+const SCHEMA: &str = "CREATE TABLE IF NOT EXISTS hits (
+    source TEXT NOT NULL,
+    name TEXT NOT NULL,
+    version TEXT NOT NULL DEFAULT '',
+    description TEXT,
+    homepage TEXT,
+    repository TEXT,
+    downloads INTEGER,
+    license TEXT,
+    searched_at INTEGER NOT NULL,
+    PRIMARY KEY (source, name, version)
+)";
+
+/// A SQLite database of search hits.
+#[derive(Debug)]
+pub struct HitDatabase {
+    connection: Connection,
+}
+
+impl HitDatabase {
+    /// Opens the database at `path`, creating the file and the `hits` table
+    /// if they don't exist.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The database file.
+    ///
+    /// # Returns
+    ///
+    /// Returns the database or an error.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, SearchError> {
+        let connection = Connection::open(path)?;
+        connection.execute(SCHEMA, [])?;
+        Ok(Self { connection })
+    }
+
+    /// Saves hits, replacing the rows of hits saved before and stamping
+    /// them all with the current time.
+    ///
+    /// # Arguments
+    ///
+    /// * `hits` - The hits to save.
+    ///
+    /// # Returns
+    ///
+    /// Returns the number of hits saved or an error.
+    pub fn save(&mut self, hits: &[SearchHit]) -> Result<usize, SearchError> {
+        let searched_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_secs() as i64);
+        let transaction = self.connection.transaction()?;
+        {
+            let mut insert = transaction.prepare(
+                "INSERT INTO hits (source, name, version, description, homepage, repository, downloads, license, searched_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
+                 ON CONFLICT (source, name, version) DO UPDATE SET
+                     description = excluded.description,
+                     homepage = excluded.homepage,
+                     repository = excluded.repository,
+                     downloads = excluded.downloads,
+                     license = excluded.license,
+                     searched_at = excluded.searched_at",
+            )?;
+            for hit in hits {
+                insert.execute(params![
+                    hit.source,
+                    hit.name,
+                    hit.version.as_deref().unwrap_or_default(),
+                    hit.description,
+                    hit.homepage,
+                    hit.repository,
+                    hit.downloads.map(|downloads| downloads as i64),
+                    hit.license,
+                    searched_at,
+                ])?;
+            }
+        }
+        transaction.commit()?;
+        Ok(hits.len())
+    }
+
+    /// Returns the underlying connection, for querying the saved hits.
+    pub fn connection(&self) -> &Connection {
+        &self.connection
+    }
+}
+
+/// Saves hits to the SQLite database at `path`; see [`HitDatabase`].
+///
+/// # Arguments
+///
+/// * `path` - The database file; it is created if it doesn't exist.
+/// * `hits` - The hits to save.
+///
+/// # Returns
+///
+/// Returns the number of hits saved or an error.
+pub fn save_hits(path: impl AsRef<Path>, hits: &[SearchHit]) -> Result<usize, SearchError> {
+    HitDatabase::open(path)?.save(hits)
+}
@@ -0,0 +1,72 @@
+use crate::error::SearchError;
+use crate::registry::{collect_hits, text, Registry, RegistryError, SearchHit, SearchQuery};
+use crate::ApiClient;
+use async_trait::async_trait;
+use serde_json::Value;
+
+/// The kinds of artifacts published to the Terraform Registry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TerraformKind {
+    #[default]
+    Module,
+    Provider,
+}
+
+/// Searches for modules or providers on the Terraform Registry.
+///
+/// # Arguments
+///
+/// * `query` - The search query (optional).
+/// * `kind` - Whether to search modules or providers.
+///
+/// # Returns
+///
+/// Returns the search results as a `serde_json::Value` or an error.
+pub async fn search_terraform(
+    query: Option<&str>,
+    kind: TerraformKind,
+) -> Result<Value, SearchError> {
+    let client = ApiClient::new("https://registry.terraform.io/v1/", None)
+        .set_param("q", query.unwrap_or(""))
+        .set_param("limit", "25");
+
+    match kind {
+        TerraformKind::Module => client.get("modules/search").await,
+        TerraformKind::Provider => client.get("providers").await,
+    }
+}
+
+/// The Terraform Registry.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TerraformRegistry {
+    /// Whether to search modules or providers.
+    pub kind: TerraformKind,
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl Registry for TerraformRegistry {
+    fn name(&self) -> &str {
+        "terraform"
+    }
+
+    async fn search(&self, query: &SearchQuery) -> Result<Vec<SearchHit>, RegistryError> {
+        let results = search_terraform(Some(&query.text), self.kind)
+            .await
+            .map_err(|error| RegistryError::new(self.name(), error))?;
+        let items = match self.kind {
+            TerraformKind::Module => &results["modules"],
+            TerraformKind::Provider => &results["providers"],
+        };
+        Ok(collect_hits(items, query.limit, |item| SearchHit {
+            name: text(&item["id"]).unwrap_or_default(),
+            version: text(&item["version"]),
+            description: text(&item["description"]),
+            homepage: text(&item["source"]),
+            repository: text(&item["source"]),
+            downloads: item["downloads"].as_u64(),
+            source: self.name().to_string(),
+            ..Default::default()
+        }))
+    }
+}
@@ -0,0 +1,350 @@
+//! The interactive `tui` subcommand of the `search` binary.
+
+use crate::{versions, PackageArgs};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Flex, Layout, Rect};
+use ratatui::style::{Style, Stylize};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Clear, List, ListItem, ListState, Paragraph};
+use ratatui::{DefaultTerminal, Frame};
+use search_libraries::aggregate::{search_all, RankingStrategy};
+use search_libraries::registry::{
+    all_registries, registry_by_name, Registry, SearchHit, SearchQuery,
+};
+use search_libraries::SearchError;
+use serde_json::Value;
+use std::error::Error;
+use std::io::{self, Write};
+use std::process::{Command, Stdio};
+
+/// The source that searches every registry at once.
+const ALL: &str = "all";
+
+/// Whether keys edit the query or move through the results.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Mode {
+    Editing,
+    Browsing,
+}
+
+/// The state of the browser between key presses.
+struct App {
+    mode: Mode,
+    query: String,
+    sources: Vec<String>,
+    source: usize,
+    limit: usize,
+    hits: Vec<SearchHit>,
+    list: ListState,
+    /// The package and versions shown in the versions popup, if open.
+    versions: Option<(String, Vec<String>)>,
+    versions_list: ListState,
+    status: String,
+}
+
+impl App {
+    fn new(limit: usize) -> Self {
+        let mut sources = vec![ALL.to_string()];
+        sources.extend(
+            all_registries()
+                .iter()
+                .map(|registry| registry.name().to_string()),
+        );
+        Self {
+            mode: Mode::Editing,
+            query: String::new(),
+            sources,
+            source: 0,
+            limit,
+            hits: Vec::new(),
+            list: ListState::default(),
+            versions: None,
+            versions_list: ListState::default(),
+            status: "Type a query and press Enter; Tab changes the source.".to_string(),
+        }
+    }
+
+    fn source(&self) -> &str {
+        &self.sources[self.source]
+    }
+
+    fn selected(&self) -> Option<&SearchHit> {
+        self.hits.get(self.list.selected()?)
+    }
+
+    /// Searches the selected source for the query.
+    async fn search(&mut self) {
+        let query = self.query.trim();
+        if query.is_empty() {
+            return;
+        }
+        let result = if self.source() == ALL {
+            let results = search_all(query, &[], self.limit).await;
+            let failed = results.errors.len();
+            let results = results.ranked(query, RankingStrategy::default());
+            Ok((results.hits, failed))
+        } else {
+            match registry_by_name(self.source()) {
+                Some(registry) => registry
+                    .search(&SearchQuery::new(query).limit(self.limit))
+                    .await
+                    .map(|hits| (hits, 0))
+                    .map_err(|error| error.error),
+                None => Err(SearchError::NotFound),
+            }
+        };
+
+        match result {
+            Ok((hits, failed)) => {
+                self.status = match failed {
+                    0 => format!("{} results.", hits.len()),
+                    failed => format!("{} results; {} registries failed.", hits.len(), failed),
+                };
+                self.list.select((!hits.is_empty()).then_some(0));
+                self.hits = hits;
+                self.mode = Mode::Browsing;
+            }
+            Err(error) => self.status = format!("Error: {}", error),
+        }
+    }
+
+    /// Opens the versions popup for the selected hit.
+    async fn show_versions(&mut self) {
+        let Some(hit) = self.selected() else {
+            return;
+        };
+        let name = hit.name.clone();
+        let args = PackageArgs {
+            source: hit.source.clone(),
+            name: name.clone(),
+        };
+        match versions(&args).await {
+            Ok(versions) => {
+                let versions = version_names(&versions);
+                self.versions_list
+                    .select((!versions.is_empty()).then_some(0));
+                self.versions = Some((name, versions));
+            }
+            Err(error) => self.status = format!("Error: {}", error),
+        }
+    }
+
+    /// Handles a key press, returning `false` once the browser should close.
+    async fn handle(&mut self, key: KeyCode) -> bool {
+        if self.versions.is_some() {
+            match key {
+                KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('v') => self.versions = None,
+                KeyCode::Down | KeyCode::Char('j') => self.versions_list.select_next(),
+                KeyCode::Up | KeyCode::Char('k') => self.versions_list.select_previous(),
+                _ => {}
+            }
+            return true;
+        }
+
+        match (self.mode, key) {
+            (_, KeyCode::Tab) => {
+                self.source = (self.source + 1) % self.sources.len();
+            }
+            (_, KeyCode::BackTab) => {
+                self.source = (self.source + self.sources.len() - 1) % self.sources.len();
+            }
+            (Mode::Editing, KeyCode::Enter) => self.search().await,
+            (Mode::Editing, KeyCode::Char(c)) => self.query.push(c),
+            (Mode::Editing, KeyCode::Backspace) => {
+                self.query.pop();
+            }
+            (Mode::Editing, KeyCode::Esc) if self.hits.is_empty() => return false,
+            (Mode::Editing, KeyCode::Esc) => self.mode = Mode::Browsing,
+            (Mode::Browsing, KeyCode::Down | KeyCode::Char('j')) => self.list.select_next(),
+            (Mode::Browsing, KeyCode::Up | KeyCode::Char('k')) => self.list.select_previous(),
+            (Mode::Browsing, KeyCode::Char('/') | KeyCode::Char('i')) => self.mode = Mode::Editing,
+            (Mode::Browsing, KeyCode::Enter) => {
+                let url = self
+                    .selected()
+                    .and_then(|hit| hit.homepage.clone().or_else(|| hit.repository.clone()));
+                self.status = match url {
+                    Some(url) => match open_url(&url) {
+                        Ok(()) => format!("Opened {}", url),
+                        Err(error) => format!("Could not open {}: {}", url, error),
+                    },
+                    None => "This package has no homepage or repository.".to_string(),
+                };
+            }
+            (Mode::Browsing, KeyCode::Char('v')) => self.show_versions().await,
+            (Mode::Browsing, KeyCode::Char('y')) => {
+                if let Some(text) = self.selected().map(copy_text) {
+                    self.status = match copy(&text) {
+                        Ok(()) => format!("Copied `{}`", text),
+                        Err(error) => format!("Could not copy: {}", error),
+                    };
+                }
+            }
+            (Mode::Browsing, KeyCode::Esc | KeyCode::Char('q')) => return false,
+            _ => {}
+        }
+        true
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [input, results, help] = Layout::vertical([
+            Constraint::Length(3),
+            Constraint::Min(0),
+            Constraint::Length(1),
+        ])
+        .areas(frame.area());
+
+        let title = format!(" Search {} (Tab to change) ", self.source());
+        let mut query = Paragraph::new(self.query.as_str()).block(Block::bordered().title(title));
+        if self.mode == Mode::Editing {
+            query = query.yellow();
+            frame.set_cursor_position((
+                input.x + 1 + self.query.chars().count() as u16,
+                input.y + 1,
+            ));
+        }
+        frame.render_widget(query, input);
+
+        let items: Vec<ListItem> = self
+            .hits
+            .iter()
+            .map(|hit| {
+                let mut spans = vec![
+                    Span::from(hit.name.clone()).bold(),
+                    Span::from(format!(" {}", hit.version.as_deref().unwrap_or_default())).green(),
+                    Span::from(format!(" [{}]", hit.source)).dark_gray(),
+                ];
+                if let Some(description) = hit.description.as_deref().and_then(|d| d.lines().next())
+                {
+                    spans.push(Span::from(format!("  {}", description.trim())));
+                }
+                ListItem::new(Line::from(spans))
+            })
+            .collect();
+        let list = List::new(items)
+            .block(Block::bordered().title(" Results "))
+            .highlight_style(Style::new().reversed())
+            .highlight_symbol("> ");
+        frame.render_stateful_widget(list, results, &mut self.list);
+
+        let keys = match self.mode {
+            Mode::Editing => "Enter search · Tab source · Esc results",
+            Mode::Browsing => "Enter open · v versions · y copy · / edit · q quit",
+        };
+        frame.render_widget(
+            Paragraph::new(format!("{}  |  {}", self.status, keys)).dark_gray(),
+            help,
+        );
+
+        if let Some((name, versions)) = &self.versions {
+            let area = popup(frame.area(), 40, 20);
+            let items: Vec<ListItem> = versions.iter().map(|v| ListItem::new(v.as_str())).collect();
+            let list = List::new(items)
+                .block(Block::bordered().title(format!(" {} versions ", name)))
+                .highlight_style(Style::new().reversed());
+            frame.render_widget(Clear, area);
+            frame.render_stateful_widget(list, area, &mut self.versions_list);
+        }
+    }
+}
+
+/// Runs the browser until the user quits.
+pub async fn run(limit: usize) -> Result<(), Box<dyn Error>> {
+    let mut terminal = ratatui::init();
+    let result = event_loop(&mut terminal, App::new(limit)).await;
+    ratatui::restore();
+    result
+}
+
+async fn event_loop(terminal: &mut DefaultTerminal, mut app: App) -> Result<(), Box<dyn Error>> {
+    loop {
+        terminal.draw(|frame| app.draw(frame))?;
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+        if key.code == KeyCode::Enter && app.mode == Mode::Editing {
+            // Show that a search is running before blocking on it.
+            app.status = format!("Searching {}…", app.source());
+            terminal.draw(|frame| app.draw(frame))?;
+        }
+        if !app.handle(key.code).await {
+            return Ok(());
+        }
+    }
+}
+
+/// Returns a rectangle of `width` by `height` cells centered in `area`.
+fn popup(area: Rect, width: u16, height: u16) -> Rect {
+    let [area] = Layout::horizontal([Constraint::Length(width)])
+        .flex(Flex::Center)
+        .areas(area);
+    let [area] = Layout::vertical([Constraint::Length(height)])
+        .flex(Flex::Center)
+        .areas(area);
+    area
+}
+
+/// Returns the version numbers in the output of the `versions` subcommand,
+/// whose shape differs by source.
+fn version_names(versions: &Value) -> Vec<String> {
+    let entries = versions["results"].as_array().or(versions.as_array());
+    entries
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| match entry {
+            Value::String(version) => Some(version.clone()),
+            entry => ["num", "version", "name"]
+                .iter()
+                .find_map(|key| entry[key].as_str())
+                .map(str::to_string),
+        })
+        .collect()
+}
+
+/// Returns the text `y` copies for a hit: its install command, or else its
+/// name.
+fn copy_text(hit: &SearchHit) -> String {
+    hit.install_command().unwrap_or_else(|| hit.name.clone())
+}
+
+/// Copies `text` to the clipboard with the OSC 52 escape sequence, which
+/// most terminals support, including over SSH.
+fn copy(text: &str) -> io::Result<()> {
+    let mut stdout = io::stdout();
+    write!(stdout, "\x1b]52;c;{}\x07", STANDARD.encode(text))?;
+    stdout.flush()
+}
+
+/// Opens `url` in the default browser.
+///
+/// The URL comes from the registry, so only `http(s)` URLs are opened, and
+/// never through a shell, which would interpret characters such as `&` in
+/// them.
+fn open_url(url: &str) -> io::Result<()> {
+    let lowercase = url.to_ascii_lowercase();
+    if !(lowercase.starts_with("https://") || lowercase.starts_with("http://")) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "only http(s) URLs are opened",
+        ));
+    }
+    let mut command = if cfg!(target_os = "macos") {
+        Command::new("open")
+    } else if cfg!(windows) {
+        let mut command = Command::new("rundll32");
+        command.arg("url.dll,FileProtocolHandler");
+        command
+    } else {
+        Command::new("xdg-open")
+    };
+    command
+        .arg(url)
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+    Ok(())
+}
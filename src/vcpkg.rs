@@ -0,0 +1,75 @@
+use crate::cache::cached_index;
+use crate::error::SearchError;
+use crate::matching::match_score;
+use crate::registry::{collect_hits, text, Registry, RegistryError, SearchHit, SearchQuery};
+use async_trait::async_trait;
+use serde_json::{json, Value};
+use std::time::Duration;
+
+const BASELINE_INDEX: &str =
+    "https://raw.githubusercontent.com/microsoft/vcpkg/master/versions/baseline.json";
+const INDEX_MAX_AGE: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Searches for ports in the vcpkg registry.
+///
+/// vcpkg has no search endpoint, so the registry baseline is downloaded from
+/// GitHub (and cached for a day) and port names are matched locally.
+///
+/// # Arguments
+///
+/// * `query` - The search query (optional).
+///
+/// # Returns
+///
+/// Returns the matching ports as a `serde_json::Value` array or an error.
+pub async fn search_vcpkg(query: Option<&str>) -> Result<Value, SearchError> {
+    let query = query.unwrap_or("");
+    let index = cached_index("vcpkg-baseline.json", BASELINE_INDEX, INDEX_MAX_AGE).await?;
+
+    let mut hits: Vec<(u8, Value)> = index["default"]
+        .as_object()
+        .into_iter()
+        .flatten()
+        .filter_map(|(name, port)| {
+            let score = match_score(query, name, "")?;
+            Some((
+                score,
+                json!({
+                    "name": name,
+                    "version": port["baseline"],
+                    "port_version": port["port-version"],
+                }),
+            ))
+        })
+        .collect();
+
+    hits.sort_by_key(|(score, _)| *score);
+    Ok(hits.into_iter().map(|(_, hit)| hit).collect())
+}
+
+/// The vcpkg port registry.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Vcpkg;
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl Registry for Vcpkg {
+    fn name(&self) -> &str {
+        "vcpkg"
+    }
+
+    async fn search(&self, query: &SearchQuery) -> Result<Vec<SearchHit>, RegistryError> {
+        let results = search_vcpkg(Some(&query.text))
+            .await
+            .map_err(|error| RegistryError::new(self.name(), error))?;
+        Ok(collect_hits(&results, query.limit, |port| SearchHit {
+            name: text(&port["name"]).unwrap_or_default(),
+            version: text(&port["version"]),
+            description: None,
+            homepage: text(&port["name"])
+                .map(|name| format!("https://vcpkg.io/en/package/{}", name)),
+            source: self.name().to_string(),
+            ..Default::default()
+        }))
+    }
+}
@@ -0,0 +1,311 @@
+//! Recording live responses to fixture files and replaying them offline.
+//!
+//! With [`VcrMode::Record`], every response a client receives is saved to a
+//! JSON fixture named after its request. With [`VcrMode::Replay`], requests
+//! are answered from those fixtures without touching the network, so tests
+//! built on real registry responses can run offline in CI.
+//!
+//! The mode is set per client with
+//! [`ApiClientBuilder::vcr`](crate::ApiClientBuilder::vcr), or for every
+//! client with the `SEARCH_LIBRARIES_VCR` environment variable (`record` or
+//! `replay`) and `SEARCH_LIBRARIES_FIXTURES` (defaulting to `tests/fixtures`).
+//!
+//! Fixtures are safe to commit: credentials in query parameters and request
+//! bodies, such as `api_key` or `client_secret`, are replaced with
+//! `REDACTED` before the request is saved or hashed, as are the values of
+//! request headers that can carry credentials, such as `Authorization` and
+//! `Cookie`. Such response headers, e.g. `Set-Cookie`, are left out.
+//!
+//! Fixtures are named after the first 128 bits of the SHA-256 hash of
+//! their request, written as 32 hex digits.
+
+use crate::client::{is_credential, read_text};
+use crate::error::SearchError;
+use reqwest::header::HeaderMap;
+use reqwest::{Method, Response};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+/// Headers that carry credentials or session state.
+const SENSITIVE_HEADERS: [&str; 4] = [
+    "authorization",
+    "cookie",
+    "proxy-authorization",
+    "set-cookie",
+];
+
+/// What credentials are replaced with in fixtures.
+const REDACTED: &str = "REDACTED";
+
+/// Whether responses are recorded or replayed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VcrMode {
+    /// Send requests and save every response as a fixture.
+    Record,
+    /// Answer requests from saved fixtures; a missing fixture is an error.
+    Replay,
+}
+
+/// Where fixtures are kept and what to do with them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Vcr {
+    mode: VcrMode,
+    dir: PathBuf,
+}
+
+impl Vcr {
+    /// Records or replays fixtures in `dir`.
+    pub fn new(mode: VcrMode, dir: impl Into<PathBuf>) -> Self {
+        Self {
+            mode,
+            dir: dir.into(),
+        }
+    }
+
+    /// Returns the mode set by `SEARCH_LIBRARIES_VCR`, if any.
+    pub fn from_env() -> Option<Self> {
+        let mode = match std::env::var("SEARCH_LIBRARIES_VCR").ok()?.as_str() {
+            "record" => VcrMode::Record,
+            "replay" => VcrMode::Replay,
+            _ => return None,
+        };
+        let dir = std::env::var_os("SEARCH_LIBRARIES_FIXTURES")
+            .map(PathBuf::from)
+            .unwrap_or_else(|| PathBuf::from("tests/fixtures"));
+        Some(Self::new(mode, dir))
+    }
+
+    /// Returns the mode.
+    pub fn mode(&self) -> VcrMode {
+        self.mode
+    }
+
+    /// Answers a request with its saved fixture.
+    pub(crate) fn replay(
+        &self,
+        method: &Method,
+        url: &str,
+        body: Option<&Value>,
+    ) -> Result<Response, SearchError> {
+        let url = &redact(url);
+        let body = body.map(redact_body);
+        let path = self.fixture_path(method, url, body.as_ref());
+        let fixture: Value = match fs::read_to_string(&path) {
+            Ok(contents) => serde_json::from_str(&contents)?,
+            Err(error) => {
+                let message = format!("no fixture for {} {} at {}", method, url, path.display());
+                return Err(io::Error::new(error.kind(), message).into());
+            }
+        };
+
+        let mut response =
+            http::Response::builder().status(fixture["status"].as_u64().unwrap_or(200) as u16);
+        for (name, value) in fixture["headers"].as_object().into_iter().flatten() {
+            if let Some(value) = value.as_str() {
+                response = response.header(name.as_str(), value);
+            }
+        }
+        let body = fixture["body"].as_str().unwrap_or_default().to_string();
+        let response = response
+            .body(body)
+            .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
+        Ok(Response::from(response))
+    }
+
+    /// Saves a live response as the fixture of its request, sent with
+    /// `request_headers`, and returns an equivalent response for the caller
+    /// to read.
+    ///
+    /// The body is read up to `limit` bytes, as the client would have.
+    pub(crate) async fn record(
+        &self,
+        method: &Method,
+        url: &str,
+        body: Option<&Value>,
+        request_headers: &HeaderMap,
+        response: Response,
+        limit: usize,
+    ) -> Result<Response, SearchError> {
+        let url = &redact(url);
+        let body = body.map(redact_body);
+        let status = response.status();
+        let headers = response.headers().clone();
+        let contents = read_text(response, limit).await?;
+
+        let fixture = json!({
+            "method": method.as_str(),
+            "url": url,
+            "request_headers": request_headers
+                .iter()
+                .filter_map(|(name, value)| {
+                    let value = if is_sensitive_header(name.as_str()) {
+                        REDACTED
+                    } else {
+                        value.to_str().ok()?
+                    };
+                    Some((name.to_string(), Value::from(value)))
+                })
+                .collect::<serde_json::Map<_, _>>(),
+            "request_body": body,
+            "status": status.as_u16(),
+            "headers": headers
+                .iter()
+                .filter(|(name, _)| !is_sensitive_header(name.as_str()))
+                .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.to_string())))
+                .collect::<serde_json::Map<_, _>>(),
+            "body": contents,
+        });
+        let path = self.fixture_path(method, url, body.as_ref());
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(&path, serde_json::to_string_pretty(&fixture)?)?;
+
+        let mut replayed = http::Response::builder().status(status);
+        if let Some(replayed_headers) = replayed.headers_mut() {
+            *replayed_headers = headers;
+        }
+        let replayed = replayed
+            .body(contents)
+            .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
+        Ok(Response::from(replayed))
+    }
+
+    /// Returns the fixture file of a request, named after the first 128
+    /// bits of the SHA-256 hash of its method, URL with query parameters,
+    /// and body, one per line, which stays the same across Rust releases.
+    fn fixture_path(&self, method: &Method, url: &str, body: Option<&Value>) -> PathBuf {
+        let body = body.map(Value::to_string).unwrap_or_default();
+        let digest = Sha256::digest(format!("{}\n{}\n{}", method, url, body));
+        let name: String = digest[..16]
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect();
+        self.dir.join(format!("{}.json", name))
+    }
+}
+
+/// Returns whether a header, named in lowercase, can carry credentials,
+/// such as `Authorization` or `X-Api-Key`.
+fn is_sensitive_header(name: &str) -> bool {
+    SENSITIVE_HEADERS.contains(&name)
+        || ["auth", "key", "secret", "token"]
+            .iter()
+            .any(|word| name.contains(word))
+}
+
+/// Replaces the values of fields that carry credentials, such as
+/// `password`, with `REDACTED`, in a JSON request body at any depth.
+fn redact_body(body: &Value) -> Value {
+    match body {
+        Value::Object(fields) => Value::Object(
+            fields
+                .iter()
+                .map(|(name, value)| {
+                    let value = if is_credential(name) {
+                        Value::from(REDACTED)
+                    } else {
+                        redact_body(value)
+                    };
+                    (name.clone(), value)
+                })
+                .collect(),
+        ),
+        Value::Array(values) => Value::Array(values.iter().map(redact_body).collect()),
+        value => value.clone(),
+    }
+}
+
+/// Replaces the values of query parameters that carry credentials, such as
+/// `api_key` or `Client_Secret`, with `REDACTED`.
+fn redact(url: &str) -> String {
+    let Some((base, query)) = url.split_once('?') else {
+        return url.to_string();
+    };
+    let params: Vec<String> = query
+        .split('&')
+        .map(|param| match param.split_once('=') {
+            Some((key, _)) if is_credential(key) => format!("{}={}", key, REDACTED),
+            _ => param.to_string(),
+        })
+        .collect();
+    format!("{}?{}", base, params.join("&"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn redacts_credentials_in_queries_ignoring_case() {
+        assert_eq!(
+            redact("https://libraries.io/api/search?API_KEY=abc&q=serde&Client-Secret=x"),
+            "https://libraries.io/api/search?API_KEY=REDACTED&q=serde&Client-Secret=REDACTED"
+        );
+        assert_eq!(
+            redact("https://example.com/?key=abc&npm_token=def&keyword=serde"),
+            "https://example.com/?key=REDACTED&npm_token=REDACTED&keyword=serde"
+        );
+        assert_eq!(
+            redact("https://crates.io/api/v1/crates"),
+            "https://crates.io/api/v1/crates"
+        );
+    }
+
+    #[test]
+    fn redacts_credentials_in_request_bodies() {
+        let body = json!({
+            "query": "serde",
+            "auth": { "password": "hunter2" },
+            "clients": [{ "client_secret": "s3cr3t", "name": "app" }],
+        });
+        assert_eq!(
+            redact_body(&body),
+            json!({
+                "query": "serde",
+                "auth": "REDACTED",
+                "clients": [{ "client_secret": "REDACTED", "name": "app" }],
+            })
+        );
+    }
+
+    #[test]
+    fn treats_credential_headers_as_sensitive() {
+        for name in [
+            "authorization",
+            "cookie",
+            "set-cookie",
+            "x-api-key",
+            "x-auth-token",
+        ] {
+            assert!(is_sensitive_header(name), "{}", name);
+        }
+        for name in ["accept", "content-type", "etag", "user-agent"] {
+            assert!(!is_sensitive_header(name), "{}", name);
+        }
+    }
+
+    #[test]
+    fn names_fixtures_after_a_stable_hash() {
+        let vcr = Vcr::new(VcrMode::Replay, "fixtures");
+        let path = vcr.fixture_path(
+            &Method::GET,
+            "https://crates.io/api/v1/crates?q=serde",
+            None,
+        );
+        let name = path.file_stem().unwrap().to_str().unwrap();
+        assert_eq!(name.len(), 32);
+        assert!(name.chars().all(|c| c.is_ascii_hexdigit()));
+        assert_eq!(
+            path,
+            vcr.fixture_path(
+                &Method::GET,
+                "https://crates.io/api/v1/crates?q=serde",
+                None
+            )
+        );
+    }
+}
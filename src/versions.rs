@@ -0,0 +1,400 @@
+//! Resolving version requirements, such as `^1.2`, against the versions a
+//! registry has published.
+//!
+//! Requirements are read the way the registry's own tools read them; see
+//! [`RequirementSyntax`]. Alternatives can be joined with `||`, and
+//! comparators separated by spaces instead of commas, in every syntax.
+
+use crate::error::SearchError;
+use crate::{composer, crates, npm};
+use semver::{Version, VersionReq};
+use serde::Serialize;
+use serde_json::Value;
+
+/// How a registry's tools read version requirements.
+///
+/// The syntaxes agree on `^`, `<`, `>=`, and the like, but differ on the
+/// rest:
+///
+/// | Requirement     | Cargo           | npm             | Composer        |
+/// |-----------------|-----------------|-----------------|-----------------|
+/// | `1.2.3`         | `^1.2.3`        | `=1.2.3`        | `=1.2.3`        |
+/// | `1.2`           | `^1.2`          | `1.2.*`         | `=1.2.0`        |
+/// | `~1.2`          | `>=1.2, <1.3`   | `>=1.2, <1.3`   | `>=1.2, <2.0`   |
+/// | `1.2 - 2.0`     | invalid         | `>=1.2, <2.1`   | `>=1.2, <2.1`   |
+///
+/// npm and Composer also accept a leading `v` on versions, and Composer a
+/// stability flag such as `@dev`, which is ignored.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RequirementSyntax {
+    #[default]
+    Cargo,
+    Npm,
+    Composer,
+}
+
+impl RequirementSyntax {
+    /// Returns the syntax of a registry: npm's for `npm`, Composer's for
+    /// `composer`, and Cargo's for any other.
+    pub fn of(source: &str) -> Self {
+        match source {
+            "npm" => Self::Npm,
+            "composer" => Self::Composer,
+            _ => Self::Cargo,
+        }
+    }
+
+    /// Rewrites one comparator in Cargo's syntax, as one or two
+    /// comparators.
+    fn comparator(self, comparator: &str) -> Vec<String> {
+        if self == Self::Cargo {
+            return vec![comparator.to_string()];
+        }
+        let comparator = match self {
+            Self::Composer => comparator.split('@').next().unwrap_or_default(),
+            _ => comparator,
+        };
+        let operator_len = comparator.len() - comparator.trim_start_matches(OPERATORS).len();
+        let (operator, version) = comparator.split_at(operator_len);
+        let version = version
+            .trim_start_matches(['v', 'V'])
+            .split('.')
+            .map(|part| match part {
+                "" | "x" | "X" => "*",
+                part => part,
+            })
+            .collect::<Vec<_>>()
+            .join(".");
+        if version.contains('*') {
+            return vec![format!("{}{}", operator, version)];
+        }
+        match (self, operator) {
+            (Self::Composer, "") => vec![format!("={}", pad_version(&version))],
+            (_, "") => vec![format!("={}", version)],
+            (Self::Composer, "~") if version.split('.').count() == 2 => {
+                let major = version.split('.').next().unwrap_or_default();
+                match major.parse::<u64>() {
+                    Ok(major) => vec![format!(">={}", version), format!("<{}", major + 1)],
+                    Err(_) => vec![format!("~{}", version)],
+                }
+            }
+            (_, operator) => vec![format!("{}{}", operator, version)],
+        }
+    }
+}
+
+/// The characters comparison operators are made of.
+const OPERATORS: [char; 5] = ['<', '>', '=', '~', '^'];
+
+/// Fills in missing minor and patch numbers of a version with zeros, so
+/// `1.2` becomes `1.2.0`.
+fn pad_version(version: &str) -> String {
+    let core_len = version.find(['-', '+']).unwrap_or(version.len());
+    let (core, rest) = version.split_at(core_len);
+    let missing = 3usize.saturating_sub(core.split('.').count());
+    format!("{}{}{}", core, ".0".repeat(missing), rest)
+}
+
+/// A version requirement: a set of alternatives, any of which may match.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VersionRequirement {
+    alternatives: Vec<VersionReq>,
+}
+
+impl VersionRequirement {
+    /// Parses a requirement in Cargo's syntax, such as `^1.2`, `>=1.0 <2.0`,
+    /// or `^1 || ^2`.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The requirement.
+    ///
+    /// # Returns
+    ///
+    /// Returns the requirement or an error if it can't be parsed.
+    pub fn parse(text: &str) -> Result<Self, SearchError> {
+        Self::parse_with(text, RequirementSyntax::Cargo)
+    }
+
+    /// Parses a requirement in the syntax of a registry, e.g. `1.2.3 - 2`
+    /// for npm or `~1.2 | ^2.0` for Composer.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The requirement.
+    /// * `syntax` - How to read it.
+    ///
+    /// # Returns
+    ///
+    /// Returns the requirement or an error if it can't be parsed.
+    pub fn parse_with(text: &str, syntax: RequirementSyntax) -> Result<Self, SearchError> {
+        let alternatives: Vec<&str> = match syntax {
+            // Composer also separates alternatives with a single `|`.
+            RequirementSyntax::Composer => text.split('|').filter(|alt| !alt.is_empty()).collect(),
+            _ => text.split("||").collect(),
+        };
+        let alternatives = alternatives
+            .into_iter()
+            .map(|alternative| {
+                let parts: Vec<&str> = alternative
+                    .split([',', ' '])
+                    .filter(|comparator| !comparator.is_empty())
+                    .collect();
+                let comparators = match (syntax, &parts[..]) {
+                    (RequirementSyntax::Npm | RequirementSyntax::Composer, [low, "-", high]) => {
+                        vec![format!(">={}", low), format!("<={}", high)]
+                    }
+                    _ => join_operators(&parts),
+                };
+                let comparators: Vec<String> = comparators
+                    .iter()
+                    .flat_map(|comparator| syntax.comparator(comparator))
+                    .collect();
+                VersionReq::parse(&comparators.join(", ")).map_err(|error| {
+                    SearchError::InvalidQuery(format!(
+                        "invalid version requirement '{}': {}",
+                        text, error
+                    ))
+                })
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self { alternatives })
+    }
+
+    /// Returns whether `version` meets the requirement.
+    pub fn matches(&self, version: &Version) -> bool {
+        self.alternatives
+            .iter()
+            .any(|requirement| requirement.matches(version))
+    }
+}
+
+/// Joins comparators split at a space between an operator and its version,
+/// as in `>= 1.2`, back together.
+fn join_operators(parts: &[&str]) -> Vec<String> {
+    let mut comparators: Vec<String> = Vec::new();
+    for part in parts {
+        match comparators.last_mut() {
+            Some(last) if last.chars().all(|c| OPERATORS.contains(&c)) => last.push_str(part),
+            _ => comparators.push(part.to_string()),
+        }
+    }
+    comparators
+}
+
+/// Parses a published version number leniently: a leading `v` is dropped
+/// and missing minor and patch numbers are read as zero, so `v1.2` is
+/// `1.2.0`.
+///
+/// # Arguments
+///
+/// * `text` - The version number.
+///
+/// # Returns
+///
+/// Returns the version, or `None` if it isn't a version number, e.g. a
+/// Composer branch such as `dev-main`.
+pub fn parse_version(text: &str) -> Option<Version> {
+    let text = text.trim().trim_start_matches(['v', 'V']);
+    if let Ok(version) = Version::parse(text) {
+        return Some(version);
+    }
+    let (core, rest) = match text.find(['-', '+']) {
+        Some(index) => text.split_at(index),
+        None => (text, ""),
+    };
+    let mut numbers = core.split('.');
+    let major = numbers.next()?;
+    let minor = numbers.next().unwrap_or("0");
+    let patch = numbers.next().unwrap_or("0");
+    if numbers.next().is_some() {
+        return None;
+    }
+    Version::parse(&format!("{}.{}.{}{}", major, minor, patch, rest)).ok()
+}
+
+/// Returns the newest of `versions` that meets `requirement`.
+///
+/// Versions that can't be parsed are skipped, and pre-releases only match
+/// requirements that name a pre-release of the same version.
+///
+/// # Arguments
+///
+/// * `versions` - The published version numbers, in any order.
+/// * `requirement` - The requirement to meet.
+///
+/// # Returns
+///
+/// Returns the matching version number as given, or `None`.
+pub fn latest_matching<'a>(
+    versions: impl IntoIterator<Item = &'a str>,
+    requirement: &VersionRequirement,
+) -> Option<&'a str> {
+    versions
+        .into_iter()
+        .filter_map(|text| Some((parse_version(text)?, text)))
+        .filter(|(version, _)| requirement.matches(version))
+        .max_by(|(a, _), (b, _)| a.cmp(b))
+        .map(|(_, text)| text)
+}
+
+/// A version resolved from a requirement.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ResolvedVersion {
+    /// The version number as the registry publishes it.
+    pub version: String,
+    /// The registry's metadata of the version: a
+    /// [`CrateVersion`](crate::crates::CrateVersion) for crates.io, the
+    /// version's manifest for npm, or the release for Packagist.
+    pub metadata: Value,
+}
+
+/// Resolves a version requirement to the newest published version of a
+/// package that meets it, like `cargo update` or `npm install` would.
+///
+/// The requirement is read in the registry's own syntax; see
+/// [`RequirementSyntax`]. For npm it may also be a dist-tag, such as
+/// `latest` or `next`. Yanked crate versions are never chosen.
+///
+/// # Arguments
+///
+/// * `source` - The registry: `crates`, `npm`, or `composer`.
+/// * `name` - The name of the package.
+/// * `requirement` - The requirement, e.g. `^1.2`.
+///
+/// # Returns
+///
+/// Returns the resolved version, or [`SearchError::NotFound`] if no
+/// version meets the requirement, or another error.
+pub async fn resolve_version(
+    source: &str,
+    name: &str,
+    requirement: &str,
+) -> Result<ResolvedVersion, SearchError> {
+    let parse = || VersionRequirement::parse_with(requirement, RequirementSyntax::of(source));
+    let (version, metadata) = match source {
+        "crates" => {
+            let requirement = parse()?;
+            let versions = crates::crate_versions(name).await?;
+            let published = versions
+                .iter()
+                .filter(|version| !version.yanked)
+                .map(|version| version.num.as_str());
+            let latest = latest_matching(published, &requirement).ok_or(SearchError::NotFound)?;
+            let metadata = versions.iter().find(|version| version.num == latest);
+            (latest.to_string(), serde_json::to_value(metadata)?)
+        }
+        "npm" => {
+            let package = npm::get_package(name).await?;
+            let versions = package["versions"].as_object().ok_or(SearchError::NotFound)?;
+            if let Some(tagged) = package["dist-tags"][requirement].as_str() {
+                let metadata = versions.get(tagged).ok_or(SearchError::NotFound)?;
+                return Ok(ResolvedVersion {
+                    version: tagged.to_string(),
+                    metadata: metadata.clone(),
+                });
+            }
+            let requirement = parse()?;
+            let latest = latest_matching(versions.keys().map(String::as_str), &requirement)
+                .ok_or(SearchError::NotFound)?;
+            (latest.to_string(), versions[latest].clone())
+        }
+        "composer" => {
+            let requirement = parse()?;
+            let package = composer::get_package(name).await?;
+            let releases = package["packages"][name]
+                .as_array()
+                .ok_or(SearchError::NotFound)?;
+            let published = releases
+                .iter()
+                .filter_map(|release| release["version"].as_str());
+            let latest = latest_matching(published, &requirement).ok_or(SearchError::NotFound)?;
+            let metadata = releases
+                .iter()
+                .find(|release| release["version"].as_str() == Some(latest));
+            (latest.to_string(), metadata.cloned().unwrap_or_default())
+        }
+        source => {
+            return Err(SearchError::InvalidQuery(format!(
+                "version resolution is not supported for '{}'; supported sources are 'crates', 'npm', and 'composer'",
+                source
+            )))
+        }
+    };
+    Ok(ResolvedVersion { version, metadata })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PUBLISHED: [&str; 7] = [
+        "0.9.0",
+        "1.0.0",
+        "1.2.0",
+        "1.2.3",
+        "1.4.0",
+        "2.0.0-beta.1",
+        "2.1.0",
+    ];
+
+    fn latest(requirement: &str, syntax: RequirementSyntax) -> Option<&'static str> {
+        let requirement = VersionRequirement::parse_with(requirement, syntax).unwrap();
+        latest_matching(PUBLISHED, &requirement)
+    }
+
+    #[test]
+    fn reads_cargo_requirements() {
+        let cargo = RequirementSyntax::Cargo;
+        assert_eq!(latest("^1.0", cargo), Some("1.4.0"));
+        assert_eq!(latest("1.2.0", cargo), Some("1.4.0"));
+        assert_eq!(latest("~1.2", cargo), Some("1.2.3"));
+        assert_eq!(latest(">= 1.0, < 1.4", cargo), Some("1.2.3"));
+        assert_eq!(latest(">=1.0 <1.2", cargo), Some("1.0.0"));
+        assert_eq!(latest("^0.9 || ^2", cargo), Some("2.1.0"));
+        assert_eq!(latest("^3", cargo), None);
+    }
+
+    #[test]
+    fn reads_npm_requirements() {
+        let npm = RequirementSyntax::Npm;
+        assert_eq!(latest("1.2.0", npm), Some("1.2.0"));
+        assert_eq!(latest("v1.2.0", npm), Some("1.2.0"));
+        assert_eq!(latest("1.2", npm), Some("1.2.3"));
+        assert_eq!(latest("1.x", npm), Some("1.4.0"));
+        assert_eq!(latest("1.0.0 - 1.2.3", npm), Some("1.2.3"));
+    }
+
+    #[test]
+    fn reads_composer_requirements() {
+        let composer = RequirementSyntax::Composer;
+        assert_eq!(latest("1.2", composer), Some("1.2.0"));
+        assert_eq!(latest("~1.2", composer), Some("1.4.0"));
+        assert_eq!(latest("^1.0@dev", composer), Some("1.4.0"));
+        assert_eq!(latest("~0.9 | ~1.2.0", composer), Some("1.2.3"));
+    }
+
+    #[test]
+    fn matches_pre_releases_only_when_named() {
+        let cargo = RequirementSyntax::Cargo;
+        assert_eq!(latest(">=2.0.0-beta.1, <2.1", cargo), Some("2.0.0-beta.1"));
+        assert_eq!(latest(">=1.4, <2.1", cargo), Some("1.4.0"));
+    }
+
+    #[test]
+    fn skips_versions_that_cannot_be_parsed() {
+        let requirement = VersionRequirement::parse("^1").unwrap();
+        assert_eq!(
+            latest_matching(["dev-main", "1.1", "v1.0.0"], &requirement),
+            Some("1.1")
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_requirements() {
+        assert!(matches!(
+            VersionRequirement::parse("not a version"),
+            Err(SearchError::InvalidQuery(_))
+        ));
+    }
+}
@@ -0,0 +1,168 @@
+use crate::error::SearchError;
+use crate::html::extract_text;
+use crate::registry::{collect_hits, text, Registry, RegistryError, SearchHit, SearchQuery};
+use crate::ApiClient;
+use async_trait::async_trait;
+use serde_json::{json, Value};
+
+/// Searches for packages on the Chocolatey community repository.
+///
+/// The OData feed only speaks Atom, so each `<entry>` is parsed into an
+/// object with `name`, `version`, and `description` fields.
+///
+/// # Arguments
+///
+/// * `query` - The search query (optional).
+///
+/// # Returns
+///
+/// Returns the search results as a `serde_json::Value` array or an error.
+pub async fn search_chocolatey(query: Option<&str>) -> Result<Value, SearchError> {
+    let feed = ApiClient::new("https://community.chocolatey.org/api/v2/", None)
+        .set_param(
+            "searchTerm",
+            &format!("'{}'", query.unwrap_or("").replace('\'', "''")),
+        )
+        .set_param("targetFramework", "''")
+        .set_param("includePrerelease", "false")
+        .set_param("$top", "25")
+        .get_text("Search()")
+        .await?;
+
+    Ok(feed
+        .split("<entry>")
+        .skip(1)
+        .map(|entry| {
+            json!({
+                "name": extract_text(entry, "<title"),
+                "version": extract_text(entry, "<d:Version>"),
+                "description": extract_text(entry, "<summary"),
+            })
+        })
+        .collect())
+}
+
+/// Searches for packages in the winget community repository via winget.run.
+///
+/// # Arguments
+///
+/// * `query` - The search query (optional).
+///
+/// # Returns
+///
+/// Returns the search results as a `serde_json::Value` or an error.
+pub async fn search_winget(query: Option<&str>) -> Result<Value, SearchError> {
+    ApiClient::new("https://api.winget.run/v2/", None)
+        .set_param("query", query.unwrap_or(""))
+        .set_param("take", "25")
+        .get("packages")
+        .await
+}
+
+/// Searches for apps across Scoop buckets.
+///
+/// Queries the search index that backs scoop.sh.
+///
+/// # Arguments
+///
+/// * `query` - The search query (optional).
+///
+/// # Returns
+///
+/// Returns the matching apps as a `serde_json::Value` array or an error.
+pub async fn search_scoop(query: Option<&str>) -> Result<Value, SearchError> {
+    let response = ApiClient::new(
+        "https://scoopsearch.search.windows.net/indexes/apps/docs/",
+        None,
+    )
+    .set_param("api-version", "2020-06-30")
+    .set_header("api-key", "DC6D2BBE65FC7313F2C52BBD2B0286ED")
+    .post(
+        "search",
+        &json!({ "search": query.unwrap_or(""), "top": 25 }),
+    )
+    .await?;
+
+    Ok(response.get("value").cloned().unwrap_or_else(|| json!([])))
+}
+
+/// The Chocolatey community repository.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Chocolatey;
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl Registry for Chocolatey {
+    fn name(&self) -> &str {
+        "choco"
+    }
+
+    async fn search(&self, query: &SearchQuery) -> Result<Vec<SearchHit>, RegistryError> {
+        let results = search_chocolatey(Some(&query.text))
+            .await
+            .map_err(|error| RegistryError::new(self.name(), error))?;
+        Ok(collect_hits(&results, query.limit, |package| SearchHit {
+            name: text(&package["name"]).unwrap_or_default(),
+            version: text(&package["version"]),
+            description: text(&package["description"]),
+            homepage: text(&package["name"])
+                .map(|name| format!("https://community.chocolatey.org/packages/{}", name)),
+            source: self.name().to_string(),
+            ..Default::default()
+        }))
+    }
+}
+
+/// The winget community repository.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Winget;
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl Registry for Winget {
+    fn name(&self) -> &str {
+        "winget"
+    }
+
+    async fn search(&self, query: &SearchQuery) -> Result<Vec<SearchHit>, RegistryError> {
+        let results = search_winget(Some(&query.text))
+            .await
+            .map_err(|error| RegistryError::new(self.name(), error))?;
+        Ok(collect_hits(&results["Packages"], query.limit, |package| {
+            SearchHit {
+                name: text(&package["Id"]).unwrap_or_default(),
+                version: text(&package["Versions"][0]),
+                description: text(&package["Latest"]["Description"]),
+                homepage: text(&package["Latest"]["Homepage"]),
+                source: self.name().to_string(),
+                ..Default::default()
+            }
+        }))
+    }
+}
+
+/// Apps across the known Scoop buckets.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Scoop;
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl Registry for Scoop {
+    fn name(&self) -> &str {
+        "scoop"
+    }
+
+    async fn search(&self, query: &SearchQuery) -> Result<Vec<SearchHit>, RegistryError> {
+        let results = search_scoop(Some(&query.text))
+            .await
+            .map_err(|error| RegistryError::new(self.name(), error))?;
+        Ok(collect_hits(&results, query.limit, |app| SearchHit {
+            name: text(&app["Name"]).unwrap_or_default(),
+            version: text(&app["Version"]),
+            description: text(&app["Description"]),
+            homepage: text(&app["Homepage"]),
+            source: self.name().to_string(),
+            ..Default::default()
+        }))
+    }
+}
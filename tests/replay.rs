@@ -0,0 +1,122 @@
+//! Searches and lookups answered from the responses in `tests/fixtures`, so
+//! they run offline.
+//!
+//! The assertions only rely on what stays true as the registries change —
+//! that results come back, that the expected package is among them, and that
+//! their versions parse and meet the requirement asked for — so fixtures can
+//! be refreshed by running the tests with `SEARCH_LIBRARIES_VCR=record`.
+
+use search_libraries::composer::{search_composer, ComposerSearchOptions};
+use search_libraries::crates::{search_crates, CrateSort};
+use search_libraries::npm::{get_package, search_npm};
+use search_libraries::versions::{
+    parse_version, resolve_version, RequirementSyntax, VersionRequirement,
+};
+use std::sync::Once;
+
+/// Answers every request from the fixtures, unless they are being recorded.
+fn replay() {
+    static INIT: Once = Once::new();
+    INIT.call_once(|| {
+        if std::env::var_os("SEARCH_LIBRARIES_VCR").is_none() {
+            std::env::set_var("SEARCH_LIBRARIES_VCR", "replay");
+        }
+        std::env::set_var(
+            "SEARCH_LIBRARIES_FIXTURES",
+            concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures"),
+        );
+    });
+}
+
+/// Whether `version` parses and meets `requirement` in the given syntax.
+fn meets(version: &str, requirement: &str, syntax: RequirementSyntax) -> bool {
+    let requirement = VersionRequirement::parse_with(requirement, syntax).unwrap();
+    parse_version(version).is_some_and(|version| requirement.matches(&version))
+}
+
+#[tokio::test]
+async fn searches_crates() {
+    replay();
+    let results = search_crates(Some("serde"), CrateSort::default())
+        .await
+        .unwrap();
+    assert!(results.hits().iter().any(|hit| hit.name == "serde"));
+    for hit in results.hits() {
+        assert!(
+            parse_version(&hit.max_version).is_some(),
+            "{}",
+            hit.max_version
+        );
+    }
+}
+
+#[tokio::test]
+async fn resolves_crate_versions_skipping_yanked() {
+    replay();
+    for requirement in ["^1.0.100", "~0.9"] {
+        let resolved = resolve_version("crates", "serde", requirement)
+            .await
+            .unwrap();
+        assert!(meets(
+            &resolved.version,
+            requirement,
+            RequirementSyntax::Cargo
+        ));
+        assert_eq!(resolved.metadata["yanked"], false);
+    }
+}
+
+#[tokio::test]
+async fn searches_npm() {
+    replay();
+    let results = search_npm(Some("react")).await.unwrap();
+    assert!(results.hits().iter().any(|hit| hit.package.name == "react"));
+    for hit in results.hits() {
+        assert!(parse_version(&hit.package.version).is_some());
+    }
+}
+
+#[tokio::test]
+async fn resolves_npm_ranges_and_dist_tags() {
+    replay();
+    let resolve = |requirement| resolve_version("npm", "left-pad", requirement);
+    for requirement in ["1.1.1", "^1.1.0", "1.0.0 - 1.2"] {
+        let resolved = resolve(requirement).await.unwrap();
+        assert!(meets(
+            &resolved.version,
+            requirement,
+            RequirementSyntax::Npm
+        ));
+    }
+    let package = get_package("left-pad").await.unwrap();
+    let latest = resolve("latest").await.unwrap();
+    assert_eq!(package["dist-tags"]["latest"], latest.version.as_str());
+}
+
+#[tokio::test]
+async fn searches_composer() {
+    replay();
+    let results = search_composer(Some("monolog"), &ComposerSearchOptions::new())
+        .await
+        .unwrap();
+    assert!(results
+        .hits()
+        .iter()
+        .any(|hit| hit.name == "monolog/monolog"));
+}
+
+#[tokio::test]
+async fn resolves_composer_tilde_ranges() {
+    replay();
+    let resolved = resolve_version("composer", "monolog/monolog", "~2.9")
+        .await
+        .unwrap();
+    assert!(meets(
+        &resolved.version,
+        "~2.9",
+        RequirementSyntax::Composer
+    ));
+    // Packagist minifies releases; the resolved one has every field filled in.
+    assert_eq!(resolved.metadata["name"], "monolog/monolog");
+    assert!(resolved.metadata["require"].is_object());
+}